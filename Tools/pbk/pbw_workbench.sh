@@ -34,6 +34,47 @@ pbw_show() {
   test "${BUD_VERBOSE:-0}" != "1" || echo "PBWSHOW: $*"
 }
 
+# Run one action key against an xterm under Xvfb + openbox
+# Args: key, screen size, RandR monitors as "name geometry ..." pairs (may be empty), cargo args
+pbw_xvfb_smoke() {
+  local z_key="$1"
+  local z_size="$2"
+  local z_monitors="$3"
+  shift 3
+  local z_display=":97"
+  command -v Xvfb    >/dev/null || buc_die "Xvfb not installed"
+  command -v openbox >/dev/null || buc_die "openbox not installed"
+  command -v xterm   >/dev/null || buc_die "xterm not installed"
+  test -z "${z_monitors}" || command -v xrandr >/dev/null || buc_die "xrandr not installed"
+  echo "Building PaneBoard PoC and tiling an xterm under Xvfb ${z_size} (key=${z_key})..."
+  cd poc
+  cargo build "$@"
+  Xvfb "${z_display}" -screen 0 "${z_size}x24" >"${BUD_TEMP_DIR}/xvfb.log" 2>&1 &
+  local z_xvfb_pid=$!
+  sleep 1
+  local z_name z_geometry
+  set -- ${z_monitors} -- "$@"
+  while test "$1" != "--"; do
+    z_name="$1"
+    z_geometry="$2"
+    shift 2
+    DISPLAY="${z_display}" xrandr --setmonitor "${z_name}" "${z_geometry}" none \
+      || buc_die "xrandr --setmonitor ${z_name} failed"
+  done
+  shift
+  DISPLAY="${z_display}" openbox >"${BUD_TEMP_DIR}/openbox.log" 2>&1 &
+  local z_wm_pid=$!
+  sleep 1
+  DISPLAY="${z_display}" xterm -geometry 80x24+40+40 &
+  local z_xterm_pid=$!
+  sleep 1
+  local z_status=0
+  DISPLAY="${z_display}" cargo run "$@" -- action "${z_key}" "${z_key}" || z_status=$?
+  DISPLAY="${z_display}" xwininfo -root -tree | grep -i xterm || true
+  kill "${z_xterm_pid}" "${z_wm_pid}" "${z_xvfb_pid}" 2>/dev/null || true
+  test "${z_status}" = 0 || buc_die "action ${z_key} failed (exit ${z_status})"
+}
+
 # Simple routing function
 pbw_route() {
   local z_command="$1"
@@ -66,6 +107,187 @@ pbw_route() {
       cargo build "$@" && cargo run "$@" -- --timeout "${z_timeout}"
       ;;
 
+    # Linux X11 smoke test - tile an xterm under Xvfb + openbox (BUD_TOKEN_3 = key)
+    pbw-x)
+      pbw_xvfb_smoke "${BUD_TOKEN_3:-home}" "1920x1080" "" "$@"
+      ;;
+
+    # Linux X11 smoke test on two RandR monitors side by side (BUD_TOKEN_3 = key)
+    pbw-x2)
+      pbw_xvfb_smoke "${BUD_TOKEN_3:-home}" "3840x1080" \
+        "left 1920/508x1080/286+0+0 right 1920/508x1080/286+1920+0" "$@"
+      ;;
+
+    # Linux XGrabKey capture test - xdotool chords under Xvfb + openbox
+    # (BUD_TOKEN_3 = XML key, BUD_TOKEN_4 = its X keysym)
+    pbw-xg)
+      local z_key="${BUD_TOKEN_3:-home}"
+      local z_keysym="${BUD_TOKEN_4:-Home}"
+      local z_display=":97"
+      local z_log="${BUD_TEMP_DIR}/capture.log"
+      command -v Xvfb    >/dev/null || buc_die "Xvfb not installed"
+      command -v openbox >/dev/null || buc_die "openbox not installed"
+      command -v xterm   >/dev/null || buc_die "xterm not installed"
+      command -v xdotool >/dev/null || buc_die "xdotool not installed"
+      echo "Building PaneBoard PoC and sending ctrl+alt+shift+${z_keysym} to XGrabKey capture..."
+      cd poc
+      cargo build "$@"
+      Xvfb "${z_display}" -screen 0 1920x1080x24 >"${BUD_TEMP_DIR}/xvfb.log" 2>&1 &
+      local z_xvfb_pid=$!
+      sleep 1
+      DISPLAY="${z_display}" openbox >"${BUD_TEMP_DIR}/openbox.log" 2>&1 &
+      local z_wm_pid=$!
+      sleep 1
+      DISPLAY="${z_display}" xterm -geometry 80x24+40+40 &
+      local z_xterm_pid=$!
+      sleep 1
+      DISPLAY="${z_display}" target/debug/paneboard-poc capture --backend x11 --timeout 4 >"${z_log}" 2>&1 &
+      local z_capture_pid=$!
+      sleep 1
+      DISPLAY="${z_display}" xdotool key --delay 200 "ctrl+shift+alt+${z_keysym}" "ctrl+shift+alt+${z_keysym}"
+      local z_status=0
+      wait "${z_capture_pid}" || z_status=$?
+      kill "${z_xterm_pid}" "${z_wm_pid}" "${z_xvfb_pid}" 2>/dev/null || true
+      cat "${z_log}"
+      test "${z_status}" = 0 || buc_die "capture failed (exit ${z_status})"
+      test "$(grep -c "^BLOCKED: ctrl+shift+alt+${z_key}$" "${z_log}")" = 2 \
+        || buc_die "expected 2 BLOCKED lines for ${z_key}"
+      ;;
+
+    # Linux clipboard test - xclip copies under Xvfb, then paste historic entry 1
+    pbw-xc)
+      local z_display=":96"
+      local z_log="${BUD_TEMP_DIR}/clipboard.log"
+      command -v Xvfb  >/dev/null || buc_die "Xvfb not installed"
+      command -v xclip >/dev/null || buc_die "xclip not installed"
+      echo "Building PaneBoard PoC and copying two texts with xclip..."
+      cd poc
+      cargo build "$@"
+      Xvfb "${z_display}" -screen 0 1280x800x24 >"${BUD_TEMP_DIR}/xvfb.log" 2>&1 &
+      local z_xvfb_pid=$!
+      sleep 1
+      DISPLAY="${z_display}" target/debug/paneboard-poc clipboard --paste 1 --timeout 4 >"${z_log}" 2>&1 &
+      local z_clip_pid=$!
+      sleep 1
+      printf 'one' | DISPLAY="${z_display}" xclip -selection clipboard
+      sleep 1
+      printf 'two' | DISPLAY="${z_display}" xclip -selection clipboard
+      sleep 1
+      local z_pasted
+      z_pasted="$(DISPLAY="${z_display}" xclip -o -selection clipboard)"
+      local z_status=0
+      wait "${z_clip_pid}" || z_status=$?
+      kill "${z_xvfb_pid}" 2>/dev/null || true
+      cat "${z_log}"
+      test "${z_status}" = 0 || buc_die "clipboard failed (exit ${z_status})"
+      test "${z_pasted}" = "one" || buc_die "expected historic 'one' on CLIPBOARD, got '${z_pasted}'"
+      ;;
+
+    # Linux sway IPC test - tile a foot terminal under headless sway (BUD_TOKEN_3 = key)
+    pbw-sw)
+      local z_key="${BUD_TOKEN_3:-home}"
+      local z_runtime="${BUD_TEMP_DIR}/xdg-runtime"
+      command -v sway >/dev/null || buc_die "sway not installed"
+      command -v foot >/dev/null || buc_die "foot not installed"
+      echo "Building PaneBoard PoC and tiling a foot terminal under headless sway..."
+      cd poc
+      cargo build "$@"
+      mkdir -p "${z_runtime}" && chmod 700 "${z_runtime}"
+      XDG_RUNTIME_DIR="${z_runtime}" WLR_BACKENDS=headless WLR_LIBINPUT_NO_DEVICES=1 \
+        sway -c /dev/null >"${BUD_TEMP_DIR}/sway.log" 2>&1 &
+      local z_sway_pid=$!
+      sleep 2
+      local z_socket
+      z_socket="$(ls "${z_runtime}"/sway-ipc.*.sock | head -1)" || buc_die "sway did not create an IPC socket"
+      SWAYSOCK="${z_socket}" swaymsg exec foot >/dev/null
+      sleep 1
+      local z_status=0
+      env -u DISPLAY SWAYSOCK="${z_socket}" target/debug/paneboard-poc action "${z_key}" "${z_key}" || z_status=$?
+      SWAYSOCK="${z_socket}" swaymsg -t get_tree | grep -E '"(app_id|type)": "(foot|floating_con)"' || true
+      kill "${z_sway_pid}" 2>/dev/null || true
+      test "${z_status}" = 0 || buc_die "action ${z_key} failed (exit ${z_status})"
+      ;;
+
+    # Linux Wayland clipboard test - wl-copy under headless sway, then paste historic entry 1
+    pbw-wc)
+      local z_runtime="${BUD_TEMP_DIR}/xdg-runtime"
+      local z_log="${BUD_TEMP_DIR}/clipboard.log"
+      command -v sway    >/dev/null || buc_die "sway not installed"
+      command -v wl-copy >/dev/null || buc_die "wl-clipboard not installed"
+      echo "Building PaneBoard PoC and copying two texts with wl-copy..."
+      cd poc
+      cargo build "$@"
+      mkdir -p "${z_runtime}" && chmod 700 "${z_runtime}"
+      XDG_RUNTIME_DIR="${z_runtime}" WLR_BACKENDS=headless WLR_LIBINPUT_NO_DEVICES=1 \
+        sway -c /dev/null >"${BUD_TEMP_DIR}/sway.log" 2>&1 &
+      local z_sway_pid=$!
+      sleep 2
+      local z_socket
+      z_socket="$(ls "${z_runtime}" | grep -m1 '^wayland-[0-9]*$')" || buc_die "sway did not create a Wayland socket"
+      local z_env="XDG_RUNTIME_DIR=${z_runtime} WAYLAND_DISPLAY=${z_socket}"
+      env -u DISPLAY ${z_env} target/debug/paneboard-poc clipboard --paste 1 --timeout 4 >"${z_log}" 2>&1 &
+      local z_clip_pid=$!
+      sleep 1
+      env -u DISPLAY ${z_env} wl-copy 'one'
+      sleep 1
+      env -u DISPLAY ${z_env} wl-copy 'two'
+      sleep 1
+      local z_pasted
+      z_pasted="$(env -u DISPLAY ${z_env} wl-paste --no-newline)"
+      local z_status=0
+      wait "${z_clip_pid}" || z_status=$?
+      kill "${z_sway_pid}" 2>/dev/null || true
+      cat "${z_log}"
+      test "${z_status}" = 0 || buc_die "clipboard failed (exit ${z_status})"
+      grep -q "Wayland" "${z_log}" || buc_die "expected the Wayland data-control provider"
+      test "${z_pasted}" = "one" || buc_die "expected historic 'one' on the selection, got '${z_pasted}'"
+      ;;
+
+    # Linux overlay test - render a panel to PNG, then show it under Xvfb
+    pbw-xo)
+      local z_display=":95"
+      local z_log="${BUD_TEMP_DIR}/overlay.log"
+      local z_png="${BUD_TEMP_DIR}/overlay.png"
+      command -v Xvfb >/dev/null || buc_die "Xvfb not installed"
+      echo "Building PaneBoard PoC and drawing the software overlay..."
+      cd poc
+      cargo build "$@"
+      local z_bin="target/debug/paneboard-poc"
+      "${z_bin}" overlay --png "${z_png}" "org.mozilla.firefox=Mozilla Firefox" "foot=~/src" \
+        || buc_die "overlay render failed"
+      echo "Overlay panel written to ${z_png}"
+      Xvfb "${z_display}" -screen 0 1280x800x24 >"${BUD_TEMP_DIR}/xvfb.log" 2>&1 &
+      local z_xvfb_pid=$!
+      sleep 1
+      local z_status=0
+      env -u WAYLAND_DISPLAY DISPLAY="${z_display}" "${z_bin}" overlay --timeout 2 \
+        "org.mozilla.firefox=Mozilla Firefox" "foot=~/src" >"${z_log}" 2>&1 || z_status=$?
+      kill "${z_xvfb_pid}" 2>/dev/null || true
+      cat "${z_log}"
+      test "${z_status}" = 0 || buc_die "overlay failed (exit ${z_status})"
+      grep -q "OVERLAY: X11 panel" "${z_log}" || buc_die "expected an X11 overlay panel"
+      ! grep -q "OVERLAY: FAILED" "${z_log}" || buc_die "overlay reported a failure"
+      ;;
+
+    # Linux evdev capture test - inject chords on a uinput keyboard (BUD_TOKEN_3 = key)
+    pbw-e)
+      local z_key="${BUD_TOKEN_3:-home}"
+      local z_log="${BUD_TEMP_DIR}/capture.log"
+      test -w /dev/uinput || buc_die "/dev/uinput not writable (input group + udev rule, or root)"
+      echo "Building PaneBoard PoC and injecting ctrl+shift+alt+${z_key} through evdev capture..."
+      cd poc
+      cargo build "$@"
+      local z_bin="target/debug/paneboard-poc"
+      env -u DISPLAY "${z_bin}" capture --timeout 6 >"${z_log}" 2>&1 &
+      local z_capture_pid=$!
+      sleep 1
+      "${z_bin}" inject "${z_key}" "${z_key}" || buc_die "inject failed"
+      wait "${z_capture_pid}" || buc_die "capture failed, see ${z_log}"
+      cat "${z_log}"
+      test "$(grep -c "^BLOCKED: ctrl+shift+alt+${z_key}$" "${z_log}")" = 2 \
+        || buc_die "expected 2 BLOCKED lines for ${z_key}"
+      ;;
+
     *)
       buc_die "Unknown command: ${z_command}"
       ;;
@@ -27,7 +27,7 @@ fn compile_swift_shim() {
 
     // Get SDK path dynamically
     let sdk_output = Command::new("xcrun")
-        .args(&["--show-sdk-path"])
+        .args(["--show-sdk-path"])
         .output()
         .expect("Failed to find SDK path");
 
@@ -71,7 +71,7 @@ fn compile_swift_shim() {
     // Link Swift runtime libraries
     // Get Swift library path
     let swift_lib_output = Command::new("xcrun")
-        .args(&["--show-sdk-path"])
+        .args(["--show-sdk-path"])
         .output()
         .expect("Failed to find SDK path");
 
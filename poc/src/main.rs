@@ -8,7 +8,20 @@ mod pbgk_keylog;
 mod pbgft_types;
 mod pbgfp_parse;
 mod pbgfr_resolve;
+mod pbgfl_lint;
 mod pbgfc_config;
+mod pbgg_geometry;
+#[cfg(any(target_os = "macos", test))]
+mod pbgt_tiling;
+mod pbgw_window;
+#[cfg(test)]
+mod pbgwm_mock;
+mod pbgam_appmin;
+mod pbgcl_clipboard;
+#[cfg(any(target_os = "macos", test))]
+mod pbgi_input;
+#[cfg(test)]
+mod pbgim_mock;
 
 // macOS base/shared modules
 #[cfg(target_os = "macos")] mod pbmba_ax;
@@ -16,11 +29,13 @@ mod pbgfc_config;
 #[cfg(target_os = "macos")] mod pbmbe_eventtap;
 #[cfg(target_os = "macos")] mod pbmbo_overlay;
 #[cfg(target_os = "macos")] mod pbmbk_keymap;
+#[cfg(target_os = "macos")] mod pbmbw_window;
 
 // macOS switcher modules
 #[cfg(target_os = "macos")] mod pbmsb_browser;
 #[cfg(target_os = "macos")] mod pbmsm_mru;
 #[cfg(target_os = "macos")] mod pbmsa_alttab;
+#[cfg(target_os = "macos")] mod pbmsf_focus;
 #[cfg(target_os = "macos")] mod pbmbo_observer;
 
 // macOS clipboard modules
@@ -28,13 +43,68 @@ mod pbgfc_config;
 
 // macOS pane modules
 #[cfg(target_os = "macos")] mod pbmp_pane;
+#[cfg(target_os = "macos")] mod pbmps_swap;
+#[cfg(target_os = "macos")] mod pbmpn_nudge;
+#[cfg(target_os = "macos")] mod pbmpt_autotile;
+#[cfg(target_os = "macos")] mod pbmpm_macro;
 
 // macOS sandbox module
 #[cfg(target_os = "macos")] mod pbmbs_sandbox;
 
-#[cfg(not(target_os = "macos"))]
+// Linux (X11) modules
+#[cfg(target_os = "linux")] mod pblbx_x11;
+#[cfg(target_os = "linux")] mod pblbd_display;
+#[cfg(target_os = "linux")] mod pblp_pane;
+#[cfg(target_os = "linux")] mod pblbk_keymap;
+#[cfg(target_os = "linux")] mod pblbe_evdev;
+#[cfg(target_os = "linux")] mod pblbg_grabkey;
+#[cfg(target_os = "linux")] mod pblbc_clipboard;
+#[cfg(target_os = "linux")] mod pblbw_clipboard;
+#[cfg(target_os = "linux")] mod pblbi_i3ipc;
+#[cfg(target_os = "linux")] mod pblbo_overlay;
+#[cfg(target_os = "linux")] mod pblbox_surface;
+#[cfg(target_os = "linux")] mod pblbow_surface;
+
+#[cfg(target_os = "linux")]
+fn main() {
+    let mut args = std::env::args().skip(1);
+    match args.next().as_deref() {
+        // `lint [path]` works on any platform (pure Form parsing)
+        Some("lint") => std::process::exit(pbgfl_lint::run_lint_cli(args.next().as_deref())),
+        // `action <key>...`: run Form bindings on the active window (bind in the WM)
+        Some("action") => std::process::exit(pblp_pane::run_action_cli(&args.collect::<Vec<_>>())),
+        // `capture`: resident ctrl+shift+alt chord capture (evdev/uinput or XGrabKey)
+        Some("capture") => std::process::exit(pblp_pane::run_capture_cli(&args.collect::<Vec<_>>())),
+        // `clipboard`: clipboard history monitor (X11 or Wayland data-control) (--paste INDEX serves a historic entry)
+        Some("clipboard") => std::process::exit(pblbc_clipboard::run_clipboard_cli(&args.collect::<Vec<_>>())),
+        // `overlay [ENTRY...]`: show the software Alt-Tab/clipboard overlay (--png renders to a file)
+        Some("overlay") => std::process::exit(pblbo_overlay::run_overlay_cli(&args.collect::<Vec<_>>())),
+        // `inject <key>...`: type chords on a uinput test keyboard (exercises `capture`)
+        Some("inject") => std::process::exit(pblbe_evdev::run_inject_cli(&args.collect::<Vec<_>>())),
+        _ => {
+            eprintln!("Usage: paneboard-poc action <key>...   run Form bindings on the active window");
+            eprintln!("       paneboard-poc capture [--backend evdev|x11] [--timeout SECONDS]");
+            eprintln!("                                        capture ctrl+shift+alt chords");
+            eprintln!("       paneboard-poc clipboard [--primary] [--paste INDEX] [--timeout SECONDS]");
+            eprintln!("                                        monitor clipboard history");
+            eprintln!("       paneboard-poc inject <key>...   type chords on a uinput test keyboard");
+            eprintln!("       paneboard-poc overlay [--clipboard] [--png PATH] [--timeout SECONDS] [APP_ID=TITLE...]");
+            eprintln!("                                        show the Alt-Tab or clipboard overlay");
+            eprintln!("       paneboard-poc lint [path]        check form.xml");
+            std::process::exit(2);
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
 fn main() {
-    eprintln!("This PoC currently only supports macOS");
+    // `lint [path]` works on any platform (pure Form parsing)
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() == Some("lint") {
+        std::process::exit(pbgfl_lint::run_lint_cli(args.next().as_deref()));
+    }
+
+    eprintln!("This PoC currently only supports macOS and Linux (X11)");
     std::process::exit(1);
 }
 
@@ -44,5 +114,11 @@ fn main() {
     // This permanently blocks all network access for this process
     pbmbs_sandbox::drop_network_access();
 
+    // `lint [path]`: check form.xml and exit without touching the event tap
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() == Some("lint") {
+        std::process::exit(pbgfl_lint::run_lint_cli(args.next().as_deref()));
+    }
+
     unsafe { pbmbe_eventtap::run_quadrant_poc(); }
 }
\ No newline at end of file
@@ -0,0 +1,267 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+//! Learned Per-App Minimum Window Sizes
+//! Remembers the smallest size each app has actually accepted, so pane cycles
+//! can skip panes an app can never fit (Chromium browsers, chat clients, ...)
+//!
+//! This module contains:
+//! - The learned size table keyed by app identity (bundle ID / exe / process name)
+//! - Learning from requested vs achieved sizes after a window set
+//! - Persistence as a tab-separated file (~/.config/paneboard/app-min-sizes.tsv)
+//!
+//! Learning runs on the tiling path (inside the macOS event tap), so it only
+//! marks the table dirty; a writer thread saves it SAVE_DELAY later, and
+//! front ends call flush_app_min_sizes before exiting.
+//!
+//! Minimums only grow when an app refuses to shrink, and drop back when a later
+//! set succeeds below them (e.g. after an app update relaxes its limits).
+//! Landing a few pixels above the request is grid snapping (terminals and
+//! editors round to their cell size), not a minimum.
+
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+
+/// Sizes within this many pixels of the request count as honored
+const SIZE_TOLERANCE: f64 = 1.0;
+
+/// Overshoot (fraction of the request) still treated as snapping rather than refusal
+const SNAP_MARGIN: f64 = 0.05;
+
+/// Floor for any pane, app-specific or not (applied when the pane cycle is built)
+pub const MIN_PANE_SIZE: f64 = 100.0;
+
+/// Delay between the first unsaved change and the write (batches bursts of learning)
+const SAVE_DELAY: Duration = Duration::from_secs(2);
+
+lazy_static! {
+    static ref APP_MIN_SIZES: Mutex<AppMinSizes> = Mutex::new(AppMinSizes::load());
+    /// Serializes writes so a later snapshot never lands before an earlier one
+    static ref SAVE_LOCK: Mutex<()> = Mutex::new(());
+}
+
+// ============================================================================
+// SECTION 1: Learned size table
+// ============================================================================
+
+struct AppMinSizes {
+    sizes: HashMap<String, (f64, f64)>,  // app_id → (min_width, min_height) in points
+    dirty: bool,                         // Changed since the last save
+    save_scheduled: bool,                // Writer thread pending
+}
+
+impl AppMinSizes {
+    /// Load from disk; a missing or unreadable file starts empty
+    fn load() -> Self {
+        let mut sizes = HashMap::new();
+        // Unit tests learn into an empty table and never touch the user's file
+        if cfg!(test) {
+            return AppMinSizes { sizes, dirty: false, save_scheduled: false };
+        }
+
+        let path = crate::pbgfc_config::app_min_sizes_path();
+
+        if let Ok(content) = fs::read_to_string(&path) {
+            for (line_no, line) in content.lines().enumerate() {
+                if line.trim().is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                match Self::parse_line(line) {
+                    Some((app_id, w, h)) => {
+                        sizes.insert(app_id, (w, h));
+                    }
+                    None => eprintln!("APPMIN: ignoring malformed line {} in {}", line_no + 1, path.display()),
+                }
+            }
+            eprintln!("APPMIN: loaded {} learned minimum sizes", sizes.len());
+        }
+
+        AppMinSizes { sizes, dirty: false, save_scheduled: false }
+    }
+
+    fn parse_line(line: &str) -> Option<(String, f64, f64)> {
+        let mut fields = line.split('\t');
+        let app_id = fields.next()?.to_string();
+        let w = fields.next()?.parse::<f64>().ok()?;
+        let h = fields.next()?.parse::<f64>().ok()?;
+        if app_id.is_empty() {
+            return None;
+        }
+        Some((app_id, w, h))
+    }
+
+    /// File contents for the table (sorted for stable diffs)
+    fn serialize(&self) -> String {
+        let mut ids: Vec<&String> = self.sizes.keys().collect();
+        ids.sort();
+
+        let mut content = String::from("# PaneBoard learned minimum window sizes (app_id, width, height)\n");
+        for id in ids {
+            let (w, h) = self.sizes[id];
+            content.push_str(&format!("{}\t{:.0}\t{:.0}\n", id, w, h));
+        }
+        content
+    }
+
+    /// Write serialized contents via a temp file + rename
+    fn save(content: String) -> Result<(), String> {
+        if cfg!(test) {
+            return Ok(());
+        }
+        let path = crate::pbgfc_config::app_min_sizes_path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("create_dir failed: {}", e))?;
+        }
+
+        let tmp = path.with_extension("tsv.tmp");
+        fs::write(&tmp, content).map_err(|e| format!("write failed: {}", e))?;
+        fs::rename(&tmp, &path).map_err(|e| format!("rename failed: {}", e))
+    }
+
+    /// One dimension of a set: did the app refuse to shrink to the request?
+    /// Refused if it stayed at (or above) its size before the set, or overshot
+    /// the request by more than SNAP_MARGIN; a smaller overshoot is snapping
+    fn refused(before: f64, requested: f64, achieved: f64) -> bool {
+        if achieved <= requested + SIZE_TOLERANCE {
+            return false;
+        }
+        let kept_size = requested < before && achieved >= before - SIZE_TOLERANCE;
+        kept_size || achieved > requested * (1.0 + SNAP_MARGIN)
+    }
+
+    /// Update from one set; returns true if the table changed
+    /// `before` is the window size before the set, (0, 0) if unknown
+    fn learn(&mut self, app_id: &str, before: (f64, f64), requested: (f64, f64), achieved: (f64, f64)) -> bool {
+        let known = self.sizes.get(app_id).copied().unwrap_or((0.0, 0.0));
+        let mut min = known;
+
+        // Refused to shrink: achieved is a lower bound on that dimension
+        if Self::refused(before.0, requested.0, achieved.0) {
+            min.0 = min.0.max(achieved.0);
+        } else if achieved.0 < min.0 - SIZE_TOLERANCE {
+            min.0 = achieved.0; // Honored below the learned minimum: relax
+        }
+        if Self::refused(before.1, requested.1, achieved.1) {
+            min.1 = min.1.max(achieved.1);
+        } else if achieved.1 < min.1 - SIZE_TOLERANCE {
+            min.1 = achieved.1;
+        }
+
+        if (min.0 - known.0).abs() < SIZE_TOLERANCE && (min.1 - known.1).abs() < SIZE_TOLERANCE {
+            return false;
+        }
+
+        if min.0 <= MIN_PANE_SIZE && min.1 <= MIN_PANE_SIZE {
+            self.sizes.remove(app_id); // Nothing beyond the global floor
+        } else {
+            self.sizes.insert(app_id.to_string(), min);
+        }
+        true
+    }
+}
+
+// ============================================================================
+// SECTION 2: Public API
+// ============================================================================
+
+/// Learned minimum (width, height) for an app, None if nothing beyond the global floor is known
+pub fn app_min_size(app_id: &str) -> Option<(f64, f64)> {
+    APP_MIN_SIZES.lock().unwrap().sizes.get(app_id).copied()
+}
+
+/// Effective minimum pane size for an app: learned minimum, never below MIN_PANE_SIZE
+pub fn effective_min_size(app_id: &str) -> (f64, f64) {
+    let (w, h) = app_min_size(app_id).unwrap_or((0.0, 0.0));
+    (w.max(MIN_PANE_SIZE), h.max(MIN_PANE_SIZE))
+}
+
+/// Record the outcome of a window set and schedule a save if anything was learned
+/// `before` is the window size before the set, (0, 0) if unknown
+/// `tag` is appended to the log line (e.g. " [chromium]")
+pub fn learn_from_set(app_id: &str, before: (f64, f64), requested: (f64, f64), achieved: (f64, f64), tag: &str) {
+    let mut table = APP_MIN_SIZES.lock().unwrap();
+    if !table.learn(app_id, before, requested, achieved) {
+        return;
+    }
+
+    match table.sizes.get(app_id) {
+        Some((w, h)) => eprintln!("APPMIN: app=\"{}\"{} learned min={:.0}x{:.0} (requested {:.0}x{:.0}, got {:.0}x{:.0})",
+            app_id, tag, w, h, requested.0, requested.1, achieved.0, achieved.1),
+        None => eprintln!("APPMIN: app=\"{}\"{} minimum cleared", app_id, tag),
+    }
+
+    table.dirty = true;
+    if !table.save_scheduled {
+        table.save_scheduled = true;
+        std::thread::spawn(|| {
+            std::thread::sleep(SAVE_DELAY);
+            flush_app_min_sizes();
+        });
+    }
+}
+
+/// Write learned sizes now if any are unsaved (call before exiting)
+pub fn flush_app_min_sizes() {
+    let _write = SAVE_LOCK.lock().unwrap();
+    let content = {
+        let mut table = APP_MIN_SIZES.lock().unwrap();
+        table.save_scheduled = false;
+        if !table.dirty {
+            return;
+        }
+        table.dirty = false;
+        table.serialize()
+    };
+
+    if let Err(e) = AppMinSizes::save(content) {
+        eprintln!("APPMIN: ERROR failed to save learned sizes: {}", e);
+        APP_MIN_SIZES.lock().unwrap().dirty = true;   // Retry on the next flush
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> AppMinSizes {
+        AppMinSizes { sizes: HashMap::new(), dirty: false, save_scheduled: false }
+    }
+
+    #[test]
+    fn learn_records_refusal_to_shrink() {
+        let mut t = table();
+        // Stayed at its previous width: that width is the minimum
+        assert!(t.learn("chat", (900.0, 700.0), (600.0, 700.0), (900.0, 700.0)));
+        assert_eq!(t.sizes["chat"], (900.0, 0.0));
+
+        // Shrank part way, far above the request: stopped at its minimum
+        let mut t = table();
+        assert!(t.learn("browser", (1200.0, 800.0), (400.0, 800.0), (500.0, 800.0)));
+        assert_eq!(t.sizes["browser"], (500.0, 0.0));
+    }
+
+    #[test]
+    fn learn_ignores_grid_snapping() {
+        let mut t = table();
+        // Terminal rounds 756x491 to its cell grid: a few pixels over is not a minimum
+        assert!(!t.learn("term", (1512.0, 982.0), (756.0, 491.0), (760.0, 496.0)));
+        assert!(t.sizes.is_empty());
+    }
+
+    #[test]
+    fn learn_relaxes_when_a_smaller_size_is_honored() {
+        let mut t = table();
+        t.sizes.insert("editor".to_string(), (900.0, 600.0));
+        // Snapped slightly above the request, still below the learned width
+        assert!(t.learn("editor", (1000.0, 700.0), (700.0, 700.0), (704.0, 700.0)));
+        assert_eq!(t.sizes["editor"], (704.0, 600.0));
+
+        // Honored below both: nothing beyond the global floor remains
+        assert!(t.learn("editor", (704.0, 700.0), (100.0, 100.0), (100.0, 100.0)));
+        assert!(!t.sizes.contains_key("editor"));
+    }
+}
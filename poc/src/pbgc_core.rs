@@ -3,7 +3,7 @@
 
 use std::collections::HashSet;
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Default, PartialEq)]
 pub struct KeyState {
     pub left_shift: bool,
     pub right_shift: bool,
@@ -18,25 +18,46 @@ pub struct KeyState {
     pub pressed_set: HashSet<u32>,
 }
 
-impl Default for KeyState {
-    fn default() -> Self {
-        Self {
-            left_shift: false,
-            right_shift: false,
-            left_control: false,
-            right_control: false,
-            left_alt: false,
-            right_alt: false,
-            left_cmd: false,
-            right_cmd: false,
-            caps_lock: false,
-            pressed_order: Vec::new(),
-            pressed_set: HashSet::new(),
-        }
+impl KeyState {
+    /// Apply a HID usage press/release (modifiers 0xE0-0xE7, others tracked in order)
+    /// Returns true if state changed
+    pub fn apply(&mut self, usage: u32, is_pressed: bool) -> bool {
+        let flag = match usage {
+            0xE0 => &mut self.left_control,
+            0xE1 => &mut self.left_shift,
+            0xE2 => &mut self.left_alt,
+            0xE3 => &mut self.left_cmd,
+            0xE4 => &mut self.right_control,
+            0xE5 => &mut self.right_shift,
+            0xE6 => &mut self.right_alt,
+            0xE7 => &mut self.right_cmd,
+            _ => {
+                // Regular keys (non-modifiers)
+                if is_pressed {
+                    if self.pressed_set.insert(usage) {
+                        self.pressed_order.push(usage);
+                        return true;
+                    }
+                } else if self.pressed_set.remove(&usage) {
+                    self.pressed_order.retain(|&u| u != usage);
+                    return true;
+                }
+                return false;
+            }
+        };
+        let changed = *flag != is_pressed;
+        *flag = is_pressed;
+        changed
+    }
+
+    /// PaneBoard chord: Control + Shift + Alt/Option held (either side), no Command/Meta
+    pub fn chord_held(&self) -> bool {
+        (self.left_control || self.right_control)
+            && (self.left_shift || self.right_shift)
+            && (self.left_alt || self.right_alt)
+            && !(self.left_cmd || self.right_cmd)
     }
-}
 
-impl KeyState {
     pub fn format_output(&self) -> String {
         let modifiers = format!(
             "{} {} {} {} {} {} {} {}",
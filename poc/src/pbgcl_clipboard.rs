@@ -0,0 +1,39 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+//! Clipboard History
+//! Text history shared by the platform clipboard monitors
+//!
+//! Most recent first, at most CLIPBOARD_HISTORY_LIMIT entries. Text equal to
+//! the current front entry is not added again, so re-announcing the same
+//! clipboard (or taking back ownership to paste historic) does not duplicate.
+
+use std::sync::{Arc, Mutex};
+
+/// Maximum number of history entries kept
+pub const CLIPBOARD_HISTORY_LIMIT: usize = 50;
+
+lazy_static::lazy_static! {
+    pub static ref CLIPBOARD_HISTORY: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+}
+
+/// Record clipboard text (non-text content never reaches here)
+/// Returns true if the text became a new history entry
+pub fn record_clipboard_text(text: String) -> bool {
+    let mut history = CLIPBOARD_HISTORY.lock().unwrap();
+    if history.first() == Some(&text) {
+        return false;
+    }
+
+    let length = text.len();
+    history.insert(0, text);
+    history.truncate(CLIPBOARD_HISTORY_LIMIT);
+
+    println!("CLIP: captured text | length={}", length);
+    true
+}
+
+/// History entry by index (0 = most recent)
+pub fn clipboard_history_entry(index: usize) -> Option<String> {
+    CLIPBOARD_HISTORY.lock().unwrap().get(index).cloned()
+}
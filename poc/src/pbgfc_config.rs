@@ -1,14 +1,15 @@
 // SPDX-License-Identifier: Apache-2.0
 // Copyright 2025 Scale Invariant
 
-/// Form Configuration I/O
-/// Handles config file management, archiving, and path resolution
-///
-/// Responsibilities:
-/// - Config directory and file path resolution (~/.config/paneboard/form.xml)
-/// - Automatic archiving of existing configs (form.xml.NNNNN)
-/// - Deployment of embedded default configuration at startup
-/// - File I/O operations for loading and saving config
+//! Form Configuration I/O
+//! Handles config file management, archiving, and path resolution
+//!
+//! Responsibilities:
+//! - Config directory and file path resolution (~/.config/paneboard/form.xml)
+//! - Path for learned per-app state (~/.config/paneboard/app-min-sizes.tsv)
+//! - Automatic archiving of existing configs (form.xml.NNNNN)
+//! - Deployment of embedded default configuration at startup
+//! - File I/O operations for loading and saving config
 
 use std::fs;
 use std::path::PathBuf;
@@ -32,6 +33,16 @@ pub fn config_path() -> PathBuf {
     path
 }
 
+/// Resolve the learned per-app minimum size file path
+/// Machine-written state; not archived or replaced at startup like form.xml
+pub fn app_min_sizes_path() -> PathBuf {
+    let mut path = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push(".config");
+    path.push("paneboard");
+    path.push("app-min-sizes.tsv");
+    path
+}
+
 // ============================================================================
 // SECTION 3: Config deployment and archiving
 // ============================================================================
@@ -39,6 +50,7 @@ pub fn config_path() -> PathBuf {
 /// Ensure fresh default config is deployed at startup
 /// Archives existing form.xml to form.xml.NNNNN and writes embedded default
 /// Called at app startup (not lazily) to guarantee latest config is used
+#[cfg(target_os = "macos")]
 pub fn ensure_fresh_default_config() {
     let mut config_path = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
     config_path.push(".config");
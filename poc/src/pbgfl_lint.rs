@@ -0,0 +1,412 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+//! Form Lint Pass
+//! Quality diagnostics for form.xml beyond what validation rejects
+//!
+//! This module contains:
+//! - Unused definitions (Frames, Measures, Layouts never bound to a key)
+//! - Unreachable panes (below MIN_PANE_SIZE on every plausible display)
+//! - Key conflicts (one key bound twice; handle_configured_key picks one silently)
+//! - Duplicate panes (identical exact rects after flattening and mirroring)
+//! - Suppressions via <Lint><Suppress code="..." target="..."/></Lint>
+//! - The `lint [path]` CLI subcommand
+//!
+//! Every diagnostic is a warning: the Form still loads and runs as configured.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::pbgam_appmin::MIN_PANE_SIZE;
+use crate::pbgfp_parse::{ParsedForm, ParsedShape, ShapeChild, MeasureRef, SpaceRule, ParsedPane, ParsedMacroStep};
+use crate::pbgfr_resolve::Form;
+use crate::pbgft_types::DisplayProps;
+
+/// Lint codes accepted by <Suppress code="...">
+const LINT_CODES: &[&str] = &[
+    "unused-frame",
+    "unused-measure",
+    "unbound-layout",
+    "unreachable-pane",
+    "key-conflict",
+    "duplicate-pane",
+    "unknown-lint-code",
+];
+
+/// Display sizes (points, landscape and portrait) a Form is expected to handle
+/// Connected displays are linted in addition to these
+const PLAUSIBLE_DISPLAYS: &[(f64, f64)] = &[
+    (1280.0, 800.0),
+    (1440.0, 900.0),
+    (1512.0, 982.0),
+    (1728.0, 1117.0),
+    (1920.0, 1080.0),
+    (2560.0, 1440.0),
+    (3440.0, 1440.0),
+    (3840.0, 2160.0),
+    (1080.0, 1920.0),
+    (1440.0, 2560.0),
+];
+
+// ============================================================================
+// SECTION 1: Diagnostics
+// ============================================================================
+
+/// One lint finding; `target` is the name a <Suppress target="..."> matches
+pub struct LintDiagnostic {
+    pub code: &'static str,
+    pub target: String,
+    pub message: String,
+}
+
+impl fmt::Display for LintDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "warning[{}] {}", self.code, self.message)
+    }
+}
+
+/// Lint result: unsuppressed diagnostics plus how many were suppressed
+pub struct LintReport {
+    pub diagnostics: Vec<LintDiagnostic>,
+    pub suppressed: usize,
+}
+
+/// Run every lint over a validated Form
+/// `displays` are the connected displays (may be empty); plausible sizes are always added
+pub(crate) fn lint_form(parsed: &ParsedForm, form: &Form, displays: &[DisplayProps]) -> LintReport {
+    let mut all = Vec::new();
+
+    lint_unused_frames(parsed, &mut all);
+    lint_unused_measures(parsed, &mut all);
+    lint_unbound_layouts(parsed, &mut all);
+    lint_panes(parsed, form, &lint_displays(displays), &mut all);
+    lint_key_conflicts(parsed, &mut all);
+    lint_suppression_codes(parsed, &mut all);
+
+    // Apply suppressions (target omitted = whole code)
+    let mut diagnostics = Vec::new();
+    let mut suppressed = 0;
+    for diagnostic in all {
+        let is_suppressed = parsed.lint_suppressions.iter().any(|s| {
+            s.code == diagnostic.code && s.target.as_ref().is_none_or(|t| *t == diagnostic.target)
+        });
+        if is_suppressed {
+            suppressed += 1;
+        } else {
+            diagnostics.push(diagnostic);
+        }
+    }
+
+    LintReport { diagnostics, suppressed }
+}
+
+fn push(out: &mut Vec<LintDiagnostic>, code: &'static str, target: &str, message: String) {
+    out.push(LintDiagnostic { code, target: target.to_string(), message });
+}
+
+/// Names sorted for stable output (Form maps are HashMaps)
+fn sorted<'a, I: Iterator<Item = &'a String>>(names: I) -> Vec<&'a String> {
+    let mut names: Vec<&String> = names.collect();
+    names.sort();
+    names
+}
+
+// ============================================================================
+// SECTION 2: Unused definitions
+// ============================================================================
+
+fn lint_unused_frames(parsed: &ParsedForm, out: &mut Vec<LintDiagnostic>) {
+    let mut used = HashSet::new();
+    for layout in parsed.layouts.values() {
+        collect_shape_refs(&layout.root_shape, &mut used, &mut HashSet::new());
+    }
+
+    for name in sorted(parsed.frames.keys()) {
+        if !used.contains(name.as_str()) {
+            push(out, "unused-frame", name, format!("Frame '{}' is not used by any Shape", name));
+        }
+    }
+}
+
+fn lint_unused_measures(parsed: &ParsedForm, out: &mut Vec<LintDiagnostic>) {
+    let mut used: HashSet<&str> = HashSet::new();
+
+    for space in parsed.spaces.values() {
+        for rule in space.matches.iter().chain(&space.excludes) {
+            collect_rule_measures(rule, &mut used);
+        }
+    }
+    for layout in parsed.layouts.values() {
+        used.extend(layout.needed_measures.iter().map(|m| m.as_str()));
+        collect_shape_refs(&layout.root_shape, &mut HashSet::new(), &mut used);
+    }
+
+    for name in sorted(parsed.measures.keys()) {
+        if !used.contains(name.as_str()) {
+            push(out, "unused-measure", name, format!("Measure '{}' is not referenced by any Space, Shape or Needs", name));
+        }
+    }
+}
+
+fn lint_unbound_layouts(parsed: &ParsedForm, out: &mut Vec<LintDiagnostic>) {
+    // Bound directly or by a Macro step, inlined via <Include layout="...">, or extended by another Layout
+    let mut used: HashSet<&str> = parsed.layout_actions.iter().map(|a| a.layout.as_str()).collect();
+    for m in &parsed.macros {
+        for step in &m.steps {
+            if let ParsedMacroStep::LayoutAction { action, .. } = step {
+                used.insert(action.layout.as_str());
+            }
+        }
+    }
+    for layout in parsed.layouts.values() {
+        if let Some(ref parent) = layout.extends {
+            used.insert(parent);
+        }
+        collect_included_layouts(&layout.root_shape, &mut used);
+    }
+
+    for name in sorted(parsed.layouts.keys()) {
+        if !used.contains(name.as_str()) {
+            push(out, "unbound-layout", name,
+                format!("Layout '{}' is not bound to any LayoutAction, Macro, Include or extends", name));
+        }
+    }
+}
+
+/// Frames and Measures referenced by a Shape tree
+fn collect_shape_refs<'a>(shape: &'a ParsedShape, frames: &mut HashSet<&'a str>, measures: &mut HashSet<&'a str>) {
+    frames.insert(shape.frame.as_str());
+    for mref in [&shape.min_width, &shape.min_height, &shape.under_width, &shape.under_height].into_iter().flatten() {
+        if let MeasureRef::Name(ref name) = mref {
+            measures.insert(name.as_str());
+        }
+    }
+    for child in &shape.children {
+        if let ShapeChild::Shape(ref child_shape) = child {
+            collect_shape_refs(child_shape, frames, measures);
+        }
+    }
+}
+
+fn collect_rule_measures<'a>(rule: &'a SpaceRule, measures: &mut HashSet<&'a str>) {
+    for mref in [&rule.min_width, &rule.min_height, &rule.under_width, &rule.under_height].into_iter().flatten() {
+        if let MeasureRef::Name(ref name) = mref {
+            measures.insert(name.as_str());
+        }
+    }
+}
+
+fn collect_included_layouts<'a>(shape: &'a ParsedShape, layouts: &mut HashSet<&'a str>) {
+    for child in &shape.children {
+        match child {
+            ShapeChild::Shape(ref child_shape) => collect_included_layouts(child_shape, layouts),
+            ShapeChild::Include(ref include) => {
+                if let Some(ref name) = include.layout {
+                    layouts.insert(name.as_str());
+                }
+            }
+        }
+    }
+}
+
+// ============================================================================
+// SECTION 3: Pane reachability and duplicates
+// ============================================================================
+
+/// Connected displays first, so per-key findings name a real display when there is one
+fn lint_displays(displays: &[DisplayProps]) -> Vec<DisplayProps> {
+    let mut all: Vec<DisplayProps> = displays.to_vec();
+    all.extend(PLAUSIBLE_DISPLAYS.iter()
+        .map(|&(width, height)| DisplayProps { width, height, name: format!("{}x{}", width, height) }));
+    all
+}
+
+fn lint_panes(parsed: &ParsedForm, form: &Form, displays: &[DisplayProps], out: &mut Vec<LintDiagnostic>) {
+    let mut keys: Vec<&String> = parsed.layout_actions.iter().map(|a| &a.key).collect();
+    keys.sort();
+    keys.dedup();
+
+    for key in keys {
+        // Exact rect (with the Layout producing it) → reachable on at least one display where it appears
+        let mut seen: Vec<(ParsedPane, &str, bool)> = Vec::new();
+        let mut any_pane = false;
+        let mut duplicates_reported = false;
+
+        for display in displays {
+            let (layout_name, dedupe) = match form.layout_action_info(key, display) {
+                Some(info) => info,
+                None => continue,  // No binding's Space matches this display
+            };
+            let mut panes = form.exact_panes_for_action(key, display).unwrap_or_default();
+
+            let duplicates = Form::dedupe_panes(&mut panes);
+            if duplicates > 0 && !duplicates_reported {
+                // Once per key: other displays usually repeat the same finding
+                duplicates_reported = true;
+                let outcome = if dedupe {
+                    "collapsed"
+                } else {
+                    "kept (dedupe=\"false\"): presses revisit the same rect"
+                };
+                push(out, "duplicate-pane", key,
+                    format!("LayoutAction key='{}' Layout '{}' has {} duplicate panes on display '{}' ({})",
+                        key, layout_name, duplicates, display.name, outcome));
+            }
+
+            for pane in panes {
+                any_pane = true;
+                let fits = pane.width.to_f64() * display.width + 1.0 >= MIN_PANE_SIZE
+                    && pane.height.to_f64() * display.height + 1.0 >= MIN_PANE_SIZE;
+                match seen.iter_mut().find(|(p, name, _)| *p == pane && *name == layout_name) {
+                    Some((_, _, reachable)) => *reachable |= fits,
+                    None => seen.push((pane, layout_name, fits)),
+                }
+            }
+        }
+
+        if !any_pane {
+            push(out, "unreachable-pane", key,
+                format!("LayoutAction key='{}' yields no panes on any plausible display", key));
+            continue;
+        }
+
+        for (pane, layout_name, _) in seen.iter().filter(|(_, _, reachable)| !reachable) {
+            push(out, "unreachable-pane", key,
+                format!("LayoutAction key='{}' Layout '{}' pane x={}/{} y={}/{} w={}/{} h={}/{} is below {:.0}pt on every plausible display",
+                    key, layout_name,
+                    pane.x.num, pane.x.den, pane.y.num, pane.y.den,
+                    pane.width.num, pane.width.den, pane.height.num, pane.height.den,
+                    MIN_PANE_SIZE));
+        }
+    }
+}
+
+// ============================================================================
+// SECTION 4: Key conflicts
+// ============================================================================
+
+fn lint_key_conflicts(parsed: &ParsedForm, out: &mut Vec<LintDiagnostic>) {
+    // Same order handle_configured_key checks bindings in (first wins)
+    let mut bindings: Vec<(&str, &String)> = Vec::new();
+    bindings.extend(parsed.macros.iter().map(|b| ("Macro", &b.key)));
+    bindings.extend(parsed.layout_actions.iter().map(|b| ("LayoutAction", &b.key)));
+    bindings.extend(parsed.display_moves.iter().map(|b| ("DisplayMove", &b.key)));
+    bindings.extend(parsed.focus_moves.iter().map(|b| ("FocusMove", &b.key)));
+    bindings.extend(parsed.swap_actions.iter().map(|b| ("SwapAction", &b.key)));
+    bindings.extend(parsed.nudge_actions.iter().map(|b| ("NudgeAction", &b.key)));
+    bindings.extend(parsed.resize_actions.iter().map(|b| ("ResizeAction", &b.key)));
+
+    let mut keys: Vec<&String> = bindings.iter().map(|(_, key)| *key).collect();
+    keys.sort();
+    keys.dedup();
+
+    for key in keys {
+        let kinds: Vec<&str> = bindings.iter()
+            .filter(|(_, k)| *k == key)
+            .map(|(kind, _)| *kind)
+            .collect();
+        if kinds.len() < 2 {
+            continue;
+        }
+
+        let winner = kinds[0];
+        if kinds.iter().all(|kind| *kind == "LayoutAction") {
+            // Space-guarded per-display bindings; shadowing is a validation error
+            continue;
+        } else if kinds.iter().all(|kind| *kind == winner) {
+            // Runtime maps are keyed by key name: the last definition replaces earlier ones
+            push(out, "key-conflict", key,
+                format!("key='{}' is bound by {} {} elements; the last one wins", key, kinds.len(), winner));
+        } else {
+            let mut distinct = kinds.clone();
+            distinct.dedup();
+            push(out, "key-conflict", key,
+                format!("key='{}' is bound by {}; {} takes priority", key, distinct.join(", "), winner));
+        }
+    }
+}
+
+fn lint_suppression_codes(parsed: &ParsedForm, out: &mut Vec<LintDiagnostic>) {
+    for suppression in &parsed.lint_suppressions {
+        if !LINT_CODES.contains(&suppression.code.as_str()) {
+            push(out, "unknown-lint-code", &suppression.code,
+                format!("Suppress code='{}' is not a lint code (expected one of: {})", suppression.code, LINT_CODES.join(", ")));
+        }
+    }
+}
+
+// ============================================================================
+// SECTION 5: CLI subcommand
+// ============================================================================
+
+/// `paneboard lint [path]`: lint a form.xml (default: the standard config path)
+/// Exit status: 0 clean, 1 parse/validation errors, 2 lint warnings
+pub fn run_lint_cli(path: Option<&str>) -> i32 {
+    let path = path.map(std::path::PathBuf::from).unwrap_or_else(crate::pbgfc_config::config_path);
+
+    let xml = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("{}: error: failed to read: {}", path.display(), e);
+            return 1;
+        }
+    };
+
+    // No live displays here: plausible sizes only
+    let (parsed, form) = match Form::parse_and_build(&xml, &[]) {
+        Ok(built) => built,
+        Err(errors) => {
+            for error in errors {
+                eprintln!("{}: error: {}", path.display(), error);
+            }
+            return 1;
+        }
+    };
+
+    let report = lint_form(&parsed, &form, &[]);
+    for diagnostic in &report.diagnostics {
+        println!("{}: {}", path.display(), diagnostic);
+    }
+    println!("{}: {} warnings ({} suppressed)", path.display(), report.diagnostics.len(), report.suppressed);
+
+    if report.diagnostics.is_empty() { 0 } else { 2 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lint a Form with one LayoutAction over a Frame whose two panes coincide
+    fn lint_duplicates(dedupe: &str) -> Vec<String> {
+        let xml = format!(r#"<?xml version="1.0" encoding="utf-8"?>
+<Form>
+  <Frame name="twice">
+    <Pane x="0" y="0" width="1/2" height="1"/>
+    <Pane x="0" y="0" width="2/4" height="1"/>
+  </Frame>
+  <Layout name="twice">
+    <Shape frame="twice"><Leaf/><Leaf/></Shape>
+  </Layout>
+  <LayoutAction key="h" layout="twice" dedupe="{}"/>
+</Form>"#, dedupe);
+        let (parsed, form) = Form::parse_and_build(&xml, &[]).expect("form builds");
+        lint_form(&parsed, &form, &[]).diagnostics.iter()
+            .filter(|d| d.code == "duplicate-pane")
+            .map(|d| d.message.clone())
+            .collect()
+    }
+
+    #[test]
+    fn duplicate_panes_warn_when_collapsed() {
+        let messages = lint_duplicates("true");
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].contains("(collapsed)"), "{}", messages[0]);
+    }
+
+    #[test]
+    fn duplicate_panes_warn_when_kept_in_cycle() {
+        let messages = lint_duplicates("false");
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].contains("presses revisit the same rect"), "{}", messages[0]);
+    }
+}
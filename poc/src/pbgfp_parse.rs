@@ -1,20 +1,20 @@
 // SPDX-License-Identifier: Apache-2.0
 // Copyright 2025 Scale Invariant
 
-/// Form Configuration Parser - XML Parsing Module
-/// Extracts XML parsing logic and parse-time structures from pbgf_form.rs
-///
-/// This module contains:
-/// - Fraction type for exact fractional proportions
-/// - Parse-time structures (ParsedForm, ParsedSpace, ParsedFrame, etc.)
-/// - XML parsing implementation using quick_xml
+//! Form Configuration Parser - XML Parsing Module
+//! Extracts XML parsing logic and parse-time structures from pbgf_form.rs
+//!
+//! This module contains:
+//! - Fraction type for exact fractional proportions
+//! - Parse-time structures (ParsedForm, ParsedSpace, ParsedFrame, etc.)
+//! - XML parsing implementation using quick_xml
 
 use quick_xml::events::Event;
 use quick_xml::Reader;
 use std::collections::HashMap;
 
 // Import runtime types from pbgft_types
-use crate::pbgft_types::DisplayMoveTarget;
+use crate::pbgft_types::{Direction, DisplayIdentity, DisplayMoveMode, DisplayMoveTarget, Edge, FallbackPolicy, MacroFailurePolicy, SwapTarget, TilingConfig, TilingMode};
 
 // ============================================================================
 // SECTION 1: Fraction type and helpers
@@ -28,6 +28,12 @@ pub(crate) struct Fraction {
 }
 
 impl Fraction {
+    /// Construct reduced fraction (den must be non-zero)
+    #[cfg(test)]
+    pub(crate) fn new(num: u32, den: u32) -> Self {
+        Fraction { num, den }.reduce()
+    }
+
     /// Parse from string: "3/10", "1", "0"
     pub(crate) fn parse(s: &str) -> Result<Self, String> {
         let s = s.trim();
@@ -56,7 +62,7 @@ impl Fraction {
     }
 
     /// Convert to f64 for pixel calculations (only at final stage)
-    pub(crate) fn to_f64(&self) -> f64 {
+    pub(crate) fn to_f64(self) -> f64 {
         self.num as f64 / self.den as f64
     }
 
@@ -91,6 +97,16 @@ impl Fraction {
         }.reduce()
     }
 
+    /// Subtract two fractions: a/b - c/d = (ad - bc) / bd (saturates at zero)
+    pub(crate) fn sub(&self, other: &Fraction) -> Fraction {
+        let lhs = self.num * other.den;
+        let rhs = other.num * self.den;
+        Fraction {
+            num: lhs.saturating_sub(rhs),
+            den: self.den * other.den,
+        }.reduce()
+    }
+
     /// Multiply two fractions: (a/b) * (c/d) = (ac) / (bd)
     pub(crate) fn mul(&self, other: &Fraction) -> Fraction {
         Fraction {
@@ -111,6 +127,22 @@ pub(crate) struct ParsedForm {
     pub(crate) layouts: HashMap<String, ParsedLayout>,
     pub(crate) layout_actions: Vec<ParsedLayoutAction>,
     pub(crate) display_moves: Vec<ParsedDisplayMove>,
+    pub(crate) focus_moves: Vec<ParsedFocusMove>,
+    pub(crate) swap_actions: Vec<ParsedSwapAction>,
+    pub(crate) nudge_actions: Vec<ParsedNudgeAction>,
+    pub(crate) resize_actions: Vec<ParsedResizeAction>,
+    pub(crate) macros: Vec<ParsedMacro>,
+    pub(crate) applications: Vec<ParsedApplication>,
+    pub(crate) tile_fallback: Option<FallbackPolicy>,  // Form-level <TileFallback>
+    pub(crate) lint_suppressions: Vec<LintSuppression>,
+}
+
+/// <Lint><Suppress code="..." target="..."/></Lint>: silence a lint diagnostic
+/// target omitted = every diagnostic with that code
+#[derive(Clone)]
+pub(crate) struct LintSuppression {
+    pub(crate) code: String,
+    pub(crate) target: Option<String>,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -121,11 +153,25 @@ pub(crate) enum Platform {
     Linux,
 }
 
+impl Platform {
+    /// Platform this binary was built for
+    pub(crate) fn current() -> Self {
+        if cfg!(target_os = "macos") {
+            Platform::MacOS
+        } else if cfg!(target_os = "windows") {
+            Platform::Windows
+        } else {
+            Platform::Linux
+        }
+    }
+}
+
 #[derive(Clone)]
 pub(crate) struct ParsedSpace {
     pub(crate) name: String,
     pub(crate) matches: Vec<SpaceRule>,
     pub(crate) excludes: Vec<SpaceRule>,
+    pub(crate) auto_tile: Option<TilingConfig>,
 }
 
 #[derive(Clone)]
@@ -157,7 +203,7 @@ pub(crate) struct ParsedFrame {
     pub(crate) panes: Vec<ParsedPane>,
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub(crate) struct ParsedPane {
     pub(crate) x: Fraction,
     pub(crate) y: Fraction,
@@ -165,11 +211,14 @@ pub(crate) struct ParsedPane {
     pub(crate) height: Fraction,
 }
 
+#[derive(Clone)]
 pub(crate) struct ParsedLayout {
     pub(crate) name: String,
     pub(crate) space: Option<String>, // references Space name
     pub(crate) needed_measures: Vec<String>,
     pub(crate) root_shape: ParsedShape,
+    pub(crate) extends: Option<String>,            // references parent Layout name (resolved after parsing)
+    pub(crate) transforms: Vec<ShapeTransform>,    // applied to every top-level Shape (inherited ones too)
 }
 
 #[derive(Clone)]
@@ -184,9 +233,62 @@ pub(crate) struct ParsedShape {
     pub(crate) under_width: Option<MeasureRef>,
     #[allow(dead_code)] // For future conditional Shape evaluation
     pub(crate) under_height: Option<MeasureRef>,
+    pub(crate) id: Option<String>,               // Top-level Shapes: replaced by same id in an extending Layout
+    pub(crate) transforms: Vec<ShapeTransform>,  // Applied in order within the Shape's own box
     pub(crate) children: Vec<ShapeChild>,
 }
 
+/// Geometric transform of a Shape's leaves within the Shape's own box
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ShapeTransform {
+    MirrorX,    // x' = 1 - x - width
+    MirrorY,    // y' = 1 - y - height
+    Transpose,  // swap x/y and width/height (landscape design → portrait display)
+    Rotate90,   // quarter turn clockwise
+}
+
+impl ShapeTransform {
+    /// Parse a whitespace-separated list: "transpose", "mirrorX rotate90"
+    pub(crate) fn parse_list(s: &str) -> Result<Vec<Self>, String> {
+        s.split_whitespace()
+            .map(|token| match token {
+                "mirrorX" => Ok(ShapeTransform::MirrorX),
+                "mirrorY" => Ok(ShapeTransform::MirrorY),
+                "transpose" => Ok(ShapeTransform::Transpose),
+                "rotate90" => Ok(ShapeTransform::Rotate90),
+                _ => Err(format!("invalid transform: {}", token)),
+            })
+            .collect()
+    }
+
+    /// Apply to a pane expressed in the unit box (exact fractions)
+    pub(crate) fn apply(&self, pane: &ParsedPane) -> ParsedPane {
+        let one = Fraction { num: 1, den: 1 };
+        match self {
+            ShapeTransform::MirrorX => ParsedPane {
+                x: one.sub(&pane.x).sub(&pane.width),
+                ..pane.clone()
+            },
+            ShapeTransform::MirrorY => ParsedPane {
+                y: one.sub(&pane.y).sub(&pane.height),
+                ..pane.clone()
+            },
+            ShapeTransform::Transpose => ParsedPane {
+                x: pane.y,
+                y: pane.x,
+                width: pane.height,
+                height: pane.width,
+            },
+            ShapeTransform::Rotate90 => ParsedPane {
+                x: one.sub(&pane.y).sub(&pane.height),
+                y: pane.x,
+                width: pane.height,
+                height: pane.width,
+            },
+        }
+    }
+}
+
 #[derive(Clone)]
 pub(crate) enum ShapeChild {
     Shape(ParsedShape),
@@ -213,10 +315,12 @@ pub(crate) struct IncludeCondition {
 
 pub(crate) struct ParsedLayoutAction {
     pub(crate) key: String,
-    pub(crate) layout: String, // references Layout name
+    pub(crate) layout: String,         // references Layout name
+    pub(crate) space: Option<String>,  // Guard Space (defaults to the Layout's Space); first match per key wins
     pub(crate) traverse: TraverseOrder,
     pub(crate) mirror_x: MirrorMode,
     pub(crate) mirror_y: MirrorMode,
+    pub(crate) dedupe: bool,  // Collapse panes with identical exact rects (default true)
 }
 
 #[derive(Clone, Copy)]
@@ -234,6 +338,70 @@ pub(crate) enum MirrorMode {
 pub(crate) struct ParsedDisplayMove {
     pub(crate) key: String,
     pub(crate) target: DisplayMoveTarget,
+    pub(crate) mode: DisplayMoveMode,
+}
+
+pub(crate) struct ParsedFocusMove {
+    pub(crate) key: String,
+    pub(crate) direction: Direction,
+}
+
+pub(crate) struct ParsedSwapAction {
+    pub(crate) key: String,
+    pub(crate) target: SwapTarget,
+}
+
+pub(crate) struct ParsedNudgeAction {
+    pub(crate) key: String,
+    pub(crate) direction: Direction,
+    pub(crate) amount: Fraction,
+}
+
+pub(crate) struct ParsedResizeAction {
+    pub(crate) key: String,
+    pub(crate) edge: Edge,
+    pub(crate) amount: Fraction,
+    pub(crate) grow: bool,
+}
+
+/// <Macro key="..." onFailure="stop|continue">: ordered steps bound to one key
+pub(crate) struct ParsedMacro {
+    pub(crate) key: String,
+    pub(crate) on_failure: MacroFailurePolicy,
+    pub(crate) steps: Vec<ParsedMacroStep>,
+}
+
+/// Macro step: the top-level binding element without its key attribute
+pub(crate) enum ParsedMacroStep {
+    LayoutAction { action: ParsedLayoutAction, pane: usize },  // pane: 1-based, as written
+    DisplayMove(ParsedDisplayMove),
+    FocusMove(ParsedFocusMove),
+    FocusMru,
+    SwapAction(ParsedSwapAction),
+    NudgeAction(ParsedNudgeAction),
+    ResizeAction(ParsedResizeAction),
+}
+
+#[derive(Clone)]
+pub(crate) struct ParsedApplication {
+    pub(crate) name: String,
+    pub(crate) mac_bundle_ids: Vec<String>,
+    pub(crate) windows_exes: Vec<String>,
+    pub(crate) linux_processes: Vec<String>,
+    pub(crate) tile_fallback: Option<FallbackPolicy>,
+}
+
+impl ParsedApplication {
+    /// True if any matcher for the given platform equals the app identifier
+    /// (bundle ID on macOS, executable name on Windows, process name on Linux)
+    pub(crate) fn matches(&self, platform: Platform, app_id: &str) -> bool {
+        let matchers = match platform {
+            Platform::MacOS => &self.mac_bundle_ids,
+            Platform::Windows => &self.windows_exes,
+            Platform::Linux => &self.linux_processes,
+        };
+        matchers.iter().any(|m| m == app_id)
+    }
 }
 
 // ============================================================================
@@ -251,6 +419,14 @@ impl ParsedForm {
         let mut layouts = HashMap::new();
         let mut layout_actions = Vec::new();
         let mut display_moves = Vec::new();
+        let mut focus_moves = Vec::new();
+        let mut swap_actions = Vec::new();
+        let mut nudge_actions = Vec::new();
+        let mut resize_actions = Vec::new();
+        let mut macros = Vec::new();
+        let mut applications = Vec::new();
+        let mut tile_fallback = None;
+        let mut lint_suppressions = Vec::new();
 
         let mut buf = Vec::new();
         let mut in_form = false;
@@ -280,6 +456,20 @@ impl ParsedForm {
                                 .map_err(|e| format!("at byte {}: {}", reader.buffer_position(), e))?;
                             layouts.insert(layout.name.clone(), layout);
                         }
+                        b"Macro" if in_form => {
+                            let m = Self::parse_macro(&mut reader, e)
+                                .map_err(|e| format!("at byte {}: {}", reader.buffer_position(), e))?;
+                            macros.push(m);
+                        }
+                        b"Application" if in_form => {
+                            let app = Self::parse_application(&mut reader, e)
+                                .map_err(|e| format!("at byte {}: {}", reader.buffer_position(), e))?;
+                            applications.push(app);
+                        }
+                        b"Lint" if in_form => {
+                            lint_suppressions.extend(Self::parse_lint(&mut reader)
+                                .map_err(|e| format!("at byte {}: {}", reader.buffer_position(), e))?);
+                        }
                         _ => {}
                     }
                 }
@@ -291,15 +481,46 @@ impl ParsedForm {
                             measures.insert(m.0, m.1);
                         }
                         b"LayoutAction" if in_form => {
-                            let action = Self::parse_layout_action(e)
+                            let action = Self::parse_layout_action(e, None)
                                 .map_err(|e| format!("at byte {}: {}", reader.buffer_position(), e))?;
                             layout_actions.push(action);
                         }
                         b"DisplayMove" if in_form => {
-                            let dm = Self::parse_display_move(e)
+                            let dm = Self::parse_display_move(e, None)
                                 .map_err(|e| format!("at byte {}: {}", reader.buffer_position(), e))?;
                             display_moves.push(dm);
                         }
+                        b"FocusMove" if in_form => {
+                            let fm = Self::parse_focus_move(e, None)
+                                .map_err(|e| format!("at byte {}: {}", reader.buffer_position(), e))?;
+                            focus_moves.push(fm);
+                        }
+                        b"SwapAction" if in_form => {
+                            let sa = Self::parse_swap_action(e, None)
+                                .map_err(|e| format!("at byte {}: {}", reader.buffer_position(), e))?;
+                            swap_actions.push(sa);
+                        }
+                        b"NudgeAction" if in_form => {
+                            let na = Self::parse_nudge_action(e, None)
+                                .map_err(|e| format!("at byte {}: {}", reader.buffer_position(), e))?;
+                            nudge_actions.push(na);
+                        }
+                        b"ResizeAction" if in_form => {
+                            let ra = Self::parse_resize_action(e, None)
+                                .map_err(|e| format!("at byte {}: {}", reader.buffer_position(), e))?;
+                            resize_actions.push(ra);
+                        }
+                        b"Layout" if in_form => {
+                            // Shape-less Layout: only meaningful with extends (checked in finish_layout)
+                            let layout = Self::parse_layout_header(e)
+                                .and_then(|l| Self::finish_layout(l, Vec::new()))
+                                .map_err(|e| format!("at byte {}: {}", reader.buffer_position(), e))?;
+                            layouts.insert(layout.name.clone(), layout);
+                        }
+                        b"TileFallback" if in_form => {
+                            tile_fallback = Some(Self::parse_tile_fallback(e)
+                                .map_err(|e| format!("at byte {}: {}", reader.buffer_position(), e))?);
+                        }
                         // Note: DisplayQuirk elements are silently ignored (deprecated)
                         _ => {}
                     }
@@ -314,6 +535,9 @@ impl ParsedForm {
             buf.clear();
         }
 
+        // Expand <Layout extends> and Layout-level transforms into plain Shape lists
+        Self::resolve_layout_inheritance(&mut layouts)?;
+
         Ok(ParsedForm {
             measures,
             spaces,
@@ -321,6 +545,14 @@ impl ParsedForm {
             layouts,
             layout_actions,
             display_moves,
+            focus_moves,
+            swap_actions,
+            nudge_actions,
+            resize_actions,
+            macros,
+            applications,
+            tile_fallback,
+            lint_suppressions,
         })
     }
 
@@ -360,6 +592,7 @@ impl ParsedForm {
         let name = name.ok_or("Space missing name attribute")?;
         let mut matches = Vec::new();
         let mut excludes = Vec::new();
+        let mut auto_tile = None;
         let mut buf = Vec::new();
 
         loop {
@@ -368,6 +601,7 @@ impl ParsedForm {
                     match e.name().as_ref() {
                         b"Match" => matches.push(Self::parse_space_rule(e)?),
                         b"Exclude" => excludes.push(Self::parse_space_rule(e)?),
+                        b"AutoTile" => auto_tile = Some(Self::parse_auto_tile(e)?),
                         _ => {}
                     }
                 }
@@ -379,7 +613,35 @@ impl ParsedForm {
             buf.clear();
         }
 
-        Ok(ParsedSpace { name, matches, excludes })
+        Ok(ParsedSpace { name, matches, excludes, auto_tile })
+    }
+
+    fn parse_auto_tile(e: &quick_xml::events::BytesStart) -> Result<TilingConfig, String> {
+        let mut mode = TilingMode::Bsp; // default
+        let mut ratio = Fraction { num: 1, den: 2 }; // default
+
+        for attr in e.attributes() {
+            let attr = attr.map_err(|e| format!("attribute error: {}", e))?;
+            let value = String::from_utf8_lossy(&attr.value).to_string();
+
+            match attr.key.as_ref() {
+                b"mode" => {
+                    mode = match value.as_str() {
+                        "bsp" => TilingMode::Bsp,
+                        "master-stack" => TilingMode::MasterStack,
+                        _ => return Err(format!("invalid AutoTile mode: {}", value)),
+                    };
+                }
+                b"ratio" => ratio = Fraction::parse(&value)?,
+                _ => {}
+            }
+        }
+
+        if ratio.num == 0 || ratio.num >= ratio.den {
+            return Err(format!("AutoTile ratio must be strictly between 0 and 1: {}/{}", ratio.num, ratio.den));
+        }
+
+        Ok(TilingConfig { mode, ratio })
     }
 
     fn parse_space_rule(e: &quick_xml::events::BytesStart) -> Result<SpaceRule, String> {
@@ -485,22 +747,7 @@ impl ParsedForm {
     }
 
     fn parse_layout(reader: &mut Reader<&[u8]>, start: &quick_xml::events::BytesStart) -> Result<ParsedLayout, String> {
-        let mut name = None;
-        let mut space = None;
-
-        for attr in start.attributes() {
-            let attr = attr.map_err(|e| format!("attribute error: {}", e))?;
-            let value = String::from_utf8_lossy(&attr.value).to_string();
-
-            match attr.key.as_ref() {
-                b"name" => name = Some(value),
-                b"space" => space = Some(value),
-                _ => {}
-            }
-        }
-
-        let name = name.ok_or("Layout missing name attribute")?;
-        let mut needed_measures = Vec::new();
+        let mut layout = Self::parse_layout_header(start)?;
         let mut root_shapes = Vec::new();
         let mut buf = Vec::new();
 
@@ -510,7 +757,7 @@ impl ParsedForm {
                     for attr in e.attributes() {
                         let attr = attr.map_err(|e| format!("attribute error: {}", e))?;
                         if attr.key.as_ref() == b"measure" {
-                            needed_measures.push(String::from_utf8_lossy(&attr.value).to_string());
+                            layout.needed_measures.push(String::from_utf8_lossy(&attr.value).to_string());
                         }
                     }
                 }
@@ -528,13 +775,53 @@ impl ParsedForm {
             buf.clear();
         }
 
-        if root_shapes.is_empty() {
-            return Err(format!("Layout '{}' missing Shape", name));
+        Self::finish_layout(layout, root_shapes)
+    }
+
+    /// Layout attributes only; Shapes and Needs are filled in by the caller
+    fn parse_layout_header(start: &quick_xml::events::BytesStart) -> Result<ParsedLayout, String> {
+        let mut name = None;
+        let mut space = None;
+        let mut extends = None;
+        let mut transforms = Vec::new();
+
+        for attr in start.attributes() {
+            let attr = attr.map_err(|e| format!("attribute error: {}", e))?;
+            let value = String::from_utf8_lossy(&attr.value).to_string();
+
+            match attr.key.as_ref() {
+                b"name" => name = Some(value),
+                b"space" => space = Some(value),
+                b"extends" => extends = Some(value),
+                b"transform" => transforms = ShapeTransform::parse_list(&value)?,
+                _ => {}
+            }
+        }
+
+        let name = name.ok_or("Layout missing name attribute")?;
+
+        Ok(ParsedLayout {
+            name,
+            space,
+            needed_measures: Vec::new(),
+            root_shape: Self::compose_root(Vec::new()),
+            extends,
+            transforms,
+        })
+    }
+
+    fn finish_layout(mut layout: ParsedLayout, root_shapes: Vec<ParsedShape>) -> Result<ParsedLayout, String> {
+        if root_shapes.is_empty() && layout.extends.is_none() {
+            return Err(format!("Layout '{}' missing Shape", layout.name));
         }
+        layout.root_shape = Self::compose_root(root_shapes);
+        Ok(layout)
+    }
 
-        // For now, wrap multiple shapes in a single composite root
-        // The flatten logic will handle concatenating results from multiple top-level shapes
-        let root_shape = if root_shapes.len() == 1 {
+    /// Wrap top-level shapes in a single root
+    /// The flatten logic will handle concatenating results from multiple top-level shapes
+    fn compose_root(root_shapes: Vec<ParsedShape>) -> ParsedShape {
+        if root_shapes.len() == 1 {
             root_shapes.into_iter().next().unwrap()
         } else {
             // Create synthetic "multi" frame that will be handled specially during flattening
@@ -545,16 +832,103 @@ impl ParsedForm {
                 min_height: None,
                 under_width: None,
                 under_height: None,
+                id: None,
+                transforms: Vec::new(),
                 children: root_shapes.into_iter().map(ShapeChild::Shape).collect(),
             }
-        };
+        }
+    }
 
-        Ok(ParsedLayout {
-            name,
-            space,
-            needed_measures,
-            root_shape,
-        })
+    /// Inverse of compose_root
+    fn top_level_shapes(root: &ParsedShape) -> Vec<ParsedShape> {
+        if root.frame == "__multi__" {
+            root.children.iter()
+                .filter_map(|child| match child {
+                    ShapeChild::Shape(shape) => Some(shape.clone()),
+                    ShapeChild::Include(_) => None,
+                })
+                .collect()
+        } else {
+            vec![root.clone()]
+        }
+    }
+
+    /// Expand every Layout's extends chain and Layout-level transforms in place
+    fn resolve_layout_inheritance(layouts: &mut HashMap<String, ParsedLayout>) -> Result<(), String> {
+        let mut names: Vec<String> = layouts.keys().cloned().collect();
+        names.sort();
+
+        let mut resolved = HashMap::new();
+        for name in &names {
+            Self::resolve_layout(name, layouts, &mut resolved, &mut Vec::new())?;
+        }
+
+        *layouts = resolved;
+        Ok(())
+    }
+
+    /// Resolve one Layout after its parent:
+    /// - Parent's top-level Shapes come first; a Shape whose id matches a parent
+    ///   Shape replaces it in place, others are appended
+    /// - space is inherited unless overridden; Needs are unioned
+    /// - This Layout's transforms are appended to every resulting top-level Shape
+    fn resolve_layout(
+        name: &str,
+        raw: &HashMap<String, ParsedLayout>,
+        resolved: &mut HashMap<String, ParsedLayout>,
+        chain: &mut Vec<String>,
+    ) -> Result<(), String> {
+        if resolved.contains_key(name) {
+            return Ok(());
+        }
+        if chain.iter().any(|n| n == name) {
+            return Err(format!("Layout inheritance cycle: {} -> {}", chain.join(" -> "), name));
+        }
+        let layout = &raw[name];
+
+        let mut result = layout.clone();
+        let mut shapes = Vec::new();
+
+        if let Some(ref parent_name) = layout.extends {
+            if !raw.contains_key(parent_name) {
+                return Err(format!("Layout '{}' extends undefined Layout '{}'", name, parent_name));
+            }
+            chain.push(name.to_string());
+            Self::resolve_layout(parent_name, raw, resolved, chain)?;
+            chain.pop();
+
+            let parent = &resolved[parent_name];
+            shapes = Self::top_level_shapes(&parent.root_shape);
+            if result.space.is_none() {
+                result.space = parent.space.clone();
+            }
+            for measure in &parent.needed_measures {
+                if !result.needed_measures.contains(measure) {
+                    result.needed_measures.push(measure.clone());
+                }
+            }
+        }
+
+        for own in Self::top_level_shapes(&layout.root_shape) {
+            let replace_at = own.id.as_ref()
+                .and_then(|id| shapes.iter().position(|s| s.id.as_ref() == Some(id)));
+            match replace_at {
+                Some(idx) => shapes[idx] = own,
+                None => shapes.push(own),
+            }
+        }
+
+        if shapes.is_empty() {
+            return Err(format!("Layout '{}' has no Shapes", name));
+        }
+        for shape in shapes.iter_mut() {
+            shape.transforms.extend(layout.transforms.iter().copied());
+        }
+
+        result.root_shape = Self::compose_root(shapes);
+        result.transforms = Vec::new();  // Baked into the Shapes
+        resolved.insert(name.to_string(), result);
+        Ok(())
     }
 
     fn parse_shape(reader: &mut Reader<&[u8]>, start: &quick_xml::events::BytesStart) -> Result<ParsedShape, String> {
@@ -628,6 +1002,8 @@ impl ParsedForm {
         let mut min_height = None;
         let mut under_width = None;
         let mut under_height = None;
+        let mut id = None;
+        let mut transforms = Vec::new();
 
         for attr in e.attributes() {
             let attr = attr.map_err(|e| format!("attribute error: {}", e))?;
@@ -647,6 +1023,8 @@ impl ParsedForm {
                 b"minHeight" => min_height = Some(Self::parse_measure_ref(&value)?),
                 b"underWidth" => under_width = Some(Self::parse_measure_ref(&value)?),
                 b"underHeight" => under_height = Some(Self::parse_measure_ref(&value)?),
+                b"id" => id = Some(value),
+                b"transform" => transforms = ShapeTransform::parse_list(&value)?,
                 _ => {}
             }
         }
@@ -660,6 +1038,8 @@ impl ParsedForm {
             min_height,
             under_width,
             under_height,
+            id,
+            transforms,
             children: Vec::new(), // populated by caller
         })
     }
@@ -723,12 +1103,14 @@ impl ParsedForm {
         })
     }
 
-    fn parse_layout_action(e: &quick_xml::events::BytesStart) -> Result<ParsedLayoutAction, String> {
-        let mut key = None;
+    fn parse_layout_action(e: &quick_xml::events::BytesStart, macro_key: Option<&str>) -> Result<ParsedLayoutAction, String> {
+        let mut key = macro_key.map(|k| k.to_string());  // Macro steps inherit the Macro's key
         let mut layout = None;
+        let mut space = None;
         let mut traverse = TraverseOrder::XfYf; // default
         let mut mirror_x = MirrorMode::Keep;    // default
         let mut mirror_y = MirrorMode::Keep;    // default
+        let mut dedupe = true;                  // default
 
         for attr in e.attributes() {
             let attr = attr.map_err(|e| format!("attribute error: {}", e))?;
@@ -737,6 +1119,7 @@ impl ParsedForm {
             match attr.key.as_ref() {
                 b"key" => key = Some(value),
                 b"layout" => layout = Some(value),
+                b"space" => space = Some(value),
                 b"traverse" => {
                     traverse = match value.to_lowercase().as_str() {
                         "xfyf" => TraverseOrder::XfYf,
@@ -764,6 +1147,13 @@ impl ParsedForm {
                         _ => return Err(format!("invalid mirrorY: {}", value)),
                     };
                 }
+                b"dedupe" => {
+                    dedupe = match value.as_str() {
+                        "true" => true,
+                        "false" => false,
+                        _ => return Err(format!("invalid dedupe: {}", value)),
+                    };
+                }
                 _ => {}
             }
         }
@@ -772,20 +1162,25 @@ impl ParsedForm {
             (Some(k), Some(l)) => Ok(ParsedLayoutAction {
                 key: k,
                 layout: l,
+                space,
                 traverse,
                 mirror_x,
                 mirror_y,
+                dedupe,
             }),
             _ => Err("LayoutAction missing required attributes (key, layout)".to_string()),
         }
     }
 
-    fn parse_display_move(e: &quick_xml::events::BytesStart) -> Result<ParsedDisplayMove, String> {
-        let mut key = None;
+    fn parse_display_move(e: &quick_xml::events::BytesStart, macro_key: Option<&str>) -> Result<ParsedDisplayMove, String> {
+        let mut key = macro_key.map(|k| k.to_string());  // Macro steps inherit the Macro's key
         let mut next = None;
         let mut prev = None;
         let mut index = None;
+        let mut direction = None;
+        let mut named = None;
         let mut wrap = false; // default
+        let mut mode = DisplayMoveMode::Preserve; // default
 
         for attr in e.attributes() {
             let attr = attr.map_err(|e| format!("attribute error: {}", e))?;
@@ -798,6 +1193,21 @@ impl ParsedForm {
                     match value.as_str() {
                         "next" => next = Some(true),
                         "prev" => prev = Some(true),
+                        _ if Direction::parse(&value).is_some() => {
+                            direction = Direction::parse(&value);
+                        }
+                        _ if value.starts_with("name:") => {
+                            let pattern = &value["name:".len()..];
+                            if pattern.is_empty() {
+                                return Err("DisplayMove target name: requires a pattern".to_string());
+                            }
+                            named = Some(DisplayMoveTarget::Name(pattern.to_string()));
+                        }
+                        _ if value.starts_with("id:") => {
+                            let identity = DisplayIdentity::parse(&value["id:".len()..])
+                                .ok_or_else(|| format!("invalid display identity (expected id:vendor:model:serial): {}", value))?;
+                            named = Some(DisplayMoveTarget::Identity(identity));
+                        }
                         _ => {
                             // Try parsing as numeric index
                             index = Some(value.parse::<usize>()
@@ -806,26 +1216,447 @@ impl ParsedForm {
                     }
                 }
                 // Legacy boolean attributes
-                b"next" => if value == "true" { next = Some(true); },
-                b"prev" => if value == "true" { prev = Some(true); },
+                b"next" if value == "true" => next = Some(true),
+                b"prev" if value == "true" => prev = Some(true),
                 b"index" => {
                     index = Some(value.parse::<usize>()
                         .map_err(|_| format!("invalid index: {}", value))?);
                 }
                 b"wrap" => wrap = value == "true",
+                b"mode" => {
+                    mode = match value.as_str() {
+                        "preserve" => DisplayMoveMode::Preserve,
+                        "proportional" => DisplayMoveMode::Proportional,
+                        _ => return Err(format!("invalid DisplayMove mode: {}", value)),
+                    };
+                }
                 _ => {}
             }
         }
 
         let key = key.ok_or("DisplayMove missing key attribute")?;
 
-        let target = match (next, prev, index) {
-            (Some(true), None, None) => DisplayMoveTarget::Next { wrap },
-            (None, Some(true), None) => DisplayMoveTarget::Prev { wrap },
-            (None, None, Some(i)) => DisplayMoveTarget::Index(i),
-            _ => return Err("DisplayMove must specify exactly one of: next, prev, or index".to_string()),
+        let target = match (next, prev, index, direction, named) {
+            (Some(true), None, None, None, None) => DisplayMoveTarget::Next { wrap },
+            (None, Some(true), None, None, None) => DisplayMoveTarget::Prev { wrap },
+            (None, None, Some(i), None, None) => DisplayMoveTarget::Index(i),
+            (None, None, None, Some(d), None) => DisplayMoveTarget::Direction(d),
+            (None, None, None, None, Some(t)) => t,
+            _ => return Err("DisplayMove must specify exactly one of: next, prev, index, direction, name:, or id:".to_string()),
         };
 
-        Ok(ParsedDisplayMove { key, target })
+        Ok(ParsedDisplayMove { key, target, mode })
+    }
+
+    fn parse_focus_move(e: &quick_xml::events::BytesStart, macro_key: Option<&str>) -> Result<ParsedFocusMove, String> {
+        let mut key = macro_key.map(|k| k.to_string());  // Macro steps inherit the Macro's key
+        let mut direction = None;
+
+        for attr in e.attributes() {
+            let attr = attr.map_err(|e| format!("attribute error: {}", e))?;
+            let value = String::from_utf8_lossy(&attr.value).to_string();
+
+            match attr.key.as_ref() {
+                b"key" => key = Some(value),
+                b"direction" => {
+                    direction = Some(Direction::parse(&value)
+                        .ok_or_else(|| format!("invalid FocusMove direction: {}", value))?);
+                }
+                _ => {}
+            }
+        }
+
+        let key = key.ok_or("FocusMove missing key attribute")?;
+        let direction = direction.ok_or("FocusMove missing direction attribute")?;
+
+        Ok(ParsedFocusMove { key, direction })
+    }
+
+    fn parse_swap_action(e: &quick_xml::events::BytesStart, macro_key: Option<&str>) -> Result<ParsedSwapAction, String> {
+        let mut key = macro_key.map(|k| k.to_string());  // Macro steps inherit the Macro's key
+        let mut target = None;
+
+        for attr in e.attributes() {
+            let attr = attr.map_err(|e| format!("attribute error: {}", e))?;
+            let value = String::from_utf8_lossy(&attr.value).to_string();
+
+            match attr.key.as_ref() {
+                b"key" => key = Some(value),
+                b"direction" => {
+                    target = Some(SwapTarget::parse(&value)
+                        .ok_or_else(|| format!("invalid SwapAction direction: {}", value))?);
+                }
+                _ => {}
+            }
+        }
+
+        let key = key.ok_or("SwapAction missing key attribute")?;
+        let target = target.ok_or("SwapAction missing direction attribute")?;
+
+        Ok(ParsedSwapAction { key, target })
+    }
+
+    fn parse_nudge_action(e: &quick_xml::events::BytesStart, macro_key: Option<&str>) -> Result<ParsedNudgeAction, String> {
+        let mut key = macro_key.map(|k| k.to_string());  // Macro steps inherit the Macro's key
+        let mut direction = None;
+        let mut amount = Fraction { num: 1, den: 24 }; // default
+
+        for attr in e.attributes() {
+            let attr = attr.map_err(|e| format!("attribute error: {}", e))?;
+            let value = String::from_utf8_lossy(&attr.value).to_string();
+
+            match attr.key.as_ref() {
+                b"key" => key = Some(value),
+                b"direction" => {
+                    direction = Some(Direction::parse(&value)
+                        .ok_or_else(|| format!("invalid NudgeAction direction: {}", value))?);
+                }
+                b"amount" => amount = Fraction::parse(&value)?,
+                _ => {}
+            }
+        }
+
+        let key = key.ok_or("NudgeAction missing key attribute")?;
+        let direction = direction.ok_or("NudgeAction missing direction attribute")?;
+
+        Ok(ParsedNudgeAction { key, direction, amount })
+    }
+
+    fn parse_resize_action(e: &quick_xml::events::BytesStart, macro_key: Option<&str>) -> Result<ParsedResizeAction, String> {
+        let mut key = macro_key.map(|k| k.to_string());  // Macro steps inherit the Macro's key
+        let mut edge = None;
+        let mut amount = Fraction { num: 1, den: 24 }; // default
+        let mut grow = true; // default
+
+        for attr in e.attributes() {
+            let attr = attr.map_err(|e| format!("attribute error: {}", e))?;
+            let value = String::from_utf8_lossy(&attr.value).to_string();
+
+            match attr.key.as_ref() {
+                b"key" => key = Some(value),
+                b"edge" => {
+                    edge = Some(Edge::parse(&value)
+                        .ok_or_else(|| format!("invalid ResizeAction edge: {}", value))?);
+                }
+                b"amount" => amount = Fraction::parse(&value)?,
+                b"op" => {
+                    grow = match value.as_str() {
+                        "grow" => true,
+                        "shrink" => false,
+                        _ => return Err(format!("invalid ResizeAction op: {}", value)),
+                    };
+                }
+                _ => {}
+            }
+        }
+
+        let key = key.ok_or("ResizeAction missing key attribute")?;
+        let edge = edge.ok_or("ResizeAction missing edge attribute")?;
+
+        Ok(ParsedResizeAction { key, edge, amount, grow })
+    }
+
+    fn parse_macro(reader: &mut Reader<&[u8]>, start: &quick_xml::events::BytesStart) -> Result<ParsedMacro, String> {
+        let mut key = None;
+        let mut on_failure = MacroFailurePolicy::Stop; // default
+
+        for attr in start.attributes() {
+            let attr = attr.map_err(|e| format!("attribute error: {}", e))?;
+            let value = String::from_utf8_lossy(&attr.value).to_string();
+
+            match attr.key.as_ref() {
+                b"key" => key = Some(value),
+                b"onFailure" => {
+                    on_failure = MacroFailurePolicy::parse(&value)
+                        .ok_or_else(|| format!("invalid Macro onFailure: {}", value))?;
+                }
+                _ => {}
+            }
+        }
+
+        let key = key.ok_or("Macro missing key attribute")?;
+
+        let mut steps = Vec::new();
+        let mut buf = Vec::new();
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Empty(ref e)) => steps.push(Self::parse_macro_step(&key, e)?),
+                Ok(Event::Start(ref e)) => {
+                    // <Step ...></Step>: same as the empty form, content is skipped
+                    steps.push(Self::parse_macro_step(&key, e)?);
+                    let end = e.to_end().into_owned();
+                    reader.read_to_end_into(end.name(), &mut Vec::new())
+                        .map_err(|e| format!("XML parse error: {}", e))?;
+                }
+                Ok(Event::End(ref e)) if e.name().as_ref() == b"Macro" => break,
+                Ok(Event::Eof) => return Err("unexpected EOF in Macro".to_string()),
+                Err(e) => return Err(format!("XML parse error: {}", e)),
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        if steps.is_empty() {
+            return Err(format!("Macro key='{}' has no steps", key));
+        }
+
+        Ok(ParsedMacro { key, on_failure, steps })
+    }
+
+    /// One Macro step element (empty or start tag); `key` is the Macro's key
+    fn parse_macro_step(key: &str, e: &quick_xml::events::BytesStart) -> Result<ParsedMacroStep, String> {
+        let element = String::from_utf8_lossy(e.name().as_ref()).to_string();
+        let mut pane = 1; // default (LayoutAction steps only)
+        for attr in e.attributes() {
+            let attr = attr.map_err(|e| format!("attribute error: {}", e))?;
+            match attr.key.as_ref() {
+                b"key" => return Err(format!("Macro key='{}': {} step must not have a key attribute", key, element)),
+                b"pane" if element != "LayoutAction" => {
+                    return Err(format!("Macro key='{}': pane is only valid on LayoutAction steps, not {}", key, element));
+                }
+                b"pane" => {
+                    let value = String::from_utf8_lossy(&attr.value).to_string();
+                    pane = value.parse::<usize>().ok().filter(|p| *p >= 1)
+                        .ok_or_else(|| format!("invalid LayoutAction pane (expected 1 or more): {}", value))?;
+                }
+                _ => {}
+            }
+        }
+
+        let k = Some(key);
+        Ok(match e.name().as_ref() {
+            b"LayoutAction" => ParsedMacroStep::LayoutAction { action: Self::parse_layout_action(e, k)?, pane },
+            b"DisplayMove" => ParsedMacroStep::DisplayMove(Self::parse_display_move(e, k)?),
+            b"FocusMove" => ParsedMacroStep::FocusMove(Self::parse_focus_move(e, k)?),
+            b"FocusMru" => ParsedMacroStep::FocusMru,
+            b"SwapAction" => ParsedMacroStep::SwapAction(Self::parse_swap_action(e, k)?),
+            b"NudgeAction" => ParsedMacroStep::NudgeAction(Self::parse_nudge_action(e, k)?),
+            b"ResizeAction" => ParsedMacroStep::ResizeAction(Self::parse_resize_action(e, k)?),
+            _ => return Err(format!("Macro key='{}': unsupported step <{}>", key, element)),
+        })
+    }
+
+    fn parse_application(reader: &mut Reader<&[u8]>, start: &quick_xml::events::BytesStart) -> Result<ParsedApplication, String> {
+        let mut name = None;
+
+        for attr in start.attributes() {
+            let attr = attr.map_err(|e| format!("attribute error: {}", e))?;
+            if attr.key.as_ref() == b"name" {
+                name = Some(String::from_utf8_lossy(&attr.value).to_string());
+            }
+        }
+
+        let name = name.ok_or("Application missing name attribute")?;
+        let mut mac_bundle_ids = Vec::new();
+        let mut windows_exes = Vec::new();
+        let mut linux_processes = Vec::new();
+        let mut tile_fallback = None;
+        let mut buf = Vec::new();
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Empty(ref e)) => {
+                    match e.name().as_ref() {
+                        b"Mac" => mac_bundle_ids.push(Self::required_attr(e, b"bundleId", "Mac")?),
+                        b"Windows" => windows_exes.push(Self::required_attr(e, b"exe", "Windows")?),
+                        b"Linux" => linux_processes.push(Self::required_attr(e, b"process", "Linux")?),
+                        b"TileFallback" => tile_fallback = Some(Self::parse_tile_fallback(e)?),
+                        // Clipboard overrides are documented in the schema but not read here
+                        _ => {}
+                    }
+                }
+                Ok(Event::End(ref e)) if e.name().as_ref() == b"Application" => break,
+                Ok(Event::Eof) => return Err("unexpected EOF in Application".to_string()),
+                Err(e) => return Err(format!("XML parse error: {}", e)),
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        Ok(ParsedApplication { name, mac_bundle_ids, windows_exes, linux_processes, tile_fallback })
+    }
+
+    fn required_attr(e: &quick_xml::events::BytesStart, name: &[u8], element: &str) -> Result<String, String> {
+        for attr in e.attributes() {
+            let attr = attr.map_err(|e| format!("attribute error: {}", e))?;
+            if attr.key.as_ref() == name {
+                return Ok(String::from_utf8_lossy(&attr.value).to_string());
+            }
+        }
+        Err(format!("{} missing {} attribute", element, String::from_utf8_lossy(name)))
+    }
+
+    fn parse_tile_fallback(e: &quick_xml::events::BytesStart) -> Result<FallbackPolicy, String> {
+        let mut policy = None;
+
+        for attr in e.attributes() {
+            let attr = attr.map_err(|e| format!("attribute error: {}", e))?;
+            let value = String::from_utf8_lossy(&attr.value).to_string();
+
+            if attr.key.as_ref() == b"policy" {
+                policy = Some(FallbackPolicy::parse(&value)
+                    .ok_or_else(|| format!("invalid TileFallback policy: {}", value))?);
+            }
+        }
+
+        policy.ok_or_else(|| "TileFallback missing policy attribute".to_string())
+    }
+
+    fn parse_lint(reader: &mut Reader<&[u8]>) -> Result<Vec<LintSuppression>, String> {
+        let mut suppressions = Vec::new();
+        let mut buf = Vec::new();
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Empty(ref e)) if e.name().as_ref() == b"Suppress" => {
+                    let mut code = None;
+                    let mut target = None;
+                    for attr in e.attributes() {
+                        let attr = attr.map_err(|e| format!("attribute error: {}", e))?;
+                        let value = String::from_utf8_lossy(&attr.value).to_string();
+                        match attr.key.as_ref() {
+                            b"code" => code = Some(value),
+                            b"target" => target = Some(value),
+                            _ => {}
+                        }
+                    }
+                    let code = code.ok_or("Suppress missing code attribute")?;
+                    suppressions.push(LintSuppression { code, target });
+                }
+                Ok(Event::End(ref e)) if e.name().as_ref() == b"Lint" => break,
+                Ok(Event::Eof) => return Err("unexpected EOF in Lint".to_string()),
+                Err(e) => return Err(format!("XML parse error: {}", e)),
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        Ok(suppressions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parse the body of a <Form> element
+    fn parse(body: &str) -> Result<ParsedForm, String> {
+        ParsedForm::from_xml(&format!("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<Form>\n{}\n</Form>", body))
+    }
+
+    fn frac(num: u32, den: u32) -> Fraction {
+        Fraction::new(num, den)
+    }
+
+    fn corners(pane: &ParsedPane) -> (Fraction, Fraction, Fraction, Fraction) {
+        (pane.x, pane.y, pane.width, pane.height)
+    }
+
+    /// Off-center, non-square pane: every transform moves it somewhere distinct
+    fn asymmetric_pane() -> ParsedPane {
+        ParsedPane { x: frac(1, 10), y: frac(1, 5), width: frac(1, 2), height: frac(1, 4) }
+    }
+
+    #[test]
+    fn transforms_map_an_asymmetric_pane() {
+        let pane = asymmetric_pane();
+        assert_eq!(corners(&ShapeTransform::MirrorX.apply(&pane)), (frac(2, 5), frac(1, 5), frac(1, 2), frac(1, 4)));
+        assert_eq!(corners(&ShapeTransform::MirrorY.apply(&pane)), (frac(1, 10), frac(11, 20), frac(1, 2), frac(1, 4)));
+        assert_eq!(corners(&ShapeTransform::Transpose.apply(&pane)), (frac(1, 5), frac(1, 10), frac(1, 4), frac(1, 2)));
+        assert_eq!(corners(&ShapeTransform::Rotate90.apply(&pane)), (frac(11, 20), frac(1, 10), frac(1, 4), frac(1, 2)));
+    }
+
+    #[test]
+    fn transform_list_applies_in_order() {
+        let list = ShapeTransform::parse_list("mirrorX rotate90").unwrap();
+        assert_eq!(list, vec![ShapeTransform::MirrorX, ShapeTransform::Rotate90]);
+
+        let pane = list.iter().fold(asymmetric_pane(), |pane, t| t.apply(&pane));
+        assert_eq!(corners(&pane), (frac(11, 20), frac(2, 5), frac(1, 4), frac(1, 2)));
+
+        assert!(ShapeTransform::parse_list("mirrorX flip").is_err());
+    }
+
+    #[test]
+    fn extends_replaces_shapes_by_id_and_appends_the_rest() {
+        let form = parse(r#"
+  <Frame name="full"><Pane x="0" y="0" width="1" height="1"/></Frame>
+  <Frame name="halves">
+    <Pane x="0" y="0" width="1/2" height="1"/>
+    <Pane x="1/2" y="0" width="1/2" height="1"/>
+  </Frame>
+  <Layout name="base" space="wide">
+    <Shape id="main" frame="full"><Leaf/></Shape>
+    <Shape id="side" frame="full"><Leaf/></Shape>
+  </Layout>
+  <Layout name="child" extends="base" transform="mirrorX">
+    <Shape id="side" frame="halves"><Leaf/><Leaf/></Shape>
+    <Shape frame="full"><Leaf/></Shape>
+  </Layout>"#).unwrap();
+
+        let child = &form.layouts["child"];
+        let shapes = ParsedForm::top_level_shapes(&child.root_shape);
+        let frames: Vec<&str> = shapes.iter().map(|s| s.frame.as_str()).collect();
+        assert_eq!(frames, vec!["full", "halves", "full"]);
+        assert_eq!(shapes[1].id.as_deref(), Some("side"));
+        assert!(shapes.iter().all(|s| s.transforms == vec![ShapeTransform::MirrorX]));
+        assert_eq!(child.space.as_deref(), Some("wide"));
+        assert!(child.transforms.is_empty());
+
+        // The parent keeps its own Shapes, untransformed
+        let base = ParsedForm::top_level_shapes(&form.layouts["base"].root_shape);
+        assert!(base.iter().all(|s| s.frame == "full" && s.transforms.is_empty()));
+    }
+
+    const HALVES: &str = r#"
+  <Frame name="halves">
+    <Pane x="0" y="0" width="1/2" height="1"/>
+    <Pane x="1/2" y="0" width="1/2" height="1"/>
+  </Frame>
+  <Layout name="halves">
+    <Shape frame="halves"><Leaf/><Leaf/></Shape>
+  </Layout>"#;
+
+    #[test]
+    fn macro_steps_parse_in_empty_and_start_form() {
+        let form = parse(&format!(r#"{}
+  <Macro key="m" onFailure="continue">
+    <LayoutAction layout="halves" pane="2"></LayoutAction>
+    <FocusMove direction="left"/>
+    <NudgeAction direction="up" amount="1/20"></NudgeAction>
+  </Macro>"#, HALVES)).unwrap();
+
+        let m = &form.macros[0];
+        assert_eq!((m.key.as_str(), m.on_failure), ("m", MacroFailurePolicy::Continue));
+        assert_eq!(m.steps.len(), 3);
+        assert!(matches!(&m.steps[0], ParsedMacroStep::LayoutAction { action, pane: 2 }
+            if action.key == "m" && action.layout == "halves"));
+        assert!(matches!(&m.steps[1], ParsedMacroStep::FocusMove(fm) if fm.direction == Direction::Left));
+        assert!(matches!(&m.steps[2], ParsedMacroStep::NudgeAction(na)
+            if na.direction == Direction::Up && na.amount == frac(1, 20)));
+    }
+
+    #[test]
+    fn macro_rejects_unknown_steps_and_misplaced_pane() {
+        let unknown = parse(r#"
+  <Macro key="m"><FocusMru/><Teleport></Teleport></Macro>"#);
+        assert!(matches!(unknown, Err(ref e) if e.contains("unsupported step <Teleport>")));
+
+        let pane = parse(r#"
+  <Macro key="m"><FocusMove direction="left" pane="2"/></Macro>"#);
+        assert!(matches!(pane, Err(ref e) if e.contains("pane is only valid on LayoutAction steps")));
+    }
+
+    #[test]
+    fn extends_cycle_is_an_error() {
+        let result = parse(r#"
+  <Frame name="full"><Pane x="0" y="0" width="1" height="1"/></Frame>
+  <Layout name="a" extends="b"/>
+  <Layout name="b" extends="a"/>"#);
+        match result {
+            Err(e) => assert!(e.contains("Layout inheritance cycle"), "{}", e),
+            Ok(_) => panic!("cycle accepted"),
+        }
     }
 }
@@ -1,16 +1,16 @@
 // SPDX-License-Identifier: Apache-2.0
 // Copyright 2025 Scale Invariant
 
-/// Form Resolution and Runtime Construction
-/// Transforms parsed layout configurations into runtime-ready structures
-///
-/// This module handles:
-/// - Quirk matching and display detection
-/// - Conditional evaluation (IncludeCondition, Space matching)
-/// - Reference resolution (frames, measures, layouts)
-/// - Shape tree processing and flattening
-/// - Conversion from ParsedForm → Form
-/// - Runtime layout computation API
+//! Form Resolution and Runtime Construction
+//! Transforms parsed layout configurations into runtime-ready structures
+//!
+//! This module handles:
+//! - Quirk matching and display detection
+//! - Conditional evaluation (IncludeCondition, Space matching)
+//! - Reference resolution (frames, measures, layouts)
+//! - Shape tree processing and flattening
+//! - Conversion from ParsedForm → Form
+//! - Runtime layout computation API
 
 use std::collections::HashMap;
 
@@ -18,10 +18,13 @@ use std::collections::HashMap;
 use crate::pbmbd_display::DisplayInfo;
 
 // Import types from sibling modules
-use crate::pbgft_types::{DisplayProps, PaneFrac, DisplayMoveTarget, LayoutSession, DisplayMoveSession};
-use crate::pbgfp_parse::{ParsedForm, ParsedSpace, ParsedFrame, ParsedPane, ParsedShape,
+use crate::pbgft_types::{DisplayProps, PaneFrac, PixelRect, DisplayPlacement, Direction, SwapTarget, NudgeSpec, ResizeSpec, FallbackPolicy, DisplayMoveTarget, DisplayMoveSpec, MacroSpec, MacroStep, LayoutSession, DisplayMoveSession, TilingConfig};
+use crate::pbgg_geometry::{display_in_direction, spatial_order};
+use crate::pbgam_appmin::MIN_PANE_SIZE;
+use crate::pbgfp_parse::{ParsedForm, ParsedSpace, ParsedFrame, ParsedPane, ParsedShape, ParsedApplication, Platform,
+                          ParsedLayoutAction, ParsedMacroStep,
                           Orientation, MeasureRef, SpaceRule, ShapeChild,
-                          IncludeCondition, TraverseOrder, MirrorMode, Fraction};
+                          IncludeCondition, TraverseOrder, MirrorMode, Fraction, ShapeTransform};
 
 // Use platform-specific or generic types depending on target
 #[cfg(not(target_os = "macos"))]
@@ -33,17 +36,19 @@ use crate::pbgft_types::DisplayInfo;
 
 /// Runtime layout configuration (stores layout data for on-demand computation)
 struct RuntimeLayout {
-    space: Option<String>,
+    name: String,
+    space: Option<String>,  // Guard: LayoutAction space, else the Layout's own Space
     root_shape: ParsedShape,
     traverse: TraverseOrder,
     mirror_x: MirrorMode,
     mirror_y: MirrorMode,
+    dedupe: bool,
 }
 
 /// Main runtime form - immutable configuration provider
 pub struct Form {
-    // Layout configurations: key_name → layout data
-    layouts: HashMap<String, RuntimeLayout>,
+    // Layout configurations: key_name → Space-guarded bindings in document order
+    layouts: HashMap<String, Vec<RuntimeLayout>>,
 
     // Parsed data needed for runtime computation
     spaces: HashMap<String, ParsedSpace>,
@@ -51,7 +56,27 @@ pub struct Form {
     measures: HashMap<String, u32>,
 
     // DisplayMove bindings: key_name → target spec
-    display_moves: HashMap<String, DisplayMoveTarget>,
+    display_moves: HashMap<String, DisplayMoveSpec>,
+
+    // FocusMove bindings: key_name → direction
+    focus_moves: HashMap<String, Direction>,
+
+    // SwapAction bindings: key_name → swap partner selection
+    swap_actions: HashMap<String, SwapTarget>,
+
+    // NudgeAction / ResizeAction bindings (accept autorepeat)
+    nudge_actions: HashMap<String, NudgeSpec>,
+    resize_actions: HashMap<String, ResizeSpec>,
+
+    // Macro bindings: key_name → ordered steps
+    macros: HashMap<String, MacroSpec>,
+
+    // Size-constraint fallback: Form-level default plus per-Application overrides
+    tile_fallback: FallbackPolicy,
+    applications: Vec<ParsedApplication>,
+
+    // Pane lists per binding and display signature (built at load, cleared on display change)
+    pane_cache: HashMap<String, Vec<CachedPanes>>,
 
     // Current layout session state (ephemeral, reset on chord release)
     layout_session: Option<LayoutSession>,
@@ -60,6 +85,21 @@ pub struct Form {
     display_move_session: Option<DisplayMoveSession>,
 }
 
+/// Precomputed pane list for one binding on one display
+/// Keyed by the display's design size and name (the inputs Space and Include conditions see)
+struct CachedPanes {
+    display: DisplayProps,
+    panes: Option<Vec<PaneFrac>>,
+}
+
+impl CachedPanes {
+    fn matches(&self, display: &DisplayProps) -> bool {
+        self.display.width == display.width
+            && self.display.height == display.height
+            && self.display.name == display.name
+    }
+}
+
 // ============================================================================
 // Conditional evaluation for Include
 // ============================================================================
@@ -133,6 +173,33 @@ impl ParsedForm {
                 errors.push(format!("LayoutAction key='{}' references undefined Layout '{}'",
                     action.key, action.layout));
             }
+            if let Some(ref space_name) = action.space {
+                if !self.spaces.contains_key(space_name) {
+                    errors.push(format!("LayoutAction key='{}' references undefined Space '{}'",
+                        action.key, space_name));
+                }
+            }
+        }
+
+        // Validate per-key binding order: first matching Space wins, so an unguarded
+        // binding (or a repeated guard) hides every later binding for that key
+        let mut seen_guards: Vec<(&str, Option<&str>, &str)> = Vec::new();  // (key, guard, layout)
+        for action in &self.layout_actions {
+            let guard = action.space.as_deref()
+                .or_else(|| self.layouts.get(&action.layout).and_then(|l| l.space.as_deref()));
+            let shadowing = seen_guards.iter()
+                .find(|(key, earlier_guard, _)| *key == action.key && (earlier_guard.is_none() || *earlier_guard == guard));
+            if let Some((_, earlier_guard, earlier_layout)) = shadowing {
+                match earlier_guard {
+                    None => errors.push(format!(
+                        "LayoutAction key='{}' layout '{}' is unreachable: earlier unguarded binding (layout '{}') matches every display",
+                        action.key, action.layout, earlier_layout)),
+                    Some(g) => errors.push(format!(
+                        "LayoutAction key='{}' layout '{}' is unreachable: earlier binding (layout '{}') has the same Space '{}'",
+                        action.key, action.layout, earlier_layout, g)),
+                }
+            }
+            seen_guards.push((&action.key, guard, &action.layout));
         }
 
         // Validate Layout → Space references and Needs/Measure consistency
@@ -167,6 +234,44 @@ impl ParsedForm {
             }
         }
 
+        // Validate Nudge/Resize step sizes (fraction of display, must be in (0, 1])
+        for na in &self.nudge_actions {
+            if na.amount.num == 0 || na.amount.num > na.amount.den {
+                errors.push(format!("NudgeAction key='{}' amount must be in (0, 1]", na.key));
+            }
+        }
+        for ra in &self.resize_actions {
+            if ra.amount.num == 0 || ra.amount.num > ra.amount.den {
+                errors.push(format!("ResizeAction key='{}' amount must be in (0, 1]", ra.key));
+            }
+        }
+
+        // Validate Macro steps (same rules as the top-level bindings they mirror)
+        for m in &self.macros {
+            for step in &m.steps {
+                match step {
+                    ParsedMacroStep::LayoutAction { action, .. } if !self.layouts.contains_key(&action.layout) => {
+                        errors.push(format!("Macro key='{}' LayoutAction references undefined Layout '{}'",
+                            m.key, action.layout));
+                    }
+                    ParsedMacroStep::NudgeAction(na) if na.amount.num == 0 || na.amount.num > na.amount.den => {
+                        errors.push(format!("Macro key='{}' NudgeAction amount must be in (0, 1]", m.key));
+                    }
+                    ParsedMacroStep::ResizeAction(ra) if ra.amount.num == 0 || ra.amount.num > ra.amount.den => {
+                        errors.push(format!("Macro key='{}' ResizeAction amount must be in (0, 1]", m.key));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // Validate Application matchers (at least one platform matcher required)
+        for app in &self.applications {
+            if app.mac_bundle_ids.is_empty() && app.windows_exes.is_empty() && app.linux_processes.is_empty() {
+                errors.push(format!("Application '{}' has no Mac/Windows/Linux matcher", app.name));
+            }
+        }
+
         if errors.is_empty() {
             Ok(())
         } else {
@@ -250,21 +355,74 @@ impl ParsedForm {
 
         // Build DisplayMove bindings
         for dm in &self.display_moves {
-            display_moves.insert(dm.key.clone(), dm.target.clone());
+            display_moves.insert(dm.key.clone(), DisplayMoveSpec {
+                target: dm.target.clone(),
+                mode: dm.mode,
+            });
         }
 
+        // Build FocusMove bindings
+        let focus_moves = self.focus_moves.iter()
+            .map(|fm| (fm.key.clone(), fm.direction))
+            .collect();
+
+        // Build SwapAction bindings
+        let swap_actions = self.swap_actions.iter()
+            .map(|sa| (sa.key.clone(), sa.target))
+            .collect();
+
+        // Build NudgeAction / ResizeAction bindings
+        let nudge_actions = self.nudge_actions.iter()
+            .map(|na| (na.key.clone(), NudgeSpec { direction: na.direction, amount: na.amount.to_f64() }))
+            .collect();
+        let resize_actions = self.resize_actions.iter()
+            .map(|ra| (ra.key.clone(), ResizeSpec { edge: ra.edge, amount: ra.amount.to_f64(), grow: ra.grow }))
+            .collect();
+
         // Build RuntimeLayout for each LayoutAction
         for action in &self.layout_actions {
-            if let Some(layout) = self.layouts.get(&action.layout) {
-                let runtime_layout = RuntimeLayout {
-                    space: layout.space.clone(),
-                    root_shape: layout.root_shape.clone(),
-                    traverse: action.traverse,
-                    mirror_x: action.mirror_x,
-                    mirror_y: action.mirror_y,
+            if let Some(runtime_layout) = self.runtime_layout(action) {
+                layouts.entry(action.key.clone()).or_insert_with(Vec::new).push(runtime_layout);
+            }
+        }
+
+        // Build Macro bindings; LayoutAction/DisplayMove steps get synthetic binding names
+        // (cannot collide with key names) so the keyed lookup paths serve them unchanged
+        let mut macros = HashMap::new();
+        for m in &self.macros {
+            let mut steps = Vec::new();
+            for (idx, step) in m.steps.iter().enumerate() {
+                let binding = format!("macro:{}#{}", m.key, idx + 1);
+                let runtime_step = match step {
+                    ParsedMacroStep::LayoutAction { action, pane } => {
+                        if let Some(runtime_layout) = self.runtime_layout(action) {
+                            layouts.insert(binding.clone(), vec![runtime_layout]);
+                        }
+                        MacroStep::LayoutAction { binding, pane: pane - 1 }
+                    }
+                    ParsedMacroStep::DisplayMove(dm) => {
+                        display_moves.insert(binding.clone(), DisplayMoveSpec {
+                            target: dm.target.clone(),
+                            mode: dm.mode,
+                        });
+                        MacroStep::DisplayMove { binding }
+                    }
+                    ParsedMacroStep::FocusMove(fm) => MacroStep::FocusMove(fm.direction),
+                    ParsedMacroStep::FocusMru => MacroStep::FocusMru,
+                    ParsedMacroStep::SwapAction(sa) => MacroStep::SwapAction(sa.target),
+                    ParsedMacroStep::NudgeAction(na) => MacroStep::Nudge(NudgeSpec {
+                        direction: na.direction,
+                        amount: na.amount.to_f64(),
+                    }),
+                    ParsedMacroStep::ResizeAction(ra) => MacroStep::Resize(ResizeSpec {
+                        edge: ra.edge,
+                        amount: ra.amount.to_f64(),
+                        grow: ra.grow,
+                    }),
                 };
-                layouts.insert(action.key.clone(), runtime_layout);
+                steps.push(runtime_step);
             }
+            macros.insert(m.key.clone(), MacroSpec { steps, on_failure: m.on_failure });
         }
 
         Form {
@@ -273,10 +431,32 @@ impl ParsedForm {
             frames: self.frames.clone(),
             measures: self.measures.clone(),
             display_moves,
+            focus_moves,
+            swap_actions,
+            nudge_actions,
+            resize_actions,
+            macros,
+            tile_fallback: self.tile_fallback.unwrap_or(FallbackPolicy::Center),
+            applications: self.applications.clone(),
+            pane_cache: HashMap::new(),
             layout_session: None,
             display_move_session: None,
         }
     }
+
+    /// Runtime layout data for a LayoutAction (top-level or Macro step)
+    fn runtime_layout(&self, action: &ParsedLayoutAction) -> Option<RuntimeLayout> {
+        let layout = self.layouts.get(&action.layout)?;
+        Some(RuntimeLayout {
+            name: action.layout.clone(),
+            space: action.space.clone().or_else(|| layout.space.clone()),
+            root_shape: layout.root_shape.clone(),
+            traverse: action.traverse,
+            mirror_x: action.mirror_x,
+            mirror_y: action.mirror_y,
+            dedupe: action.dedupe,
+        })
+    }
 }
 
 // ============================================================================
@@ -291,6 +471,14 @@ impl Form {
             frames: HashMap::new(),
             measures: HashMap::new(),
             display_moves: HashMap::new(),
+            focus_moves: HashMap::new(),
+            swap_actions: HashMap::new(),
+            nudge_actions: HashMap::new(),
+            resize_actions: HashMap::new(),
+            macros: HashMap::new(),
+            tile_fallback: FallbackPolicy::Center,
+            applications: Vec::new(),
+            pane_cache: HashMap::new(),
             layout_session: None,
             display_move_session: None,
         }
@@ -308,28 +496,51 @@ impl Form {
             }
         };
 
-        // Parse XML
-        let parsed = match crate::pbgfp_parse::ParsedForm::from_xml(&xml) {
-            Ok(p) => p,
-            Err(e) => {
-                eprintln!("FORM: ERROR failed to parse config: {}", e);
+        let (parsed, mut form) = match Self::parse_and_build(&xml, displays) {
+            Ok(built) => built,
+            Err(errors) => {
+                eprintln!("FORM: ERROR invalid config:");
+                for error in errors {
+                    eprintln!("  {}", error);
+                }
                 eprintln!("FORM: Returning empty Form");
                 return Self::empty();
             }
         };
 
-        // Validate
-        if let Err(errors) = parsed.validate() {
-            eprintln!("FORM: ERROR validation failed:");
-            for error in errors {
-                eprintln!("  {}", error);
-            }
-            eprintln!("FORM: Returning empty Form");
-            return Self::empty();
+        // Non-fatal: quality warnings (unused definitions, unreachable panes, key conflicts)
+        let display_props: Vec<DisplayProps> = displays.iter()
+            .map(|d| DisplayProps {
+                width: d.design_width,
+                height: d.design_height,
+                name: d.name.clone(),
+            })
+            .collect();
+        // Precompute pane lists so key presses don't flatten Shape trees
+        form.rebuild_pane_cache(&display_props);
+
+        let report = crate::pbgfl_lint::lint_form(&parsed, &form, &display_props);
+        for diagnostic in &report.diagnostics {
+            eprintln!("FORM: LINT {}", diagnostic);
+        }
+        if !report.diagnostics.is_empty() || report.suppressed > 0 {
+            eprintln!("FORM: LINT {} warnings ({} suppressed)", report.diagnostics.len(), report.suppressed);
         }
 
+        form
+    }
+
+    /// Parse, validate and build a Form from XML
+    /// Returns the parse-time structures too (for lint); Err holds every parse/validation error
+    pub(crate) fn parse_and_build(xml: &str, displays: &[DisplayInfo]) -> Result<(ParsedForm, Form), Vec<String>> {
+        let parsed = ParsedForm::from_xml(xml)
+            .map_err(|e| vec![format!("failed to parse config: {}", e)])?;
+
+        parsed.validate()?;
+
         // Build runtime (DisplayQuirk is deprecated; symmetric viewport handles adjustments)
-        parsed.build_runtime(displays)
+        let form = parsed.build_runtime(displays);
+        Ok((parsed, form))
     }
 
     // Private helper methods (moved from ParsedForm)
@@ -433,6 +644,37 @@ impl Form {
             }
         }
 
+        // Transformed Shape: flatten in its own unit box, transform, then place in the parent pane
+        if !shape.transforms.is_empty() {
+            let full = Fraction { num: 1, den: 1 };
+            let zero = Fraction { num: 0, den: 1 };
+            for leaf in self.flatten_shape_body(shape, display, &zero, &zero, &full, &full) {
+                let t = shape.transforms.iter().fold(leaf, |pane, transform| transform.apply(&pane));
+                leaves.push(ParsedPane {
+                    x: parent_x.add(&parent_width.mul(&t.x)),
+                    y: parent_y.add(&parent_height.mul(&t.y)),
+                    width: parent_width.mul(&t.width),
+                    height: parent_height.mul(&t.height),
+                });
+            }
+            return leaves;
+        }
+
+        self.flatten_shape_body(shape, display, parent_x, parent_y, parent_width, parent_height)
+    }
+
+    /// Flatten a Shape's frame and children (orientation and transforms handled by caller)
+    fn flatten_shape_body(
+        &self,
+        shape: &ParsedShape,
+        display: &DisplayProps,
+        parent_x: &Fraction,
+        parent_y: &Fraction,
+        parent_width: &Fraction,
+        parent_height: &Fraction,
+    ) -> Vec<ParsedPane> {
+        let mut leaves = Vec::new();
+
         // Special case: synthetic "__multi__" frame for multiple top-level shapes
         if shape.frame == "__multi__" {
             for child in &shape.children {
@@ -504,7 +746,7 @@ impl Form {
 
                     // Check if this Include references a layout for further subdivision
                     if let Some(ref layout_name) = include.layout {
-                        if let Some(layout) = self.layouts.get(layout_name) {
+                        if let Some(layout) = self.layouts.get(layout_name).and_then(|bindings| bindings.first()) {
                             // Recurse into the referenced layout's structure
                             let layout_leaves = self.flatten_shape_tree(
                                 &layout.root_shape,
@@ -534,21 +776,52 @@ impl Form {
         leaves
     }
 
-    fn apply_mirroring_fracs(&self, panes: &mut [PaneFrac], mirror_x: MirrorMode, mirror_y: MirrorMode) {
+    /// Flatten a layout's shape tree for a display and apply the action's mirroring
+    fn mirrored_leaf_panes(&self, layout: &RuntimeLayout, display: &DisplayProps) -> Vec<ParsedPane> {
+        let full = Fraction { num: 1, den: 1 };
+        let zero = Fraction { num: 0, den: 1 };
+        let mut leaf_panes = self.flatten_shape_tree(
+            &layout.root_shape,
+            display,
+            &zero,    // parent x = 0
+            &zero,    // parent y = 0
+            &full,    // parent width = 1
+            &full,    // parent height = 1
+        );
+        self.apply_mirroring(&mut leaf_panes, layout.mirror_x, layout.mirror_y);
+        leaf_panes
+    }
+
+    fn apply_mirroring(&self, panes: &mut [ParsedPane], mirror_x: MirrorMode, mirror_y: MirrorMode) {
         for pane in panes.iter_mut() {
             if mirror_x == MirrorMode::Flip {
-                // x' = 1.0 - x - width (fractional space)
-                pane.x = 1.0 - pane.x - pane.width;
+                // x' = 1 - x - width (exact fractional space)
+                *pane = ShapeTransform::MirrorX.apply(pane);
             }
 
             if mirror_y == MirrorMode::Flip {
-                // y' = 1.0 - y - height (fractional space)
-                pane.y = 1.0 - pane.y - pane.height;
+                // y' = 1 - y - height (exact fractional space)
+                *pane = ShapeTransform::MirrorY.apply(pane);
+            }
+        }
+    }
+
+    /// Remove panes whose reduced Fraction rect repeats an earlier one; returns count removed
+    /// Identical rects have identical sort keys, so keeping the first occurrence here
+    /// yields the same sequence as keeping the first by sort order.
+    pub(crate) fn dedupe_panes(panes: &mut Vec<ParsedPane>) -> usize {
+        let before = panes.len();
+        let mut kept: Vec<ParsedPane> = Vec::with_capacity(before);
+        for pane in panes.drain(..) {
+            if !kept.contains(&pane) {
+                kept.push(pane);
             }
         }
+        *panes = kept;
+        before - panes.len()
     }
 
-    fn sort_pane_list_fracs(&self, panes: &mut Vec<PaneFrac>, traverse: TraverseOrder) {
+    fn sort_pane_list_fracs(&self, panes: &mut [PaneFrac], traverse: TraverseOrder) {
         // Decode traverse order
         let (primary_axis, primary_dir, secondary_axis, secondary_dir) = match traverse {
             TraverseOrder::XfYf => ('x', 1, 'y', 1),
@@ -620,36 +893,49 @@ impl Form {
         displays.to_vec()
     }
 
-    /// Compute fractional panes for a given action and display
-    /// Returns None if key not found or no panes after conditional pruning
-    pub fn panes_for_action(&self, key: &str, display: &DisplayProps) -> Option<Vec<PaneFrac>> {
-        let layout = self.layouts.get(key)?;
-
-        // Check if Layout's Space matches this display
-        if let Some(ref space_name) = layout.space {
-            if let Some(space) = self.spaces.get(space_name) {
-                if !self.space_matches_display(space, display) {
-                    return None; // Space doesn't match
-                }
+    /// Binding for a key on a display: the first LayoutAction whose Space guard matches
+    /// Unguarded bindings match every display (validation keeps them last and unique)
+    fn layout_for_display(&self, key: &str, display: &DisplayProps) -> Option<&RuntimeLayout> {
+        self.layouts.get(key)?.iter().find(|layout| {
+            match layout.space.as_ref().and_then(|name| self.spaces.get(name)) {
+                Some(space) => self.space_matches_display(space, display),
+                None => true,
             }
-        }
+        })
+    }
 
-        // Flatten shape tree to leaf panes using pure rational arithmetic
-        let full = Fraction { num: 1, den: 1 };
-        let zero = Fraction { num: 0, den: 1 };
-        let leaf_panes = self.flatten_shape_tree(
-            &layout.root_shape,
-            display,
-            &zero,    // parent x = 0
-            &zero,    // parent y = 0
-            &full,    // parent width = 1
-            &full,    // parent height = 1
-        );
+    /// Exact (Fraction) leaf panes of a LayoutAction on a display, before dedupe and sorting
+    /// None if the key is unbound or no binding's Space matches; no logging (used by lint)
+    pub(crate) fn exact_panes_for_action(&self, key: &str, display: &DisplayProps) -> Option<Vec<ParsedPane>> {
+        let layout = self.layout_for_display(key, display)?;
+
+        // Flatten and mirror using pure rational arithmetic
+        Some(self.mirrored_leaf_panes(layout, display))
+    }
+
+    /// Layout name and dedupe setting a LayoutAction key resolves to on a display
+    pub(crate) fn layout_action_info(&self, key: &str, display: &DisplayProps) -> Option<(&str, bool)> {
+        self.layout_for_display(key, display).map(|layout| (layout.name.as_str(), layout.dedupe))
+    }
+
+    /// Compute fractional panes for a given action and display (flatten, dedupe, sort)
+    /// Returns None if key not found, no binding's Space matches, or no panes after conditional pruning
+    fn compute_panes(&self, key: &str, display: &DisplayProps) -> Option<Vec<PaneFrac>> {
+        let layout = self.layout_for_display(key, display)?;
+        let mut leaf_panes = self.exact_panes_for_action(key, display)?;
 
         if leaf_panes.is_empty() {
             return None;
         }
 
+        // Collapse identical rects (exact Fraction comparison; survivors keep sort order)
+        if layout.dedupe {
+            let removed = Self::dedupe_panes(&mut leaf_panes);
+            if removed > 0 {
+                eprintln!("LAYOUT: key='{}' collapsed {} duplicate panes", key, removed);
+            }
+        }
+
         eprintln!("LAYOUT: key='{}' → {} panes on display '{}'", key, leaf_panes.len(), display.name);
 
         // Convert ParsedPane (fractions) to PaneFrac
@@ -662,48 +948,148 @@ impl Form {
             })
             .collect();
 
-        // Apply mirroring in fractional space
-        self.apply_mirroring_fracs(&mut frac_panes, layout.mirror_x, layout.mirror_y);
-
         // Sort by area descending, then by traverse order
         self.sort_pane_list_fracs(&mut frac_panes, layout.traverse);
 
+        // Logged once per display signature; key presses only count
+        let floor = (MIN_PANE_SIZE, MIN_PANE_SIZE);
+        let below_floor = frac_panes.iter().filter(|p| !Self::pane_fits(p, display, floor)).count();
+        if below_floor > 0 {
+            eprintln!("LAYOUT: key='{}' drops {} panes below {:.0}pt on display '{}'",
+                key, below_floor, MIN_PANE_SIZE, display.name);
+        }
+
         Some(frac_panes)
     }
 
-    /// Get next pane with MRU session tracking
-    /// Returns (fractional pane, index) or None if no panes available
-    pub fn get_next_pane(&mut self, key: &str, display: &DisplayProps) -> Option<(PaneFrac, usize)> {
-        let pane_list = self.panes_for_action(key, display)?;
+    /// Precompute pane lists for every binding on every display
+    /// Called at load and after a display change; a later miss (new display signature) is computed on demand
+    pub fn rebuild_pane_cache(&mut self, displays: &[DisplayProps]) {
+        let mut cache: HashMap<String, Vec<CachedPanes>> = HashMap::new();
+        for key in self.layouts.keys() {
+            let entries = displays.iter()
+                .map(|display| CachedPanes { display: display.clone(), panes: self.compute_panes(key, display) })
+                .collect();
+            cache.insert(key.clone(), entries);
+        }
+        self.pane_cache = cache;
+    }
 
-        if pane_list.is_empty() {
+    /// Cached pane list: outer None = not cached, inner None = no panes on that display
+    fn cached_panes(&self, key: &str, display: &DisplayProps) -> Option<Option<&[PaneFrac]>> {
+        self.pane_cache.get(key)?.iter()
+            .find(|entry| entry.matches(display))
+            .map(|entry| entry.panes.as_deref())
+    }
+
+    /// Fractional panes for a given action and display (from the cache when present)
+    pub fn panes_for_action(&self, key: &str, display: &DisplayProps) -> Option<Vec<PaneFrac>> {
+        match self.cached_panes(key, display) {
+            Some(panes) => panes.map(|p| p.to_vec()),
+            None => self.compute_panes(key, display),
+        }
+    }
+
+    /// Minimum size a pane must fit to be in the cycle, and how many panes qualify
+    /// Filtering happens here, before index selection, so every press lands on a usable pane:
+    /// - Panes below the global MIN_PANE_SIZE floor are always dropped
+    /// - Panes below the app's minimum are skipped, unless none would remain
+    ///   (then the key still works and the size fallback policy applies)
+    ///
+    /// Duplicate rects are already collapsed by compute_panes, unless the LayoutAction
+    /// sets dedupe="false" (then they stay in the cycle; lint warns with duplicate-pane)
+    /// Counts only (no allocation, no logging): used on the key press path.
+    /// Floor drops are logged by compute_panes when the list is cached.
+    fn cycle_threshold(panes: &[PaneFrac], display: &DisplayProps, min_size: (f64, f64)) -> Option<((f64, f64), usize)> {
+        let floor = (MIN_PANE_SIZE, MIN_PANE_SIZE);
+        let usable = panes.iter().filter(|p| Self::pane_fits(p, display, floor)).count();
+        if usable == 0 {
             return None;
         }
 
-        // Check if we're continuing the same session
-        let pane_index = if let Some(ref session) = self.layout_session {
-            if session.current_key == key {
-                // Continue session, advance index
-                session.pane_index % pane_list.len()
-            } else {
-                // Different key, start new session
-                0
+        let app_min = (min_size.0.max(floor.0), min_size.1.max(floor.1));
+        let fitting = panes.iter().filter(|p| Self::pane_fits(p, display, app_min)).count();
+        if fitting == 0 {
+            return Some((floor, usable));
+        }
+
+        Some((app_min, fitting))
+    }
+
+    /// True if a pane is at least `min` points on this display
+    /// 1pt tolerance absorbs rounding in design-size scaling
+    fn pane_fits(p: &PaneFrac, display: &DisplayProps, min: (f64, f64)) -> bool {
+        p.width * display.width + 1.0 >= min.0 && p.height * display.height + 1.0 >= min.1
+    }
+
+    /// Compute the pane cycle a key press walks for an app needing at least `min_size` (points)
+    /// See cycle_threshold for the filtering rules
+    pub fn pane_cycle(&self, key: &str, display: &DisplayProps, min_size: (f64, f64)) -> Option<Vec<PaneFrac>> {
+        let computed;
+        let panes: &[PaneFrac] = match self.cached_panes(key, display) {
+            Some(cached) => cached?,
+            None => {
+                computed = self.compute_panes(key, display)?;
+                &computed
             }
-        } else {
-            // First press, start at index 0
-            0
         };
 
-        let pane = pane_list[pane_index].clone();
-        let next_index = (pane_index + 1) % pane_list.len();
+        let (min, _) = Self::cycle_threshold(panes, display, min_size)?;
+        Some(panes.iter().filter(|p| Self::pane_fits(p, display, min)).cloned().collect())
+    }
+
+    /// Get next pane with MRU session tracking
+    /// Returns (fractional pane, index) or None if no panes available
+    /// Served from the pane cache without allocating (a cache miss computes and stores the list)
+    pub fn get_next_pane(&mut self, key: &str, display: &DisplayProps, min_size: (f64, f64)) -> Option<(PaneFrac, usize)> {
+        if self.cached_panes(key, display).is_none() {
+            let panes = self.compute_panes(key, display);
+            self.pane_cache.entry(key.to_string()).or_default()
+                .push(CachedPanes { display: display.clone(), panes });
+        }
+
+        let (pane, pane_index, count) = {
+            let panes = self.cached_panes(key, display)??;
+            let (min, count) = Self::cycle_threshold(panes, display, min_size)?;
+
+            // Check if we're continuing the same session
+            let pane_index = match self.layout_session {
+                // Continue session, advance index
+                Some(ref session) if session.current_key == key => session.pane_index % count,
+                // First press or different key: start at index 0
+                _ => 0,
+            };
+
+            let pane = panes.iter().filter(|p| Self::pane_fits(p, display, min)).nth(pane_index)?.clone();
+            (pane, pane_index, count)
+        };
+
+        let next_index = (pane_index + 1) % count;
 
-        // Update session
+        // Update session (key string only allocated when a new session starts)
+        match self.layout_session {
+            Some(ref mut session) if session.current_key == key => session.pane_index = next_index,
+            _ => {
+                self.layout_session = Some(LayoutSession {
+                    current_key: key.to_string(),
+                    pane_index: next_index,
+                });
+            }
+        }
+
+        Some((pane, pane_index))
+    }
+
+    /// Continue the layout session after `pane_index` instead of the pane last returned
+    /// Used when a size fallback skips ahead in the cycle (next-larger policy)
+    pub fn skip_layout_session_to(&mut self, key: &str, pane_index: usize, pane_count: usize) {
+        if pane_count == 0 {
+            return;
+        }
         self.layout_session = Some(LayoutSession {
             current_key: key.to_string(),
-            pane_index: next_index,
+            pane_index: (pane_index + 1) % pane_count,
         });
-
-        Some((pane, pane_index))
     }
 
     /// Reset layout session (called on chord release)
@@ -723,15 +1109,16 @@ impl Form {
     }
 
     /// Store original size and position for display move session (called on first move in chord)
-    /// offset_x/offset_y are relative to the viewport origin
+    /// offset_x/offset_y are relative to the viewport origin; frac is the same rect as viewport fractions
     /// Returns true if this is a new session (first move), false if session already exists
-    pub fn start_display_move_session(&mut self, original_width: f64, original_height: f64, offset_x: f64, offset_y: f64) -> bool {
+    pub fn start_display_move_session(&mut self, original_width: f64, original_height: f64, offset_x: f64, offset_y: f64, frac: PaneFrac) -> bool {
         if self.display_move_session.is_none() {
             eprintln!("DISPLAYMOVE: Starting session, storing original size ({:.0}x{:.0}) offset ({:.0},{:.0})",
                 original_width, original_height, offset_x, offset_y);
             self.display_move_session = Some(DisplayMoveSession {
                 original_size: Some((original_width, original_height)),
                 original_offset: Some((offset_x, offset_y)),
+                original_frac: Some(frac),
             });
             true
         } else {
@@ -749,51 +1136,310 @@ impl Form {
         self.display_move_session.as_ref().and_then(|s| s.original_offset)
     }
 
+    /// Get original fractional rect stored at start of display move session
+    pub fn get_original_frac(&self) -> Option<PaneFrac> {
+        self.display_move_session.as_ref().and_then(|s| s.original_frac.clone())
+    }
+
     /// Check if a key has a LayoutAction binding
     pub fn has_layout_action(&self, key: &str) -> bool {
         self.layouts.contains_key(key)
     }
 
     /// Check if a key has a DisplayMove binding
-    #[allow(dead_code)] // Public API, may be used by future callers
     pub fn has_display_move(&self, key: &str) -> bool {
         self.display_moves.contains_key(key)
     }
 
+    /// Get the FocusMove direction bound to a key
+    pub fn focus_move_direction(&self, key: &str) -> Option<Direction> {
+        self.focus_moves.get(key).copied()
+    }
+
+    /// Get the Macro bound to a key
+    pub fn macro_spec(&self, key: &str) -> Option<MacroSpec> {
+        self.macros.get(key).cloned()
+    }
+
+    /// Get the SwapAction target bound to a key
+    pub fn swap_action_target(&self, key: &str) -> Option<SwapTarget> {
+        self.swap_actions.get(key).copied()
+    }
+
+    /// Get the auto-tiling configuration for a display
+    /// First Space (by name) that has <AutoTile> and matches the display wins
+    pub(crate) fn auto_tile_for_display(&self, display: &DisplayProps) -> Option<TilingConfig> {
+        let mut names: Vec<&String> = self.spaces.iter()
+            .filter(|(_, space)| space.auto_tile.is_some())
+            .map(|(name, _)| name)
+            .collect();
+        names.sort();
+
+        names.into_iter()
+            .map(|name| &self.spaces[name])
+            .find(|space| self.space_matches_display(space, display))
+            .and_then(|space| space.auto_tile)
+    }
+
+    /// Get the size-constraint fallback policy for an app
+    /// First <Application> whose matcher for this platform equals app_id wins;
+    /// otherwise the Form-level <TileFallback> (default: center)
+    pub fn tile_fallback_for_app(&self, app_id: &str) -> FallbackPolicy {
+        let platform = Platform::current();
+        self.applications.iter()
+            .find(|app| app.tile_fallback.is_some() && app.matches(platform, app_id))
+            .and_then(|app| app.tile_fallback)
+            .unwrap_or(self.tile_fallback)
+    }
+
+    /// Get the NudgeAction bound to a key
+    pub fn nudge_action(&self, key: &str) -> Option<NudgeSpec> {
+        self.nudge_actions.get(key).copied()
+    }
+
+    /// Get the ResizeAction bound to a key
+    pub fn resize_action(&self, key: &str) -> Option<ResizeSpec> {
+        self.resize_actions.get(key).copied()
+    }
+
+    /// Every key with a binding of any kind, sorted (for hotkey registration)
+    /// Synthetic Macro step bindings ("macro:<key>#<step>") are not keys
+    pub fn bound_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.layouts.keys()
+            .chain(self.display_moves.keys())
+            .chain(self.focus_moves.keys())
+            .chain(self.swap_actions.keys())
+            .chain(self.nudge_actions.keys())
+            .chain(self.resize_actions.keys())
+            .chain(self.macros.keys())
+            .filter(|key| !key.starts_with("macro:"))
+            .cloned()
+            .collect();
+        keys.sort();
+        keys.dedup();
+        keys
+    }
+
+    /// Check if a key's binding should fire on autorepeat (incremental actions only)
+    /// Bindings dispatch in order Macro, LayoutAction, DisplayMove, FocusMove,
+    /// SwapAction, NudgeAction, ResizeAction; a Nudge/Resize shadowed by an
+    /// earlier kind on the same key never runs, so it does not enable repeat.
+    pub fn accepts_autorepeat(&self, key: &str) -> bool {
+        let shadowed = self.macros.contains_key(key)
+            || self.layouts.contains_key(key)
+            || self.display_moves.contains_key(key)
+            || self.focus_moves.contains_key(key)
+            || self.swap_actions.contains_key(key);
+        !shadowed && (self.nudge_actions.contains_key(key) || self.resize_actions.contains_key(key))
+    }
+
     /// Execute a DisplayMove for the given key and current display index
+    /// displays are the live placements in OS enumeration order; next/prev/index
+    /// follow left-to-right physical order, name/id match against placement metadata
     /// Returns (target_display_index, target_spec) or None if key not bound or target out of range
-    pub fn execute_display_move(&self, key: &str, current_display_index: usize, total_displays: usize) -> Option<(usize, &DisplayMoveTarget)> {
-        let target = self.display_moves.get(key)?;
+    pub fn execute_display_move(&self, key: &str, current_display_index: usize, displays: &[DisplayPlacement]) -> Option<(usize, &DisplayMoveSpec)> {
+        let spec = self.display_moves.get(key)?;
+
+        // Only displays with a usable viewport take part in ordering and matching
+        let frames: Vec<PixelRect> = displays.iter().map(|d| d.frame.clone()).collect();
+        let order = spatial_order(&frames);
+        let total_displays = order.len();
+        let position = order.iter().position(|&idx| idx == current_display_index)?;
 
-        let target_index = match target {
+        let target_index = match &spec.target {
             DisplayMoveTarget::Next { wrap } => {
-                if current_display_index + 1 < total_displays {
-                    Some(current_display_index + 1)
+                if position + 1 < total_displays {
+                    Some(order[position + 1])
                 } else if *wrap {
-                    Some(0) // Wrap to first display
+                    Some(order[0]) // Wrap to leftmost display
                 } else {
                     None // No-op at boundary
                 }
             }
             DisplayMoveTarget::Prev { wrap } => {
-                if current_display_index > 0 {
-                    Some(current_display_index - 1)
+                if position > 0 {
+                    Some(order[position - 1])
                 } else if *wrap {
-                    Some(total_displays - 1) // Wrap to last display
+                    Some(order[total_displays - 1]) // Wrap to rightmost display
                 } else {
                     None // No-op at boundary
                 }
             }
             DisplayMoveTarget::Index(idx) => {
                 if *idx < total_displays {
-                    Some(*idx)
+                    Some(order[*idx])
                 } else {
                     eprintln!("DISPLAYMOVE: target={} out of range (max={})", idx, total_displays - 1);
                     None
                 }
             }
+            DisplayMoveTarget::Direction(direction) => {
+                let found = display_in_direction(&frames, current_display_index, *direction);
+                if found.is_none() {
+                    eprintln!("DISPLAYMOVE: target={} no display in that direction", direction.as_str());
+                }
+                found
+            }
+            DisplayMoveTarget::Name(pattern) => {
+                let needle = pattern.to_lowercase();
+                let found = order.iter().copied()
+                    .find(|&idx| displays[idx].name.to_lowercase().contains(&needle));
+                if found.is_none() {
+                    eprintln!("DISPLAYMOVE: target=name:{} no matching display", pattern);
+                }
+                found
+            }
+            DisplayMoveTarget::Identity(identity) => {
+                let found = order.iter().copied()
+                    .find(|&idx| displays[idx].identity == *identity);
+                if found.is_none() {
+                    eprintln!("DISPLAYMOVE: target=id:{} not connected", identity);
+                }
+                found
+            }
         };
 
-        target_index.map(|idx| (idx, target))
+        target_index.map(|idx| (idx, spec))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pbgft_types::MacroFailurePolicy;
+
+    /// Build a Form from the body of a <Form> element
+    fn form(body: &str) -> Form {
+        let xml = format!("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<Form>\n{}\n</Form>", body);
+        match Form::parse_and_build(&xml, &[]) {
+            Ok((_, form)) => form,
+            Err(errors) => panic!("form did not build: {:?}", errors),
+        }
+    }
+
+    const HALVES: &str = r#"
+  <Frame name="halves">
+    <Pane x="0" y="0" width="1/2" height="1"/>
+    <Pane x="1/2" y="0" width="1/2" height="1"/>
+  </Frame>
+  <Layout name="halves">
+    <Shape frame="halves"><Leaf/><Leaf/></Shape>
+  </Layout>"#;
+
+    #[test]
+    fn autorepeat_only_for_dispatched_incremental_bindings() {
+        let form = form(&format!(r#"{}
+  <LayoutAction key="u" layout="halves"/>
+  <NudgeAction key="u" direction="left" amount="1/20"/>
+  <NudgeAction key="n" direction="left" amount="1/20"/>
+  <ResizeAction key="r" edge="right" amount="1/20" op="grow"/>"#, HALVES));
+
+        assert!(form.accepts_autorepeat("n"));
+        assert!(form.accepts_autorepeat("r"));
+        // LayoutAction dispatches first, so its Nudge never runs
+        assert!(!form.accepts_autorepeat("u"));
+        assert!(!form.accepts_autorepeat("x"));
+    }
+
+    #[test]
+    fn macro_steps_resolve_to_synthetic_bindings() {
+        let form = form(&format!(r#"{}
+  <Macro key="m">
+    <LayoutAction layout="halves" pane="2"/>
+    <DisplayMove next="true"/>
+    <FocusMove direction="left"/>
+  </Macro>"#, HALVES));
+
+        let spec = form.macro_spec("m").unwrap();
+        assert_eq!(spec.on_failure, MacroFailurePolicy::Stop);
+        assert!(matches!(&spec.steps[..], [
+            MacroStep::LayoutAction { binding: layout, pane: 1 },
+            MacroStep::DisplayMove { binding: display },
+            MacroStep::FocusMove(Direction::Left),
+        ] if layout == "macro:m#1" && display == "macro:m#2"));
+
+        // Served by the keyed lookup paths, never as a key of their own
+        assert!(form.has_layout_action("macro:m#1"));
+        assert!(form.has_display_move("macro:m#2"));
+        assert!(!form.has_layout_action("m"));
+        assert!(form.macro_spec("macro:m#1").is_none());
+    }
+
+    #[test]
+    fn bound_keys_skip_macro_step_bindings() {
+        let form = form(&format!(r#"{}
+  <LayoutAction key="u" layout="halves"/>
+  <Macro key="m">
+    <LayoutAction layout="halves"/>
+    <DisplayMove next="true"/>
+  </Macro>"#, HALVES));
+        assert_eq!(form.bound_keys(), vec!["m".to_string(), "u".to_string()]);
+    }
+
+    fn display(width: f64, height: f64, name: &str) -> DisplayProps {
+        DisplayProps { width, height, name: name.to_string() }
+    }
+
+    #[test]
+    fn pane_cache_hit_serves_stored_list() {
+        let mut form = form(&format!(r#"{}
+  <LayoutAction key="u" layout="halves"/>"#, HALVES));
+        let laptop = display(1512.0, 982.0, "Built-in");
+        form.rebuild_pane_cache(std::slice::from_ref(&laptop));
+        assert_eq!(form.cached_panes("u", &laptop).flatten().map(|p| p.len()), Some(2));
+
+        // A hit never recomputes: a stored list is what presses cycle through
+        let marker = PaneFrac { x: 0.25, y: 0.0, width: 0.5, height: 1.0 };
+        form.pane_cache.get_mut("u").unwrap()[0].panes = Some(vec![marker]);
+        let (pane, index) = form.get_next_pane("u", &laptop, (0.0, 0.0)).unwrap();
+        assert_eq!((pane.x, index), (0.25, 0));
+    }
+
+    #[test]
+    fn pane_cache_miss_computes_and_stores() {
+        let mut form = form(&format!(r#"{}
+  <LayoutAction key="u" layout="halves"/>"#, HALVES));
+        let laptop = display(1512.0, 982.0, "Built-in");
+        let external = display(2560.0, 1440.0, "DELL U2720Q");
+        form.rebuild_pane_cache(std::slice::from_ref(&laptop));
+        assert!(form.cached_panes("u", &external).is_none());
+
+        let (first, _) = form.get_next_pane("u", &external, (0.0, 0.0)).unwrap();
+        let (second, _) = form.get_next_pane("u", &external, (0.0, 0.0)).unwrap();
+        assert_eq!((first.x, second.x), (0.0, 0.5));
+        assert_eq!(form.pane_cache["u"].len(), 2);
+
+        // Same size under another name is a different signature
+        let renamed = display(1512.0, 982.0, "Sidecar");
+        assert!(form.cached_panes("u", &renamed).is_none());
+        // No binding: the miss is stored as "no panes on this display"
+        assert!(form.get_next_pane("x", &laptop, (0.0, 0.0)).is_none());
+        assert!(matches!(form.cached_panes("x", &laptop), Some(None)));
+    }
+
+    #[test]
+    fn pane_cache_rebuild_replaces_every_display() {
+        let mut form = form(&format!(r#"{}
+  <LayoutAction key="u" layout="halves"/>"#, HALVES));
+        let laptop = display(1512.0, 982.0, "Built-in");
+        let external = display(2560.0, 1440.0, "DELL U2720Q");
+        form.rebuild_pane_cache(&[laptop.clone(), external.clone()]);
+        assert!(form.cached_panes("u", &laptop).is_some());
+        assert!(form.cached_panes("u", &external).is_some());
+
+        // External unplugged: only the displays present now stay cached
+        form.rebuild_pane_cache(std::slice::from_ref(&laptop));
+        assert_eq!(form.cached_panes("u", &laptop).flatten().map(|p| p.len()), Some(2));
+        assert!(form.cached_panes("u", &external).is_none());
+    }
+
+    #[test]
+    fn cycle_skips_panes_below_app_minimum_unless_none_fit() {
+        let form = form(&format!(r#"{}
+  <LayoutAction key="u" layout="halves"/>"#, HALVES));
+        let laptop = display(1512.0, 982.0, "Built-in");
+        assert_eq!(form.pane_cycle("u", &laptop, (700.0, 0.0)).map(|p| p.len()), Some(2));
+        // Neither half fits 800pt: the key still cycles both
+        assert_eq!(form.pane_cycle("u", &laptop, (800.0, 0.0)).map(|p| p.len()), Some(2));
     }
 }
@@ -1,25 +1,40 @@
 // SPDX-License-Identifier: Apache-2.0
 // Copyright 2025 Scale Invariant
 
-/// Form Configuration Runtime Types
-///
-/// This module contains runtime type definitions used by the Form configuration system.
-/// These types are kept after XML parsing is complete and are used during layout
-/// computation and execution.
+//! Form Configuration Runtime Types
+//!
+//! This module contains runtime type definitions used by the Form configuration system.
+//! These types are kept after XML parsing is complete and are used during layout
+//! computation and execution.
+
+use crate::pbgfp_parse::Fraction;
 
 // Re-export platform display types for convenience
 #[cfg(target_os = "macos")]
 #[allow(unused_imports)] // Re-exported for module interface
 pub use crate::pbmbd_display::DisplayInfo;
 
-// Stub types for non-macOS platforms (not used, but needed for compilation)
+// Display description for non-macOS platforms (filled by the platform display layer)
 #[cfg(not(target_os = "macos"))]
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct DisplayInfo {
     pub index: usize,
     pub design_width: f64,
     pub design_height: f64,
     pub name: String,
+    pub identity: DisplayIdentity,
+}
+
+#[cfg(not(target_os = "macos"))]
+impl DisplayInfo {
+    /// Get DisplayProps for Form queries
+    pub fn as_props(&self) -> DisplayProps {
+        DisplayProps {
+            width: self.design_width,
+            height: self.design_height,
+            name: self.name.clone(),
+        }
+    }
 }
 
 // ============================================================================
@@ -34,6 +49,52 @@ pub struct DisplayProps {
     pub name: String,
 }
 
+/// Stable display identity from EDID-derived numbers
+/// Survives replugging, unlike OS enumeration index. Serial may be 0 when
+/// the display does not report one (two identical unserialized panels collide).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DisplayIdentity {
+    pub vendor: u32,
+    pub model: u32,
+    pub serial: u32,
+}
+
+impl DisplayIdentity {
+    /// Parse "vendor:model:serial" (decimal or 0x-prefixed hex per field)
+    pub fn parse(s: &str) -> Option<Self> {
+        fn field(f: &str) -> Option<u32> {
+            match f.strip_prefix("0x").or_else(|| f.strip_prefix("0X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                None => f.parse::<u32>().ok(),
+            }
+        }
+
+        let parts: Vec<&str> = s.split(':').collect();
+        if parts.len() != 3 {
+            return None;
+        }
+        Some(DisplayIdentity {
+            vendor: field(parts[0])?,
+            model: field(parts[1])?,
+            serial: field(parts[2])?,
+        })
+    }
+}
+
+impl std::fmt::Display for DisplayIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.vendor, self.model, self.serial)
+    }
+}
+
+/// Live placement of one display, as seen by DisplayMove resolution
+#[derive(Debug, Clone)]
+pub struct DisplayPlacement {
+    pub frame: PixelRect,   // Viewport in global coordinates (top-left origin)
+    pub name: String,
+    pub identity: DisplayIdentity,
+}
+
 /// Fractional pane in [0,1] relative to display (Form output)
 #[derive(Debug, Clone)]
 pub struct PaneFrac {
@@ -56,11 +117,247 @@ pub struct PixelRect {
 // DisplayMove types
 // ============================================================================
 
+/// Spatial direction relative to the current display or window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Direction {
+    /// Parse from XML attribute value: "left", "right", "up", "down"
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "left" => Some(Direction::Left),
+            "right" => Some(Direction::Right),
+            "up" => Some(Direction::Up),
+            "down" => Some(Direction::Down),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Left => "left",
+            Direction::Right => "right",
+            Direction::Up => "up",
+            Direction::Down => "down",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum DisplayMoveTarget {
     Next { wrap: bool },
     Prev { wrap: bool },
-    Index(usize),
+    Index(usize),          // Position in left-to-right physical order
+    Direction(Direction),  // Nearest display in physical arrangement
+    Name(String),          // Case-insensitive substring of display name
+    Identity(DisplayIdentity),
+}
+
+/// How the window's geometry is carried to the target display
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayMoveMode {
+    Preserve,      // Keep pixel size and offset (full-screen fallback if too large)
+    Proportional,  // Keep fractional rect relative to the viewport
+}
+
+/// DisplayMove binding: where to go and how to carry the window
+#[derive(Debug, Clone)]
+pub struct DisplayMoveSpec {
+    pub target: DisplayMoveTarget,
+    pub mode: DisplayMoveMode,
+}
+
+// ============================================================================
+// Window action types
+// ============================================================================
+
+/// SwapAction partner selection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapTarget {
+    Direction(Direction),  // Spatial neighbor of the focused window
+    Mru,                   // Previously focused window
+}
+
+impl SwapTarget {
+    /// Parse from XML attribute value: a direction or "mru"
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "mru" => Some(SwapTarget::Mru),
+            _ => Direction::parse(s).map(SwapTarget::Direction),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SwapTarget::Direction(direction) => direction.as_str(),
+            SwapTarget::Mru => "mru",
+        }
+    }
+}
+
+/// Window edge moved by a ResizeAction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl Edge {
+    /// Parse from XML attribute value: "left", "right", "top", "bottom"
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "left" => Some(Edge::Left),
+            "right" => Some(Edge::Right),
+            "top" => Some(Edge::Top),
+            "bottom" => Some(Edge::Bottom),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Edge::Left => "left",
+            Edge::Right => "right",
+            Edge::Top => "top",
+            Edge::Bottom => "bottom",
+        }
+    }
+}
+
+/// NudgeAction binding: move the window by a fraction of its display
+#[derive(Debug, Clone, Copy)]
+pub struct NudgeSpec {
+    pub direction: Direction,
+    pub amount: f64,  // Fraction of viewport width (left/right) or height (up/down)
+}
+
+/// ResizeAction binding: move one edge by a fraction of its display
+#[derive(Debug, Clone, Copy)]
+pub struct ResizeSpec {
+    pub edge: Edge,
+    pub amount: f64,  // Fraction of viewport width (left/right) or height (top/bottom)
+    pub grow: bool,   // true = edge moves outward, false = inward
+}
+
+/// What to do when an app refuses the pane's size (AX size constrained or clamped)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FallbackPolicy {
+    None,        // Leave the window where the app put it
+    Center,      // Center the achievable size inside the pane
+    NextLarger,  // Skip ahead in the cycle to the first pane that fits the achievable size
+    AlignEdge,   // Pin to the pane edges that touch the viewport boundary
+}
+
+impl FallbackPolicy {
+    /// Parse from XML attribute value: "none", "center", "next-larger", "align-edge"
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "none" => Some(FallbackPolicy::None),
+            "center" => Some(FallbackPolicy::Center),
+            "next-larger" => Some(FallbackPolicy::NextLarger),
+            "align-edge" => Some(FallbackPolicy::AlignEdge),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FallbackPolicy::None => "none",
+            FallbackPolicy::Center => "center",
+            FallbackPolicy::NextLarger => "next-larger",
+            FallbackPolicy::AlignEdge => "align-edge",
+        }
+    }
+}
+
+// ============================================================================
+// Macro types
+// ============================================================================
+
+/// What a Macro does when a step fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MacroFailurePolicy {
+    Stop,      // Abort remaining steps (default)
+    Continue,  // Run every step, report failures at the end
+}
+
+impl MacroFailurePolicy {
+    /// Parse from XML attribute value: "stop", "continue"
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "stop" => Some(MacroFailurePolicy::Stop),
+            "continue" => Some(MacroFailurePolicy::Continue),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MacroFailurePolicy::Stop => "stop",
+            MacroFailurePolicy::Continue => "continue",
+        }
+    }
+}
+
+/// One step of a Macro, executed on the focused window at that point
+/// LayoutAction/DisplayMove steps are registered in the Form under a synthetic
+/// binding name ("macro:<key>#<step>") so they reuse the keyed lookup paths.
+#[derive(Debug, Clone)]
+pub enum MacroStep {
+    LayoutAction { binding: String, pane: usize },  // pane: 0-based index into the pane cycle
+    DisplayMove { binding: String },
+    FocusMove(Direction),
+    FocusMru,                                       // Previously focused window
+    SwapAction(SwapTarget),
+    Nudge(NudgeSpec),
+    Resize(ResizeSpec),
+}
+
+impl MacroStep {
+    /// Step name for logging (matches the XML element)
+    pub fn name(&self) -> &'static str {
+        match self {
+            MacroStep::LayoutAction { .. } => "LayoutAction",
+            MacroStep::DisplayMove { .. } => "DisplayMove",
+            MacroStep::FocusMove(_) => "FocusMove",
+            MacroStep::FocusMru => "FocusMru",
+            MacroStep::SwapAction(_) => "SwapAction",
+            MacroStep::Nudge(_) => "NudgeAction",
+            MacroStep::Resize(_) => "ResizeAction",
+        }
+    }
+}
+
+/// Macro binding: ordered steps plus failure handling
+#[derive(Debug, Clone)]
+pub struct MacroSpec {
+    pub steps: Vec<MacroStep>,
+    pub on_failure: MacroFailurePolicy,
+}
+
+// ============================================================================
+// Auto-tiling types
+// ============================================================================
+
+/// Arrangement algorithm
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TilingMode {
+    Bsp,          // Each new window splits the remaining space (dwindle)
+    MasterStack,  // First window on the left, others stacked on the right
+}
+
+/// Per-display tiling configuration (from <AutoTile> on a Space, see pbgt_tiling)
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TilingConfig {
+    pub(crate) mode: TilingMode,
+    pub(crate) ratio: Fraction,  // Share given to the first side of each split (0 < ratio < 1)
 }
 
 // ============================================================================
@@ -75,4 +372,5 @@ pub(crate) struct LayoutSession {
 pub(crate) struct DisplayMoveSession {
     pub(crate) original_size: Option<(f64, f64)>,     // (width, height) before first move
     pub(crate) original_offset: Option<(f64, f64)>,   // (offset_x, offset_y) relative to viewport origin
+    pub(crate) original_frac: Option<PaneFrac>,       // Fractional rect relative to viewport (proportional mode)
 }
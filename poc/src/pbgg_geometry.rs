@@ -0,0 +1,474 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+//! Platform-Neutral Geometry Helpers
+//! Pure rectangle arithmetic shared by display moves and window placement
+//!
+//! This module contains:
+//! - Conversion between pixel rects and viewport-relative fractions
+//! - Clamping a rect into a viewport
+//! - Spatial display selection by direction (physical arrangement, not OS order)
+//! - Left-to-right display ordering for next/prev/index targets
+//! - Directional window selection for focus navigation
+//! - Incremental nudge/resize steps in viewport fractions
+//! - Fallback placement when an app refuses the pane's size
+//!
+//! All rects use top-left origin with Y increasing downward (AX convention).
+
+use crate::pbgft_types::{Direction, Edge, PaneFrac, PixelRect};
+
+// ============================================================================
+// SECTION 1: Fractional Mapping
+// ============================================================================
+
+/// True when a rect covers some area (failed viewport queries yield zero rects)
+pub fn has_area(rect: &PixelRect) -> bool {
+    rect.width > 0.0 && rect.height > 0.0
+}
+
+/// Express a pixel rect as fractions of a viewport
+/// A viewport without area maps everything to the empty fraction at its origin.
+pub fn rect_to_frac(rect: &PixelRect, viewport: &PixelRect) -> PaneFrac {
+    if !has_area(viewport) {
+        return PaneFrac { x: 0.0, y: 0.0, width: 0.0, height: 0.0 };
+    }
+    PaneFrac {
+        x: (rect.x - viewport.x) / viewport.width,
+        y: (rect.y - viewport.y) / viewport.height,
+        width: rect.width / viewport.width,
+        height: rect.height / viewport.height,
+    }
+}
+
+/// Realize a fractional rect inside a viewport
+pub fn frac_to_rect(frac: &PaneFrac, viewport: &PixelRect) -> PixelRect {
+    PixelRect {
+        x: viewport.x + frac.x * viewport.width,
+        y: viewport.y + frac.y * viewport.height,
+        width: frac.width * viewport.width,
+        height: frac.height * viewport.height,
+    }
+}
+
+/// Shrink a rect to fit the viewport, then shift it inside
+pub fn clamp_rect_to_viewport(rect: &PixelRect, viewport: &PixelRect) -> PixelRect {
+    let width = rect.width.min(viewport.width);
+    let height = rect.height.min(viewport.height);
+    let max_x = viewport.x + viewport.width - width;
+    let max_y = viewport.y + viewport.height - height;
+
+    PixelRect {
+        x: rect.x.max(viewport.x).min(max_x),
+        y: rect.y.max(viewport.y).min(max_y),
+        width,
+        height,
+    }
+}
+
+// ============================================================================
+// SECTION 2: Directional Display Selection
+// ============================================================================
+
+/// Distance between two 1D ranges (0 when they overlap)
+fn range_gap(a_min: f64, a_max: f64, b_min: f64, b_max: f64) -> f64 {
+    if a_max < b_min {
+        b_min - a_max
+    } else if b_max < a_min {
+        a_min - b_max
+    } else {
+        0.0
+    }
+}
+
+/// Find the display adjacent to `from` in the given direction
+/// Candidates must have area and lie beyond the current display's edge in that direction.
+/// Displays sharing an edge span win over diagonal ones; ties go to the
+/// nearest edge, then the smallest perpendicular offset of centers.
+pub fn display_in_direction(displays: &[PixelRect], from: usize, direction: Direction) -> Option<usize> {
+    let src = displays.get(from)?;
+    let src_cx = src.x + src.width / 2.0;
+    let src_cy = src.y + src.height / 2.0;
+
+    let mut best: Option<(usize, (bool, f64, f64))> = None;
+
+    for (idx, cand) in displays.iter().enumerate() {
+        if idx == from || !has_area(cand) {
+            continue;
+        }
+
+        let cand_cx = cand.x + cand.width / 2.0;
+        let cand_cy = cand.y + cand.height / 2.0;
+
+        // (beyond edge?, primary gap, perpendicular span gap, perpendicular center offset)
+        let (beyond, gap, perp_gap, perp_offset) = match direction {
+            Direction::Right => (
+                cand_cx > src.x + src.width,
+                (cand.x - (src.x + src.width)).max(0.0),
+                range_gap(src.y, src.y + src.height, cand.y, cand.y + cand.height),
+                (cand_cy - src_cy).abs(),
+            ),
+            Direction::Left => (
+                cand_cx < src.x,
+                (src.x - (cand.x + cand.width)).max(0.0),
+                range_gap(src.y, src.y + src.height, cand.y, cand.y + cand.height),
+                (cand_cy - src_cy).abs(),
+            ),
+            Direction::Down => (
+                cand_cy > src.y + src.height,
+                (cand.y - (src.y + src.height)).max(0.0),
+                range_gap(src.x, src.x + src.width, cand.x, cand.x + cand.width),
+                (cand_cx - src_cx).abs(),
+            ),
+            Direction::Up => (
+                cand_cy < src.y,
+                (src.y - (cand.y + cand.height)).max(0.0),
+                range_gap(src.x, src.x + src.width, cand.x, cand.x + cand.width),
+                (cand_cx - src_cx).abs(),
+            ),
+        };
+
+        if !beyond {
+            continue;
+        }
+
+        let score = (perp_gap > 0.0, gap + perp_gap, perp_offset);
+        let better = match &best {
+            None => true,
+            Some((_, b)) => {
+                // Sharing an edge span (false) ranks ahead of diagonal (true)
+                (!score.0 && b.0)
+                    || (score.0 == b.0 && (score.1 < b.1 || (score.1 == b.1 && score.2 < b.2)))
+            }
+        };
+        if better {
+            best = Some((idx, score));
+        }
+    }
+
+    best.map(|(idx, _)| idx)
+}
+
+// ============================================================================
+// SECTION 3: Spatial Display Ordering
+// ============================================================================
+
+/// Display indices sorted left-to-right by left edge, top-to-bottom on ties
+/// Stable across replugging as long as the physical arrangement is unchanged.
+/// Displays without area (viewport query failed) are left out.
+pub fn spatial_order(displays: &[PixelRect]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..displays.len()).filter(|&idx| has_area(&displays[idx])).collect();
+    order.sort_by(|&a, &b| {
+        let (ra, rb) = (&displays[a], &displays[b]);
+        ra.x.partial_cmp(&rb.x)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(ra.y.partial_cmp(&rb.y).unwrap_or(std::cmp::Ordering::Equal))
+    });
+    order
+}
+
+// ============================================================================
+// SECTION 4: Directional Window Selection
+// ============================================================================
+
+/// Overlap length of two 1D ranges (0 when disjoint)
+fn range_overlap(a_min: f64, a_max: f64, b_min: f64, b_max: f64) -> f64 {
+    (a_max.min(b_max) - a_min.max(b_min)).max(0.0)
+}
+
+/// True if two rects share more than a 1px sliver on both axes
+/// Used by the swap rollback check, which only macOS has
+#[cfg(any(target_os = "macos", test))]
+pub fn rects_overlap(a: &PixelRect, b: &PixelRect) -> bool {
+    range_overlap(a.x, a.x + a.width, b.x, b.x + b.width) > 1.0
+        && range_overlap(a.y, a.y + a.height, b.y, b.y + b.height) > 1.0
+}
+
+/// Find the window nearest to `from` in the given direction
+/// candidates are (rect, mru_rank) pairs; lower rank = more recently used.
+/// A candidate qualifies when its center lies beyond `from`'s center in that
+/// direction. Ranking, in order:
+/// 1. Windows overlapping `from` on the perpendicular axis before diagonal ones
+/// 2. Smallest edge distance (whole pixels, so tiled gaps compare equal)
+/// 3. Largest perpendicular overlap
+/// 4. Most recently used
+///
+/// Returns the index into `candidates`.
+pub fn window_in_direction(from: &PixelRect, candidates: &[(PixelRect, usize)], direction: Direction) -> Option<usize> {
+    let src_cx = from.x + from.width / 2.0;
+    let src_cy = from.y + from.height / 2.0;
+
+    candidates.iter().enumerate()
+        .filter_map(|(idx, (cand, mru_rank))| {
+            let cand_cx = cand.x + cand.width / 2.0;
+            let cand_cy = cand.y + cand.height / 2.0;
+
+            let (beyond, edge_distance, overlap) = match direction {
+                Direction::Right => (
+                    cand_cx > src_cx,
+                    cand.x - (from.x + from.width),
+                    range_overlap(from.y, from.y + from.height, cand.y, cand.y + cand.height),
+                ),
+                Direction::Left => (
+                    cand_cx < src_cx,
+                    from.x - (cand.x + cand.width),
+                    range_overlap(from.y, from.y + from.height, cand.y, cand.y + cand.height),
+                ),
+                Direction::Down => (
+                    cand_cy > src_cy,
+                    cand.y - (from.y + from.height),
+                    range_overlap(from.x, from.x + from.width, cand.x, cand.x + cand.width),
+                ),
+                Direction::Up => (
+                    cand_cy < src_cy,
+                    from.y - (cand.y + cand.height),
+                    range_overlap(from.x, from.x + from.width, cand.x, cand.x + cand.width),
+                ),
+            };
+
+            if !beyond {
+                return None;
+            }
+
+            // Overlapping windows have negative edge distance; treat them as touching
+            let score = (
+                overlap <= 0.0,
+                edge_distance.max(0.0).round() as i64,
+                -(overlap.round() as i64),
+                *mru_rank,
+            );
+            Some((idx, score))
+        })
+        .min_by(|a, b| a.1.cmp(&b.1))
+        .map(|(idx, _)| idx)
+}
+
+// ============================================================================
+// SECTION 5: Incremental Move and Resize
+// ============================================================================
+
+/// Move a rect by `amount` of the viewport's width/height, clamped inside it
+pub fn nudge_rect(rect: &PixelRect, viewport: &PixelRect, direction: Direction, amount: f64) -> PixelRect {
+    let (dx, dy) = match direction {
+        Direction::Left => (-amount * viewport.width, 0.0),
+        Direction::Right => (amount * viewport.width, 0.0),
+        Direction::Up => (0.0, -amount * viewport.height),
+        Direction::Down => (0.0, amount * viewport.height),
+    };
+
+    let moved = PixelRect {
+        x: rect.x + dx,
+        y: rect.y + dy,
+        width: rect.width,
+        height: rect.height,
+    };
+    clamp_rect_to_viewport(&moved, viewport)
+}
+
+/// Move one edge of a rect by `amount` of the viewport's width/height
+/// Growing stops at the viewport boundary; shrinking stops at `min_size`.
+/// The opposite edge stays fixed.
+pub fn resize_rect(rect: &PixelRect, viewport: &PixelRect, edge: Edge, amount: f64, grow: bool, min_size: f64) -> PixelRect {
+    let sign = if grow { 1.0 } else { -1.0 };
+
+    // Start from the rect as currently clamped into the viewport
+    let mut left = rect.x.max(viewport.x);
+    let mut top = rect.y.max(viewport.y);
+    let mut right = (rect.x + rect.width).min(viewport.x + viewport.width);
+    let mut bottom = (rect.y + rect.height).min(viewport.y + viewport.height);
+
+    match edge {
+        Edge::Left => {
+            left = (left - sign * amount * viewport.width)
+                .max(viewport.x)
+                .min(right - min_size);
+        }
+        Edge::Right => {
+            right = (right + sign * amount * viewport.width)
+                .min(viewport.x + viewport.width)
+                .max(left + min_size);
+        }
+        Edge::Top => {
+            top = (top - sign * amount * viewport.height)
+                .max(viewport.y)
+                .min(bottom - min_size);
+        }
+        Edge::Bottom => {
+            bottom = (bottom + sign * amount * viewport.height)
+                .min(viewport.y + viewport.height)
+                .max(top + min_size);
+        }
+    }
+
+    PixelRect {
+        x: left,
+        y: top,
+        width: right - left,
+        height: bottom - top,
+    }
+}
+
+// ============================================================================
+// SECTION 6: Size-Constrained Placement Fallbacks
+// ============================================================================
+
+/// Place a `width` x `height` window centered on a pane, clamped inside the viewport
+pub fn center_in_pane(pane: &PixelRect, width: f64, height: f64, viewport: &PixelRect) -> PixelRect {
+    let centered = PixelRect {
+        x: pane.x + (pane.width - width) / 2.0,
+        y: pane.y + (pane.height - height) / 2.0,
+        width,
+        height,
+    };
+    clamp_rect_to_viewport(&centered, viewport)
+}
+
+/// Place a `width` x `height` window against the pane edges that touch the viewport
+/// A right-half pane pins the window's right edge, a bottom pane its bottom edge;
+/// panes touching both (or neither) opposite edges pin left/top.
+pub fn align_to_pane_edge(pane: &PixelRect, width: f64, height: f64, viewport: &PixelRect) -> PixelRect {
+    const EPSILON: f64 = 1.0;
+    let touches_left = (pane.x - viewport.x).abs() < EPSILON;
+    let touches_right = ((pane.x + pane.width) - (viewport.x + viewport.width)).abs() < EPSILON;
+    let touches_top = (pane.y - viewport.y).abs() < EPSILON;
+    let touches_bottom = ((pane.y + pane.height) - (viewport.y + viewport.height)).abs() < EPSILON;
+
+    let x = if touches_right && !touches_left { pane.x + pane.width - width } else { pane.x };
+    let y = if touches_bottom && !touches_top { pane.y + pane.height - height } else { pane.y };
+
+    clamp_rect_to_viewport(&PixelRect { x, y, width, height }, viewport)
+}
+
+/// First pane after `current` (cyclic, excluding `current`) that can hold a
+/// `width` x `height` window; 1px tolerance absorbs rounding in realized panes
+pub fn next_pane_fitting(panes: &[PixelRect], current: usize, width: f64, height: f64) -> Option<usize> {
+    let count = panes.len();
+    (1..count)
+        .map(|step| (current + step) % count)
+        .find(|&idx| panes[idx].width + 1.0 >= width && panes[idx].height + 1.0 >= height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f64, y: f64, width: f64, height: f64) -> PixelRect {
+        PixelRect { x, y, width, height }
+    }
+
+    // ------------------------------------------------------------------------
+    // Degenerate viewports
+    // ------------------------------------------------------------------------
+
+    #[test]
+    fn rect_to_frac_zero_viewport_is_empty() {
+        let frac = rect_to_frac(&rect(10.0, 10.0, 100.0, 100.0), &rect(0.0, 0.0, 0.0, 0.0));
+        assert_eq!((frac.x, frac.y, frac.width, frac.height), (0.0, 0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn rect_to_frac_maps_into_viewport() {
+        let frac = rect_to_frac(&rect(1000.0, 100.0, 500.0, 400.0), &rect(500.0, 0.0, 1000.0, 800.0));
+        assert_eq!((frac.x, frac.y, frac.width, frac.height), (0.5, 0.125, 0.5, 0.5));
+    }
+
+    #[test]
+    fn zero_display_never_chosen_by_direction() {
+        // Display 1 failed its viewport query and sits at the origin
+        let displays = [rect(0.0, 0.0, 1920.0, 1080.0), rect(0.0, 0.0, 0.0, 0.0), rect(1920.0, 0.0, 1920.0, 1080.0)];
+        assert_eq!(display_in_direction(&displays, 0, Direction::Right), Some(2));
+        assert_eq!(display_in_direction(&displays, 2, Direction::Left), Some(0));
+        assert_eq!(display_in_direction(&displays, 0, Direction::Left), None);
+    }
+
+    #[test]
+    fn spatial_order_skips_zero_displays() {
+        let displays = [rect(1920.0, 0.0, 1920.0, 1080.0), rect(0.0, 0.0, 0.0, 0.0), rect(0.0, 0.0, 1920.0, 1080.0)];
+        assert_eq!(spatial_order(&displays), vec![2, 0]);
+    }
+
+    // ------------------------------------------------------------------------
+    // Directional window selection
+    // ------------------------------------------------------------------------
+
+    /// 2x2 grid of 500x500 windows with 10px gaps; MRU rank = index
+    fn grid() -> Vec<(PixelRect, usize)> {
+        vec![
+            (rect(0.0, 0.0, 500.0, 500.0), 0),
+            (rect(510.0, 0.0, 500.0, 500.0), 1),
+            (rect(0.0, 510.0, 500.0, 500.0), 2),
+            (rect(510.0, 510.0, 500.0, 500.0), 3),
+        ]
+    }
+
+    #[test]
+    fn window_picks_each_direction() {
+        let windows = grid();
+        let from = &windows[0].0;
+        assert_eq!(window_in_direction(from, &windows[1..], Direction::Right), Some(0));
+        assert_eq!(window_in_direction(from, &windows[1..], Direction::Down), Some(1));
+
+        let from = &windows[3].0;
+        assert_eq!(window_in_direction(from, &windows[..3], Direction::Left), Some(2));
+        assert_eq!(window_in_direction(from, &windows[..3], Direction::Up), Some(1));
+    }
+
+    #[test]
+    fn window_no_neighbor_in_direction() {
+        let windows = grid();
+        assert_eq!(window_in_direction(&windows[0].0, &windows[1..], Direction::Left), None);
+        assert_eq!(window_in_direction(&windows[0].0, &windows[1..], Direction::Up), None);
+        assert_eq!(window_in_direction(&windows[0].0, &[], Direction::Right), None);
+    }
+
+    #[test]
+    fn window_overlapping_span_beats_nearer_diagonal() {
+        let from = rect(0.0, 0.0, 500.0, 500.0);
+        let candidates = [
+            (rect(505.0, 600.0, 500.0, 500.0), 0),   // Diagonal, 5px away
+            (rect(800.0, 100.0, 500.0, 300.0), 1),   // Same row, 300px away
+        ];
+        assert_eq!(window_in_direction(&from, &candidates, Direction::Right), Some(1));
+    }
+
+    #[test]
+    fn window_ties_break_on_overlap_then_mru() {
+        let from = rect(0.0, 0.0, 500.0, 500.0);
+
+        // Same edge distance: larger perpendicular overlap wins
+        let candidates = [
+            (rect(510.0, 400.0, 500.0, 500.0), 0),
+            (rect(510.0, 100.0, 500.0, 500.0), 1),
+        ];
+        assert_eq!(window_in_direction(&from, &candidates, Direction::Right), Some(1));
+
+        // Same distance and overlap (sub-pixel differences round away): most recent wins
+        let candidates = [
+            (rect(510.3, 0.0, 500.0, 500.0), 4),
+            (rect(510.0, 0.0, 500.0, 500.0), 2),
+        ];
+        assert_eq!(window_in_direction(&from, &candidates, Direction::Right), Some(1));
+    }
+
+    #[test]
+    fn window_overlapping_from_counts_as_touching() {
+        // Candidate overlaps `from` but its center lies to the right
+        let from = rect(0.0, 0.0, 500.0, 500.0);
+        let candidates = [
+            (rect(400.0, 0.0, 500.0, 500.0), 1),
+            (rect(600.0, 0.0, 500.0, 500.0), 0),
+        ];
+        assert_eq!(window_in_direction(&from, &candidates, Direction::Right), Some(0));
+        // Centered on `from`: not beyond in any direction
+        let stacked = [(rect(0.0, 0.0, 500.0, 500.0), 0)];
+        assert_eq!(window_in_direction(&from, &stacked, Direction::Right), None);
+    }
+
+    #[test]
+    fn rects_overlap_ignores_slivers() {
+        let a = rect(0.0, 0.0, 500.0, 500.0);
+        assert!(rects_overlap(&a, &rect(400.0, 400.0, 500.0, 500.0)));
+        assert!(!rects_overlap(&a, &rect(500.0, 0.0, 500.0, 500.0)));
+        assert!(!rects_overlap(&a, &rect(499.5, 0.0, 500.0, 500.0)));
+        assert!(!rects_overlap(&a, &rect(0.0, 600.0, 500.0, 500.0)));
+    }
+}
@@ -0,0 +1,700 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+//! Input Event Pipeline
+//! Platform-neutral key/mouse events run through an ordered chain of handlers
+//!
+//! A platform tap translates each native event into an InputEvent, runs the
+//! pipeline and applies the Verdict. Handlers run in order until one claims
+//! the event; an event nobody claims passes through:
+//! 1. SwitcherClickCancel - a mouse click during Alt-Tab cancels the session
+//! 2. ClipboardHistory - Ctrl+Shift+V opens the history overlay, which then
+//!    owns every key press until Enter or Escape
+//! 3. CopyPasteMirror - Ctrl+C/X/V also sends Cmd+C/X/V
+//! 4. Switcher - Cmd+Tab steps through the MRU list, Cmd release commits
+//! 5. ChordRelease - releasing Ctrl+Shift+Alt resets pane cycles (never claims)
+//! 6. FormChords - Ctrl+Shift+Alt+key runs the Form binding for the key
+//!
+//! Order matters: the clipboard overlay must see keys before the mirror and
+//! the switcher, and the switcher must see Cmd release before ChordRelease
+//! tracks modifiers. Keys are identified by their Form name ("tab", "v");
+//! Alt is Option on macOS.
+//!
+//! Side effects (overlays, focus, Form actions) go through InputHost, so each
+//! handler can be driven by synthetic event streams without a window system.
+
+use crate::pbgcl_clipboard::CLIPBOARD_HISTORY;
+
+// ============================================================================
+// SECTION 1: Event model
+// ============================================================================
+
+/// Modifier state carried by every event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,   // Option on macOS
+    pub cmd: bool,   // Command on macOS, Super/Meta elsewhere
+}
+
+impl Modifiers {
+    pub const COMMAND: Modifiers = Modifiers { ctrl: false, shift: false, alt: false, cmd: true };
+
+    /// Control+Shift+Alt all held (the Form chord)
+    pub fn chord_held(&self) -> bool {
+        self.ctrl && self.shift && self.alt
+    }
+}
+
+/// A physical key: platform keycode plus its Form key name, if it has one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    pub code: u16,
+    pub name: Option<&'static str>,
+}
+
+impl Key {
+    pub fn is(&self, name: &str) -> bool {
+        self.name == Some(name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputEvent {
+    KeyDown { key: Key, mods: Modifiers, repeat: bool },
+    KeyUp { key: Key, mods: Modifiers },
+    FlagsChanged { code: u16, mods: Modifiers },   // Modifier key pressed or released
+    MouseDown { mods: Modifiers },                 // Any button
+}
+
+impl InputEvent {
+    pub fn mods(&self) -> Modifiers {
+        match self {
+            InputEvent::KeyDown { mods, .. }
+            | InputEvent::KeyUp { mods, .. }
+            | InputEvent::FlagsChanged { mods, .. }
+            | InputEvent::MouseDown { mods } => *mods,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            InputEvent::KeyDown { .. } => "key_down",
+            InputEvent::KeyUp { .. } => "key_up",
+            InputEvent::FlagsChanged { .. } => "flags_changed",
+            InputEvent::MouseDown { .. } => "mouse_down",
+        }
+    }
+
+    pub fn code(&self) -> u16 {
+        match self {
+            InputEvent::KeyDown { key, .. } | InputEvent::KeyUp { key, .. } => key.code,
+            InputEvent::FlagsChanged { code, .. } => *code,
+            InputEvent::MouseDown { .. } => 0,
+        }
+    }
+}
+
+/// What the platform does with an event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Continue,                   // Not this handler's event; ask the next one
+    Pass,                       // Deliver unchanged
+    Swallow,                    // Drop
+    Replace(Vec<InputEvent>),   // Deliver these instead (the original, if listed, is delivered in place)
+}
+
+// ============================================================================
+// SECTION 2: Sessions and host
+// ============================================================================
+
+/// Alt-Tab session state
+#[derive(Debug, Clone, Default)]
+pub struct AltTabSession {
+    pub active: bool,           // Is Cmd held with the switcher open?
+    pub popup_shown: bool,      // Has popup been displayed this session?
+    pub highlight_index: Option<usize>,  // Current highlight position (None = not navigating)
+}
+
+/// Clipboard overlay session state
+#[derive(Debug, Clone, Default)]
+pub struct ClipboardSession {
+    pub active: bool,           // Is Ctrl+Shift+V overlay active?
+    pub highlight_index: Option<usize>,  // Current highlight position
+}
+
+/// Overlay sessions several handlers look at
+#[derive(Debug, Clone, Default)]
+pub struct Sessions {
+    pub alt_tab: AltTabSession,
+    pub clipboard: ClipboardSession,
+}
+
+/// One switcher row, for logs
+#[derive(Debug, Clone)]
+pub struct SwitcherEntry {
+    pub app_id: String,
+    pub title: String,
+}
+
+/// Platform side effects of the handlers (runs on the tap thread, inside the callback)
+pub trait InputHost {
+    /// Chord modifiers as users know them ("ctrl+shift+option" on macOS)
+    fn chord_label(&self) -> &'static str {
+        "ctrl+shift+alt"
+    }
+
+    /// MRU windows, most recent first; `commit_switcher` indexes the last list returned
+    fn switcher_entries(&mut self) -> Vec<SwitcherEntry>;
+    /// Drop MRU entries whose windows are gone (session start)
+    fn prune_switcher_entries(&mut self);
+    fn show_switcher(&mut self, highlight: usize);
+    fn update_switcher(&mut self, highlight: usize);
+    fn hide_switcher(&mut self);
+    /// Focus the entry's window (after the event returns)
+    fn commit_switcher(&mut self, index: usize);
+
+    fn show_clipboard(&mut self, entries: &[String], highlight: usize);
+    fn update_clipboard(&mut self, entries: &[String], highlight: usize);
+    fn hide_clipboard(&mut self);
+    fn set_clipboard_text(&mut self, text: &str);
+
+    /// True if the key's binding wants auto-repeat (NudgeAction/ResizeAction)
+    fn key_accepts_autorepeat(&self, key: &str) -> bool;
+    /// Run the Form binding for a chord key on the frontmost window
+    /// Returns false if there is no frontmost window (the chord then passes)
+    fn run_chord(&mut self, key: &'static str, is_repeat: bool) -> bool;
+    /// The chord was released (end of a pane cycle)
+    fn reset_layout_session(&mut self);
+}
+
+/// One link of the chain
+pub trait InputHandler: Send {
+    fn handle(&mut self, event: &InputEvent, sessions: &mut Sessions, host: &mut dyn InputHost) -> Verdict;
+}
+
+// ============================================================================
+// SECTION 3: Handlers
+// ============================================================================
+
+/// A click during Alt-Tab cancels it; the click is consumed so it cannot
+/// activate a different window than the one the switcher shows
+pub struct SwitcherClickCancel;
+
+impl InputHandler for SwitcherClickCancel {
+    fn handle(&mut self, event: &InputEvent, sessions: &mut Sessions, host: &mut dyn InputHost) -> Verdict {
+        if !matches!(event, InputEvent::MouseDown { .. }) || !sessions.alt_tab.active {
+            return Verdict::Continue;
+        }
+        host.hide_switcher();
+        sessions.alt_tab = AltTabSession::default();
+        println!("SWITCHER: cancelled | reason=mouse_click");
+        Verdict::Swallow
+    }
+}
+
+/// Ctrl+Shift+V history overlay: Up/Down move, Enter pastes, Escape closes
+pub struct ClipboardHistory;
+
+impl ClipboardHistory {
+    fn show(sessions: &mut Sessions, host: &mut dyn InputHost) {
+        let history = CLIPBOARD_HISTORY.lock().unwrap().clone();
+        if history.is_empty() {
+            // Still show overlay with the "(no items)" message
+            println!("CLIP: overlay shown | entries=0");
+            host.show_clipboard(&[], 0);
+            return;
+        }
+
+        sessions.clipboard.active = true;
+        sessions.clipboard.highlight_index = Some(0); // Start at first entry
+        println!("CLIP: overlay shown | entries={}", history.len());
+        host.show_clipboard(&history, 0);
+    }
+
+    fn hide(sessions: &mut Sessions, host: &mut dyn InputHost) {
+        host.hide_clipboard();
+        sessions.clipboard = ClipboardSession::default();
+    }
+
+    fn navigate(key: &Key, sessions: &mut Sessions, host: &mut dyn InputHost) {
+        let history = CLIPBOARD_HISTORY.lock().unwrap().clone();
+        if history.is_empty() {
+            return;
+        }
+
+        let current_idx = sessions.clipboard.highlight_index.unwrap_or(0);
+        let new_idx = match key.name {
+            Some("up") => current_idx.saturating_sub(1),
+            Some("down") => (current_idx + 1).min(history.len() - 1),
+            Some("enter") => {
+                // Paste selected entry
+                let selected_text = &history[current_idx];
+                host.set_clipboard_text(selected_text);
+                println!("CLIP: pasted historic | index={} | length={}", current_idx, selected_text.len());
+                Self::hide(sessions, host);
+                return;
+            }
+            Some("escape") => {
+                Self::hide(sessions, host);
+                return;
+            }
+            _ => current_idx,
+        };
+
+        if new_idx != current_idx {
+            sessions.clipboard.highlight_index = Some(new_idx);
+            host.update_clipboard(&history, new_idx);
+        }
+    }
+}
+
+impl InputHandler for ClipboardHistory {
+    fn handle(&mut self, event: &InputEvent, sessions: &mut Sessions, host: &mut dyn InputHost) -> Verdict {
+        if sessions.clipboard.active {
+            return match event {
+                InputEvent::KeyDown { key, .. } => {
+                    Self::navigate(key, sessions, host);
+                    Verdict::Swallow // Block all keys during clipboard session
+                }
+                _ => Verdict::Pass, // Pass through other events during session
+            };
+        }
+
+        match event {
+            InputEvent::KeyDown { key, mods, .. } if key.is("v") && mods.ctrl && mods.shift && !mods.cmd && !mods.alt => {
+                Self::show(sessions, host);
+                Verdict::Swallow
+            }
+            _ => Verdict::Continue,
+        }
+    }
+}
+
+/// Ctrl+C/X/V (without Shift) also sends Cmd+C/X/V; the Ctrl event still passes
+pub struct CopyPasteMirror;
+
+impl InputHandler for CopyPasteMirror {
+    fn handle(&mut self, event: &InputEvent, _: &mut Sessions, _: &mut dyn InputHost) -> Verdict {
+        let is_edit_key = |key: &Key| key.is("c") || key.is("x") || key.is("v");
+        let mirror = match *event {
+            InputEvent::KeyDown { key, mods, .. } if is_edit_key(&key) && mods.ctrl && !mods.shift && !mods.cmd && !mods.alt => {
+                eprintln!("CLIP: mirror issued cmd+{}", key.name.unwrap_or("?"));
+                InputEvent::KeyDown { key, mods: Modifiers::COMMAND, repeat: false }
+            }
+            // Key ups are mirrored too, so the Cmd key is not left down
+            InputEvent::KeyUp { key, mods } if is_edit_key(&key) && mods.ctrl && !mods.cmd && !mods.alt => {
+                InputEvent::KeyUp { key, mods: Modifiers::COMMAND }
+            }
+            _ => return Verdict::Continue,
+        };
+        Verdict::Replace(vec![*event, mirror])
+    }
+}
+
+/// Cmd+Tab MRU switcher: first Tab shows the list on the next window (Shift+Tab
+/// on the last), further Tabs step with wrap-around, releasing Cmd commits
+pub struct Switcher;
+
+impl Switcher {
+    fn finish(sessions: &mut Sessions, host: &mut dyn InputHost) {
+        host.hide_switcher();
+        sessions.alt_tab = AltTabSession::default();
+        println!("SWITCHER: cleanup | overlays hidden, state reset");
+    }
+
+    /// Cmd released: focus the highlighted entry and close
+    fn commit(sessions: &mut Sessions, host: &mut dyn InputHost) {
+        eprintln!("SWITCHER: Command released (detected via flags)");
+
+        // Defensive bounds check: verify MRU snapshot is non-empty
+        let entries = host.switcher_entries();
+        if entries.is_empty() {
+            eprintln!("SWITCHER: switch | SKIPPED reason=empty_mru");
+            Self::finish(sessions, host);
+            return;
+        }
+
+        match sessions.alt_tab.highlight_index {
+            Some(idx) if idx < entries.len() => host.commit_switcher(idx),
+            Some(idx) => {
+                eprintln!("SWITCHER: switch | SKIPPED reason=index_out_of_bounds idx={} len={}", idx, entries.len());
+            }
+            None => {
+                // No highlight set - should not happen in normal flow
+                eprintln!("SWITCHER: switch | SKIPPED reason=no_highlight_index");
+            }
+        }
+
+        // Always cleanup overlays and session state
+        Self::finish(sessions, host);
+    }
+
+    /// Tab with Cmd held: start the session or step the highlight
+    fn step(backward: bool, sessions: &mut Sessions, host: &mut dyn InputHost) {
+        // Start session on first Tab press
+        if !sessions.alt_tab.active {
+            sessions.alt_tab.active = true;
+            println!("SWITCHER: session start");
+            host.prune_switcher_entries();
+        }
+
+        let entries = host.switcher_entries();
+        let mru_count = entries.len();
+        if mru_count == 0 {
+            // No windows to show - reset session state
+            sessions.alt_tab = AltTabSession::default();
+            eprintln!("SWITCHER: no windows available");
+            println!("BLOCKED: cmd+tab (no windows)");
+            return;
+        }
+
+        let new_idx = if !sessions.alt_tab.popup_shown {
+            // First Tab press - skip the current window (index 0), or wrap to the end backwards
+            sessions.alt_tab.popup_shown = true;
+            let idx = if backward { mru_count - 1 } else if mru_count > 1 { 1 } else { 0 };
+            host.show_switcher(idx);
+            idx
+        } else {
+            let current_idx = sessions.alt_tab.highlight_index.unwrap_or(0);
+            let idx = if backward {
+                if current_idx == 0 { mru_count - 1 } else { current_idx - 1 }
+            } else {
+                (current_idx + 1) % mru_count
+            };
+            host.update_switcher(idx);
+            idx
+        };
+        sessions.alt_tab.highlight_index = Some(new_idx);
+
+        let entry = &entries[new_idx];
+        if backward {
+            println!("SWITCHER: backward step -> index={} app={} win=\"{}\"", new_idx, entry.app_id, entry.title);
+            println!("BLOCKED: cmd+shift+tab");
+        } else {
+            println!("SWITCHER: forward step -> index={} app={} win=\"{}\"", new_idx, entry.app_id, entry.title);
+            println!("BLOCKED: cmd+tab");
+        }
+    }
+}
+
+impl InputHandler for Switcher {
+    fn handle(&mut self, event: &InputEvent, sessions: &mut Sessions, host: &mut dyn InputHost) -> Verdict {
+        // Cmd release shows up as flags changed, or on the key up of a key pressed with it
+        if matches!(event, InputEvent::FlagsChanged { .. } | InputEvent::KeyUp { .. }) && sessions.alt_tab.active {
+            let mods = event.mods();
+            eprintln!("SWITCHER: checking flags: event={} keycode={} has_cmd={} has_shift={}",
+                     event.kind(), event.code(), mods.cmd, mods.shift);
+            if !mods.cmd {
+                Self::commit(sessions, host);
+                return Verdict::Pass; // Don't block flags changed events
+            }
+        }
+
+        match event {
+            // Tab key up belongs to the session
+            InputEvent::KeyUp { key, .. } if key.is("tab") => {
+                if sessions.alt_tab.active { Verdict::Swallow } else { Verdict::Pass }
+            }
+            InputEvent::KeyDown { key, mods, repeat } if key.is("tab") && mods.cmd && !mods.alt && !mods.ctrl => {
+                // Ignore auto-repeat to prevent uncontrolled cycling
+                if *repeat {
+                    eprintln!("SWITCHER: Ignoring Tab auto-repeat");
+                } else {
+                    Self::step(mods.shift, sessions, host);
+                }
+                Verdict::Swallow
+            }
+            _ => Verdict::Continue,
+        }
+    }
+}
+
+/// Releasing Ctrl+Shift+Alt resets all pane sequence indices (per spec)
+#[derive(Default)]
+pub struct ChordRelease {
+    was_held: bool,
+}
+
+impl InputHandler for ChordRelease {
+    fn handle(&mut self, event: &InputEvent, _: &mut Sessions, host: &mut dyn InputHost) -> Verdict {
+        if let InputEvent::FlagsChanged { mods, .. } = event {
+            let held = mods.chord_held();
+            if self.was_held && !held {
+                host.reset_layout_session();
+            }
+            self.was_held = held;
+        }
+        Verdict::Continue
+    }
+}
+
+/// Ctrl+Shift+Alt+key (without Cmd, to avoid app shortcuts) runs the Form binding
+#[derive(Default)]
+pub struct FormChords {
+    warned_unknown: bool,
+}
+
+impl InputHandler for FormChords {
+    fn handle(&mut self, event: &InputEvent, _: &mut Sessions, host: &mut dyn InputHost) -> Verdict {
+        // Chord interception only acts on key down
+        let (key, mods, repeat) = match *event {
+            InputEvent::KeyDown { key, mods, repeat } => (key, mods, repeat),
+            _ => return Verdict::Continue,
+        };
+
+        // Plain typing never reaches the Form lookup below
+        if !mods.chord_held() || mods.cmd {
+            return Verdict::Pass;
+        }
+        // Ignore autorepeat, except for keys bound to incremental actions (NudgeAction/ResizeAction)
+        if repeat && !key.name.is_some_and(|k| host.key_accepts_autorepeat(k)) {
+            return Verdict::Pass;
+        }
+
+        // Always log chord keypresses to help diagnose keycode issues
+        eprintln!("DEBUG: {}+keycode=0x{:02x}", host.chord_label(), key.code);
+
+        let name = match key.name {
+            Some(name) => name,
+            None => {
+                // Warn once per session for unmapped keycodes
+                if !self.warned_unknown {
+                    self.warned_unknown = true;
+                    eprintln!("NOTE: {} on unmapped keycode=0x{:02x}. This key is not configured in form.xml.",
+                             host.chord_label(), key.code);
+                }
+                return Verdict::Pass;
+            }
+        };
+
+        if host.run_chord(name, repeat) { Verdict::Swallow } else { Verdict::Pass }
+    }
+}
+
+// ============================================================================
+// SECTION 4: Pipeline
+// ============================================================================
+
+pub struct InputPipeline {
+    handlers: Vec<Box<dyn InputHandler>>,
+    sessions: Sessions,
+}
+
+impl InputPipeline {
+    /// The full chain, in order (see module doc)
+    pub fn standard() -> Self {
+        Self::with_handlers(vec![
+            Box::new(SwitcherClickCancel),
+            Box::new(ClipboardHistory),
+            Box::new(CopyPasteMirror),
+            Box::new(Switcher),
+            Box::new(ChordRelease::default()),
+            Box::new(FormChords::default()),
+        ])
+    }
+
+    pub fn with_handlers(handlers: Vec<Box<dyn InputHandler>>) -> Self {
+        InputPipeline { handlers, sessions: Sessions::default() }
+    }
+
+    pub fn sessions(&self) -> &Sessions {
+        &self.sessions
+    }
+
+    /// Run one event through the chain; never returns Continue
+    pub fn handle(&mut self, event: &InputEvent, host: &mut dyn InputHost) -> Verdict {
+        for handler in self.handlers.iter_mut() {
+            match handler.handle(event, &mut self.sessions, host) {
+                Verdict::Continue => continue,
+                verdict => return verdict,
+            }
+        }
+        Verdict::Pass
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pbgim_mock::{click, flags, key, key_down, key_repeat, key_up, mods, MockInputHost};
+
+    fn host_with_windows(count: usize) -> MockInputHost {
+        let mut host = MockInputHost::new();
+        for idx in 0..count {
+            host.add_entry(&format!("app{}", idx), &format!("window {}", idx));
+        }
+        host
+    }
+
+    /// Run events through the pipeline; returns the verdicts
+    fn run(pipeline: &mut InputPipeline, host: &mut MockInputHost, events: &[InputEvent]) -> Vec<Verdict> {
+        events.iter().map(|event| pipeline.handle(event, host)).collect()
+    }
+
+    // ------------------------------------------------------------------------
+    // Switcher
+    // ------------------------------------------------------------------------
+
+    #[test]
+    fn switcher_cycles_and_commits_on_cmd_release() {
+        let mut pipeline = InputPipeline::standard();
+        let mut host = host_with_windows(3);
+
+        let verdicts = run(&mut pipeline, &mut host, &[
+            flags("cmd"),
+            key_down("tab", "cmd"),
+            key_repeat("tab", "cmd"),   // Auto-repeat never steps
+            key_up("tab", "cmd"),
+            key_down("tab", "cmd"),
+            key_down("tab", "cmd"),     // Wraps to the top
+            key_down("tab", "cmd+shift"),
+        ]);
+        assert_eq!(verdicts, [Verdict::Pass, Verdict::Swallow, Verdict::Swallow, Verdict::Swallow,
+            Verdict::Swallow, Verdict::Swallow, Verdict::Swallow]);
+        assert_eq!(host.effects(), ["prune_switcher", "show_switcher 1", "update_switcher 2",
+            "update_switcher 0", "update_switcher 2"]);
+        assert_eq!(pipeline.sessions().alt_tab.highlight_index, Some(2));
+
+        host.clear_effects();
+        assert_eq!(pipeline.handle(&flags(""), &mut host), Verdict::Pass);
+        assert_eq!(host.effects(), ["commit_switcher 2", "hide_switcher"]);
+        assert!(!pipeline.sessions().alt_tab.active);
+    }
+
+    #[test]
+    fn switcher_cancelled_by_click() {
+        let mut pipeline = InputPipeline::standard();
+        let mut host = host_with_windows(3);
+
+        // Shift+Tab first goes to the end of the list
+        run(&mut pipeline, &mut host, &[flags("cmd+shift"), key_down("tab", "cmd+shift")]);
+        assert_eq!(pipeline.handle(&click(), &mut host), Verdict::Swallow);
+        assert_eq!(pipeline.handle(&flags(""), &mut host), Verdict::Pass);
+        assert_eq!(host.effects(), ["prune_switcher", "show_switcher 2", "hide_switcher"]);
+
+        // No session: clicks pass
+        assert_eq!(pipeline.handle(&click(), &mut host), Verdict::Pass);
+    }
+
+    #[test]
+    fn switcher_without_windows_does_not_start() {
+        let mut pipeline = InputPipeline::standard();
+        let mut host = host_with_windows(0);
+        assert_eq!(pipeline.handle(&key_down("tab", "cmd"), &mut host), Verdict::Swallow);
+        assert_eq!(host.effects(), ["prune_switcher"]);
+        assert!(!pipeline.sessions().alt_tab.active);
+        assert_eq!(pipeline.handle(&key_up("tab", "cmd"), &mut host), Verdict::Pass);
+    }
+
+    // ------------------------------------------------------------------------
+    // Clipboard history and Ctrl→Cmd mirror
+    // ------------------------------------------------------------------------
+
+    /// The only test that touches the shared CLIPBOARD_HISTORY
+    #[test]
+    fn clipboard_overlay_navigates_and_pastes() {
+        let mut pipeline = InputPipeline::standard();
+        let mut host = MockInputHost::new();
+        let open = key_down("v", "ctrl+shift");
+
+        // Empty history: the overlay shows its message but takes no keys
+        CLIPBOARD_HISTORY.lock().unwrap().clear();
+        assert_eq!(pipeline.handle(&open, &mut host), Verdict::Swallow);
+        assert!(!pipeline.sessions().clipboard.active);
+
+        *CLIPBOARD_HISTORY.lock().unwrap() = vec!["one".to_string(), "two".to_string(), "three".to_string()];
+        host.clear_effects();
+        let verdicts = run(&mut pipeline, &mut host, &[
+            open,
+            key_down("down", ""),
+            key_down("down", ""),
+            key_down("down", ""),   // Clamped at the last entry
+            key_down("up", ""),
+            key_down("c", "ctrl"),  // Every key belongs to the overlay, even the mirror's
+            key_up("c", "ctrl"),
+            key_down("enter", ""),
+        ]);
+        assert_eq!(verdicts, [Verdict::Swallow, Verdict::Swallow, Verdict::Swallow, Verdict::Swallow,
+            Verdict::Swallow, Verdict::Swallow, Verdict::Pass, Verdict::Swallow]);
+        assert_eq!(host.effects(), ["show_clipboard 3 0", "update_clipboard 3 1", "update_clipboard 3 2",
+            "update_clipboard 3 1", "set_clipboard \"two\"", "hide_clipboard"]);
+        assert!(!pipeline.sessions().clipboard.active);
+
+        host.clear_effects();
+        run(&mut pipeline, &mut host, &[open, key_down("escape", "")]);
+        assert_eq!(host.effects(), ["show_clipboard 3 0", "hide_clipboard"]);
+        assert_eq!(pipeline.handle(&key_down("down", ""), &mut host), Verdict::Pass);
+    }
+
+    #[test]
+    fn ctrl_edit_keys_are_mirrored_as_cmd() {
+        let mut pipeline = InputPipeline::standard();
+        let mut host = MockInputHost::new();
+
+        let down = key_down("c", "ctrl");
+        let mirrored_down = InputEvent::KeyDown { key: key("c"), mods: Modifiers::COMMAND, repeat: false };
+        assert_eq!(pipeline.handle(&down, &mut host), Verdict::Replace(vec![down, mirrored_down]));
+
+        let up = key_up("c", "ctrl");
+        let mirrored_up = InputEvent::KeyUp { key: key("c"), mods: mods("cmd") };
+        assert_eq!(pipeline.handle(&up, &mut host), Verdict::Replace(vec![up, mirrored_up]));
+
+        // Shift, Alt or Cmd alongside, or another key: not an edit shortcut
+        for event in [key_down("x", "ctrl+shift"), key_down("v", "ctrl+alt"), key_down("c", "ctrl+cmd"), key_down("a", "ctrl")] {
+            assert_eq!(pipeline.handle(&event, &mut host), Verdict::Pass);
+        }
+        assert!(host.effects().is_empty());
+    }
+
+    // ------------------------------------------------------------------------
+    // Form chords
+    // ------------------------------------------------------------------------
+
+    #[test]
+    fn chord_keys_run_and_repeat_only_when_accepted() {
+        let mut pipeline = InputPipeline::standard();
+        let mut host = MockInputHost::new();
+        host.autorepeat_keys = vec!["n"];
+        let chord = "ctrl+shift+alt";
+
+        let verdicts = run(&mut pipeline, &mut host, &[
+            flags(chord),
+            key_down("u", chord),
+            key_repeat("u", chord),
+            key_down("n", chord),
+            key_repeat("n", chord),
+            key_down("u", "ctrl+shift+alt+cmd"),   // Cmd excludes app shortcuts
+            key_down("u", "ctrl+shift"),
+        ]);
+        assert_eq!(verdicts, [Verdict::Pass, Verdict::Swallow, Verdict::Pass, Verdict::Swallow,
+            Verdict::Swallow, Verdict::Pass, Verdict::Pass]);
+        assert_eq!(host.effects(), ["run_chord u", "run_chord n", "run_chord n repeat"]);
+
+        // Unmapped keys and a missing frontmost window pass
+        host.clear_effects();
+        let unmapped = InputEvent::KeyDown { key: Key { code: 0x7f, name: None }, mods: mods(chord), repeat: false };
+        assert_eq!(pipeline.handle(&unmapped, &mut host), Verdict::Pass);
+        host.frontmost = false;
+        assert_eq!(pipeline.handle(&key_down("u", chord), &mut host), Verdict::Pass);
+        assert!(host.effects().is_empty());
+    }
+
+    #[test]
+    fn chord_release_resets_once_and_ends_repeats() {
+        let mut pipeline = InputPipeline::standard();
+        let mut host = MockInputHost::new();
+        host.autorepeat_keys = vec!["n"];
+
+        let verdicts = run(&mut pipeline, &mut host, &[
+            flags("ctrl+shift+alt"),
+            key_down("n", "ctrl+shift+alt"),
+            flags("ctrl+shift"),
+            flags("ctrl"),
+            key_repeat("n", "ctrl"),   // Held key still repeating after the chord is gone
+        ]);
+        assert_eq!(verdicts, [Verdict::Pass, Verdict::Swallow, Verdict::Pass, Verdict::Pass, Verdict::Pass]);
+        assert_eq!(host.effects(), ["run_chord n", "reset_layout_session"]);
+    }
+}
@@ -0,0 +1,148 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+//! Recording Input Host
+//! Deterministic InputHost for driving the input pipeline with synthetic events
+//!
+//! Stands in for the platform: the MRU list is a plain vector, the frontmost
+//! window is a flag, and auto-repeat keys are a list. Every side effect the
+//! handlers ask for is appended to an effect log ("show_switcher 1",
+//! "run_chord home"), so a harness can feed an event stream through
+//! InputPipeline and compare verdicts and effects against expectations.
+//! Keys carry code 0; handlers only look at their Form names.
+
+use crate::pbgi_input::{InputEvent, InputHost, Key, Modifiers, SwitcherEntry};
+
+#[derive(Default)]
+pub struct MockInputHost {
+    pub entries: Vec<SwitcherEntry>,   // MRU list, most recent first
+    pub frontmost: bool,               // run_chord fails without a frontmost window
+    pub autorepeat_keys: Vec<&'static str>,
+    effects: Vec<String>,
+}
+
+impl MockInputHost {
+    pub fn new() -> Self {
+        MockInputHost { frontmost: true, ..Self::default() }
+    }
+
+    /// Add an MRU entry at the end (least recent)
+    pub fn add_entry(&mut self, app_id: &str, title: &str) {
+        self.entries.push(SwitcherEntry { app_id: app_id.to_string(), title: title.to_string() });
+    }
+
+    /// Effects requested so far, in order
+    pub fn effects(&self) -> Vec<String> {
+        self.effects.clone()
+    }
+
+    pub fn clear_effects(&mut self) {
+        self.effects.clear();
+    }
+
+    fn record(&mut self, effect: String) {
+        self.effects.push(effect);
+    }
+}
+
+impl InputHost for MockInputHost {
+    fn switcher_entries(&mut self) -> Vec<SwitcherEntry> {
+        self.entries.clone()
+    }
+
+    fn prune_switcher_entries(&mut self) {
+        self.record("prune_switcher".to_string());
+    }
+
+    fn show_switcher(&mut self, highlight: usize) {
+        self.record(format!("show_switcher {}", highlight));
+    }
+
+    fn update_switcher(&mut self, highlight: usize) {
+        self.record(format!("update_switcher {}", highlight));
+    }
+
+    fn hide_switcher(&mut self) {
+        self.record("hide_switcher".to_string());
+    }
+
+    fn commit_switcher(&mut self, index: usize) {
+        self.record(format!("commit_switcher {}", index));
+    }
+
+    fn show_clipboard(&mut self, entries: &[String], highlight: usize) {
+        self.record(format!("show_clipboard {} {}", entries.len(), highlight));
+    }
+
+    fn update_clipboard(&mut self, entries: &[String], highlight: usize) {
+        self.record(format!("update_clipboard {} {}", entries.len(), highlight));
+    }
+
+    fn hide_clipboard(&mut self) {
+        self.record("hide_clipboard".to_string());
+    }
+
+    fn set_clipboard_text(&mut self, text: &str) {
+        self.record(format!("set_clipboard {:?}", text));
+    }
+
+    fn key_accepts_autorepeat(&self, key: &str) -> bool {
+        self.autorepeat_keys.contains(&key)
+    }
+
+    fn run_chord(&mut self, key: &'static str, is_repeat: bool) -> bool {
+        if !self.frontmost {
+            return false;
+        }
+        self.record(format!("run_chord {}{}", key, if is_repeat { " repeat" } else { "" }));
+        true
+    }
+
+    fn reset_layout_session(&mut self) {
+        self.record("reset_layout_session".to_string());
+    }
+}
+
+// ============================================================================
+// Synthetic events
+// ============================================================================
+
+/// Modifiers from a "ctrl+shift+alt+cmd" style list ("" for none)
+pub fn mods(list: &str) -> Modifiers {
+    let mut mods = Modifiers::default();
+    for name in list.split('+').filter(|n| !n.is_empty()) {
+        match name {
+            "ctrl" => mods.ctrl = true,
+            "shift" => mods.shift = true,
+            "alt" => mods.alt = true,
+            "cmd" => mods.cmd = true,
+            other => panic!("unknown modifier {}", other),
+        }
+    }
+    mods
+}
+
+pub fn key(name: &'static str) -> Key {
+    Key { code: 0, name: Some(name) }
+}
+
+pub fn key_down(name: &'static str, modifiers: &str) -> InputEvent {
+    InputEvent::KeyDown { key: key(name), mods: mods(modifiers), repeat: false }
+}
+
+pub fn key_repeat(name: &'static str, modifiers: &str) -> InputEvent {
+    InputEvent::KeyDown { key: key(name), mods: mods(modifiers), repeat: true }
+}
+
+pub fn key_up(name: &'static str, modifiers: &str) -> InputEvent {
+    InputEvent::KeyUp { key: key(name), mods: mods(modifiers) }
+}
+
+/// Modifier press or release, leaving `modifiers` held
+pub fn flags(modifiers: &str) -> InputEvent {
+    InputEvent::FlagsChanged { code: 0, mods: mods(modifiers) }
+}
+
+pub fn click() -> InputEvent {
+    InputEvent::MouseDown { mods: Modifiers::default() }
+}
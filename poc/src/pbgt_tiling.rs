@@ -0,0 +1,320 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+//! Automatic Tiling Engine
+//! Platform-neutral window arrangement for displays that opt in via <AutoTile>
+//!
+//! This module contains:
+//! - Per-display window lists updated from add/remove/focus events
+//! - Arrangement computation in viewport fractions (the ratio is an exact
+//!   Fraction, but repeated splits are done in f64: their denominators grow
+//!   geometrically and would overflow Fraction after a dozen windows)
+//!
+//! The engine never touches windows. Platform glue feeds it events, then realizes
+//! the returned fractional rects on the affected displays. Only macOS has that
+//! glue (pbmpt_autotile); the <AutoTile> configuration itself (TilingConfig) is
+//! a Form type and lives in pbgft_types.
+
+use std::collections::HashMap;
+
+use crate::pbgfp_parse::Fraction;
+use crate::pbgft_types::{PaneFrac, TilingConfig, TilingMode};
+
+// ============================================================================
+// SECTION 1: Windows and events
+// ============================================================================
+
+/// Platform window handle as seen by the engine
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TiledWindow {
+    pub pid: u32,
+    pub window_id: u32,
+}
+
+/// Window lifecycle events fed by platform glue
+#[derive(Debug, Clone, Copy)]
+pub enum TilingEvent {
+    Added { window: TiledWindow, display: usize },
+    Removed { window: TiledWindow },
+    Focused { window: TiledWindow },
+}
+
+struct DisplayTiles {
+    config: TilingConfig,
+    windows: Vec<TiledWindow>,  // Arrangement order (first = master / first split)
+}
+
+// ============================================================================
+// SECTION 2: Engine state
+// ============================================================================
+
+pub struct TilingEngine {
+    displays: HashMap<usize, DisplayTiles>,
+    focused: Option<TiledWindow>,
+}
+
+impl TilingEngine {
+    pub fn new() -> Self {
+        TilingEngine {
+            displays: HashMap::new(),
+            focused: None,
+        }
+    }
+
+    /// Enable (Some) or disable (None) tiling for a display
+    pub(crate) fn configure(&mut self, display: usize, config: Option<TilingConfig>) {
+        match config {
+            Some(config) => {
+                self.displays.entry(display)
+                    .and_modify(|d| d.config = config)
+                    .or_insert(DisplayTiles { config, windows: Vec::new() });
+            }
+            None => {
+                self.displays.remove(&display);
+            }
+        }
+    }
+
+    /// True if any display has tiling enabled
+    pub fn is_enabled(&self) -> bool {
+        !self.displays.is_empty()
+    }
+
+    /// All windows currently managed, with their display
+    pub fn managed_windows(&self) -> Vec<(TiledWindow, usize)> {
+        self.displays.iter()
+            .flat_map(|(display, tiles)| tiles.windows.iter().map(move |w| (*w, *display)))
+            .collect()
+    }
+
+    fn display_of(&self, window: &TiledWindow) -> Option<usize> {
+        self.displays.iter()
+            .find(|(_, tiles)| tiles.windows.contains(window))
+            .map(|(display, _)| *display)
+    }
+
+    /// Apply an event; returns displays whose arrangement changed
+    pub fn handle_event(&mut self, event: TilingEvent) -> Vec<usize> {
+        let mut changed = Vec::new();
+
+        match event {
+            TilingEvent::Added { window, display } => {
+                let previous = self.display_of(&window);
+                if previous == Some(display) {
+                    return changed; // Already managed here
+                }
+
+                // Moved off a tiled display (or onto an untiled one): drop from old list
+                if let Some(old) = previous {
+                    if let Some(tiles) = self.displays.get_mut(&old) {
+                        tiles.windows.retain(|w| *w != window);
+                    }
+                    changed.push(old);
+                }
+
+                let focused = self.focused;
+                if let Some(tiles) = self.displays.get_mut(&display) {
+                    // New window goes right after the focused one (splits it in BSP)
+                    let insert_at = focused
+                        .and_then(|f| tiles.windows.iter().position(|w| *w == f))
+                        .map(|i| i + 1)
+                        .unwrap_or(tiles.windows.len());
+                    tiles.windows.insert(insert_at, window);
+                    changed.push(display);
+                }
+            }
+            TilingEvent::Removed { window } => {
+                if let Some(display) = self.display_of(&window) {
+                    if let Some(tiles) = self.displays.get_mut(&display) {
+                        tiles.windows.retain(|w| *w != window);
+                    }
+                    changed.push(display);
+                }
+                if self.focused == Some(window) {
+                    self.focused = None;
+                }
+            }
+            TilingEvent::Focused { window } => {
+                self.focused = Some(window);
+            }
+        }
+
+        changed
+    }
+
+    /// Fractional rects for every window on a tiled display, in arrangement order
+    pub fn layout(&self, display: usize) -> Vec<(TiledWindow, PaneFrac)> {
+        let tiles = match self.displays.get(&display) {
+            Some(t) => t,
+            None => return Vec::new(),
+        };
+
+        let regions = match tiles.config.mode {
+            TilingMode::Bsp => bsp_regions(tiles.windows.len(), tiles.config.ratio),
+            TilingMode::MasterStack => master_stack_regions(tiles.windows.len(), tiles.config.ratio),
+        };
+
+        tiles.windows.iter().copied().zip(regions).collect()
+    }
+}
+
+// ============================================================================
+// SECTION 3: Arrangement (viewport fractions)
+// ============================================================================
+
+fn full_region() -> PaneFrac {
+    PaneFrac { x: 0.0, y: 0.0, width: 1.0, height: 1.0 }
+}
+
+/// Dwindle BSP: each window takes `ratio` of the remaining region, alternating
+/// side-by-side and stacked splits; the last window takes what is left
+fn bsp_regions(count: usize, ratio: Fraction) -> Vec<PaneFrac> {
+    let ratio = ratio.to_f64();
+    let mut regions = Vec::with_capacity(count);
+    let mut remaining = full_region();
+
+    for i in 0..count {
+        if i + 1 == count {
+            regions.push(remaining);
+            break;
+        }
+
+        if i % 2 == 0 {
+            // Side-by-side split
+            let first_w = remaining.width * ratio;
+            regions.push(PaneFrac { width: first_w, ..remaining });
+            remaining = PaneFrac {
+                x: remaining.x + first_w,
+                width: remaining.width - first_w,
+                ..remaining
+            };
+        } else {
+            // Stacked split
+            let first_h = remaining.height * ratio;
+            regions.push(PaneFrac { height: first_h, ..remaining });
+            remaining = PaneFrac {
+                y: remaining.y + first_h,
+                height: remaining.height - first_h,
+                ..remaining
+            };
+        }
+    }
+
+    regions
+}
+
+/// Master/stack: first window gets `ratio` of the width, the rest share the
+/// remaining column in equal rows
+fn master_stack_regions(count: usize, ratio: Fraction) -> Vec<PaneFrac> {
+    if count == 0 {
+        return Vec::new();
+    }
+    if count == 1 {
+        return vec![full_region()];
+    }
+
+    let ratio = ratio.to_f64();
+    let stack_count = (count - 1) as f64;
+
+    let mut regions = vec![PaneFrac { x: 0.0, y: 0.0, width: ratio, height: 1.0 }];
+    for i in 0..count - 1 {
+        regions.push(PaneFrac {
+            x: ratio,
+            y: i as f64 / stack_count,
+            width: 1.0 - ratio,
+            height: 1.0 / stack_count,
+        });
+    }
+
+    regions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(id: u32) -> TiledWindow {
+        TiledWindow { pid: 100, window_id: id }
+    }
+
+    /// Tile `count` windows on display 0 and return the arrangement
+    fn tile(mode: TilingMode, ratio: Fraction, count: u32) -> Vec<(TiledWindow, PaneFrac)> {
+        let mut engine = TilingEngine::new();
+        engine.configure(0, Some(TilingConfig { mode, ratio }));
+        for id in 0..count {
+            engine.handle_event(TilingEvent::Added { window: window(id), display: 0 });
+        }
+        engine.layout(0)
+    }
+
+    /// Every window has area inside the viewport, none overlap, together they cover it
+    fn assert_partition(layout: &[(TiledWindow, PaneFrac)]) {
+        const EPSILON: f64 = 1e-9;
+        let mut total = 0.0;
+        for (i, (_, a)) in layout.iter().enumerate() {
+            assert!(a.width > 0.0 && a.height > 0.0, "window {} has no area: {:?}", i, a);
+            assert!(a.x >= 0.0 && a.y >= 0.0, "window {} outside: {:?}", i, a);
+            assert!(a.x + a.width <= 1.0 + EPSILON && a.y + a.height <= 1.0 + EPSILON, "window {} outside: {:?}", i, a);
+            total += a.width * a.height;
+
+            for (j, (_, b)) in layout.iter().enumerate().skip(i + 1) {
+                let overlap_w = (a.x + a.width).min(b.x + b.width) - a.x.max(b.x);
+                let overlap_h = (a.y + a.height).min(b.y + b.height) - a.y.max(b.y);
+                assert!(overlap_w <= EPSILON || overlap_h <= EPSILON, "windows {} and {} overlap", i, j);
+            }
+        }
+        assert!((total - 1.0).abs() < 1e-6, "windows cover {} of the viewport", total);
+    }
+
+    #[test]
+    fn bsp_tiles_many_windows() {
+        for ratio in [Fraction::new(1, 2), Fraction::new(3, 7), Fraction::new(9999, 10000)] {
+            let layout = tile(TilingMode::Bsp, ratio, 40);
+            assert_eq!(layout.len(), 40);
+            assert_partition(&layout);
+        }
+    }
+
+    #[test]
+    fn master_stack_tiles_many_windows() {
+        let layout = tile(TilingMode::MasterStack, Fraction::new(3, 5), 33);
+        assert_eq!(layout.len(), 33);
+        assert_partition(&layout);
+        assert_eq!(layout[0].1.width, 0.6);
+        assert!(layout[1..].iter().all(|(_, r)| (r.height - 1.0 / 32.0).abs() < 1e-12));
+    }
+
+    #[test]
+    fn bsp_splits_alternate() {
+        let layout = tile(TilingMode::Bsp, Fraction::new(1, 2), 3);
+        let rects: Vec<(f64, f64, f64, f64)> = layout.iter().map(|(_, r)| (r.x, r.y, r.width, r.height)).collect();
+        assert_eq!(rects, vec![(0.0, 0.0, 0.5, 1.0), (0.5, 0.0, 0.5, 0.5), (0.5, 0.5, 0.5, 0.5)]);
+    }
+
+    #[test]
+    fn events_reflow_tiled_displays_only() {
+        let mut engine = TilingEngine::new();
+        assert!(!engine.is_enabled());
+        engine.configure(0, Some(TilingConfig { mode: TilingMode::Bsp, ratio: Fraction::new(1, 2) }));
+        assert!(engine.is_enabled());
+
+        for id in 0..3 {
+            assert_eq!(engine.handle_event(TilingEvent::Added { window: window(id), display: 0 }), vec![0]);
+        }
+        // Untiled display: not managed, nothing to reflow
+        assert!(engine.handle_event(TilingEvent::Added { window: window(9), display: 1 }).is_empty());
+
+        // New window lands right after the focused one
+        engine.handle_event(TilingEvent::Focused { window: window(0) });
+        engine.handle_event(TilingEvent::Added { window: window(3), display: 0 });
+        let order: Vec<u32> = engine.layout(0).iter().map(|(w, _)| w.window_id).collect();
+        assert_eq!(order, vec![0, 3, 1, 2]);
+
+        assert_eq!(engine.handle_event(TilingEvent::Removed { window: window(0) }), vec![0]);
+        assert!(engine.handle_event(TilingEvent::Removed { window: window(9) }).is_empty());
+        let mut managed: Vec<u32> = engine.managed_windows().iter().map(|(w, _)| w.window_id).collect();
+        managed.sort();
+        assert_eq!(managed, vec![1, 2, 3]);
+        assert_partition(&engine.layout(0));
+    }
+}
@@ -0,0 +1,1027 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+//! Platform-Neutral Window Operations
+//! Window-system access behind one trait, and the window actions written once against it
+//!
+//! This module contains:
+//! - WindowBackend: focus, enumerate, get/set geometry and raise
+//! - Position→Size rect placement with the FAILED reasons the logs report
+//! - LayoutAction tiling (pane cycle, fallback policy, learned minimum sizes)
+//! - DisplayMove (size preservation, proportional mapping, clamping)
+//! - NudgeAction/ResizeAction steps (the platform supplies the viewport)
+//! - Directional focus without MRU history (Linux)
+//!
+//! Implementations: AxBackend (macOS Accessibility, pbmbw_window) and MockBackend
+//! (deterministic in-memory windows, pbgwm_mock). Geometry is global, top-left
+//! origin, Y down (AX convention). Functions here take the Form mutex and only
+//! hold it between backend calls, never across them.
+
+use std::sync::Mutex;
+
+use crate::pbgam_appmin::{effective_min_size, learn_from_set};
+use crate::pbgfr_resolve::Form;
+use crate::pbgft_types::{
+    Direction, DisplayMoveMode, DisplayMoveTarget, DisplayPlacement, DisplayProps, FallbackPolicy, NudgeSpec, PaneFrac,
+    PixelRect, ResizeSpec,
+};
+use crate::pbgg_geometry::{
+    align_to_pane_edge, center_in_pane, clamp_rect_to_viewport, frac_to_rect, has_area, next_pane_fitting, nudge_rect,
+    rect_to_frac, resize_rect, window_in_direction,
+};
+
+// ============================================================================
+// SECTION 1: Backend trait
+// ============================================================================
+
+/// Why a geometry write was rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowError {
+    #[cfg(any(target_os = "macos", test))]
+    Permission,     // Accessibility access missing or revoked (AX only)
+    Constrained,    // App refused the size (fixed-size window, fullscreen, ...)
+    Platform(i32),  // Any other backend error code
+}
+
+/// One window of an app, as listed by enumerate_windows
+#[derive(Debug, Clone)]
+pub struct WindowInfo {
+    pub window_id: u32,
+    pub title: String,
+    pub rect: Option<PixelRect>,
+    pub minimized: bool,
+}
+
+/// Window-system operations used by window actions
+pub trait WindowBackend {
+    /// Handle to one live window
+    type Window;
+
+    /// Focused window of an app
+    /// Err is the FAILED reason; "ax_not_ready_retry_needed" and
+    /// "ax_cannot_complete_retry_needed" mean the app may answer shortly
+    fn focused_window(&self, pid: u32) -> Result<Self::Window, String>;
+
+    /// Look up a window of an app by window ID
+    /// Here and in enumerate_windows/raise, pid 0 matches every app where the
+    /// backend can list windows system-wide (X11, i3); AX needs a real pid
+    fn window_by_id(&self, pid: u32, window_id: u32) -> Option<Self::Window>;
+
+    /// Standard windows of an app, in the window system's order
+    fn enumerate_windows(&self, pid: u32) -> Vec<WindowInfo>;
+
+    fn window_id(&self, win: &Self::Window) -> Option<u32>;
+
+    fn get_rect(&self, win: &Self::Window) -> Option<PixelRect>;
+
+    fn set_position(&self, win: &Self::Window, x: f64, y: f64) -> Result<(), WindowError>;
+
+    fn set_size(&self, win: &Self::Window, width: f64, height: f64) -> Result<(), WindowError>;
+
+    /// Focus and raise a window, restoring it first if minimized
+    fn raise(&self, pid: u32, window_id: u32) -> bool;
+
+    /// Whether a window still exists (MRU pruning, macOS only)
+    #[cfg(any(target_os = "macos", test))]
+    fn window_exists(&self, pid: u32, window_id: u32) -> bool {
+        self.window_by_id(pid, window_id).is_some()
+    }
+
+    /// Diagnostic tag appended to APPMIN log lines for an app (e.g. " [chromium]")
+    fn app_tag(&self, _app_id: &str) -> &'static str {
+        ""
+    }
+}
+
+// ============================================================================
+// SECTION 2: Rect placement
+// ============================================================================
+
+/// FAILED reason for a rejected position write
+/// Reasons keep the AX operation names the TILE/DISPLAYMOVE log format has always used
+fn position_reason(e: WindowError) -> String {
+    match e {
+        #[cfg(any(target_os = "macos", test))]
+        WindowError::Permission => "ax_permission_missing_or_revoked".to_string(),
+        WindowError::Platform(code) => format!("ax_error(code={}, op=AXPosition)", code),
+        WindowError::Constrained => "ax_error(code=-1, op=AXPosition)".to_string(),
+    }
+}
+
+/// FAILED reason for a rejected size write
+fn size_reason(e: WindowError) -> String {
+    match e {
+        #[cfg(any(target_os = "macos", test))]
+        WindowError::Permission => "ax_permission_missing_or_revoked".to_string(),
+        WindowError::Constrained => "size_constrained_or_fullscreen".to_string(),
+        WindowError::Platform(code) => format!("ax_error(code={}, op=AXSize)", code),
+    }
+}
+
+fn rects_match(a: &PixelRect, b: &PixelRect) -> (bool, bool) {
+    let pos = (a.x - b.x).abs() < 1.0 && (a.y - b.y).abs() < 1.0;
+    let size = (a.width - b.width).abs() < 1.0 && (a.height - b.height).abs() < 1.0;
+    (pos, size)
+}
+
+/// Set a window's frame using the Position → Size policy (Chrome-safe ordering)
+/// Err is the FAILED reason; "size_constrained_or_fullscreen" means only the size was refused
+pub fn set_window_rect<B: WindowBackend>(backend: &B, win: &B::Window, r: &PixelRect) -> Result<(), String> {
+    eprintln!("DEBUG: Using Position→Size policy (global experiment)");
+    eprintln!("DEBUG: set_window_rect() target: ({:.0},{:.0},{:.0},{:.0})", r.x, r.y, r.width, r.height);
+
+    match backend.get_rect(win) {
+        Some(b) => eprintln!("DEBUG: Before resize - current rect: ({:.0},{:.0},{:.0},{:.0})", b.x, b.y, b.width, b.height),
+        None => eprintln!("DEBUG: Before resize - could not get current rect"),
+    }
+
+    eprintln!("DEBUG: Setting position to ({:.0},{:.0})", r.x, r.y);
+    backend.set_position(win, r.x, r.y).map_err(|e| {
+        let reason = position_reason(e);
+        eprintln!("DEBUG: AXPosition failed: {}", reason);
+        reason
+    })?;
+
+    match backend.get_rect(win) {
+        Some(a) => eprintln!("DEBUG: After position change - current rect: ({:.0},{:.0},{:.0},{:.0})", a.x, a.y, a.width, a.height),
+        None => eprintln!("DEBUG: After position change - could not get current rect"),
+    }
+
+    eprintln!("DEBUG: Setting size to ({:.0},{:.0})", r.width, r.height);
+    backend.set_size(win, r.width, r.height).map_err(|e| {
+        let reason = size_reason(e);
+        eprintln!("DEBUG: AXSize failed: {}", reason);
+        reason
+    })?;
+
+    match backend.get_rect(win) {
+        Some(f) => {
+            eprintln!("DEBUG: Final rect after size change: ({:.0},{:.0},{:.0},{:.0})", f.x, f.y, f.width, f.height);
+            match rects_match(&f, r) {
+                (true, true) => eprintln!("DEBUG: SUCCESS - Final rect matches target"),
+                _ => eprintln!("DEBUG: Final rect does not exactly match target (app constraints likely)"),
+            }
+        }
+        None => eprintln!("DEBUG: Final rect - could not get current rect"),
+    }
+
+    Ok(())
+}
+
+/// Move a window to a new origin without touching its size
+pub fn reposition_window<B: WindowBackend>(backend: &B, win: &B::Window, to: &PixelRect) -> Result<(), String> {
+    backend.set_position(win, to.x, to.y).map_err(position_reason)
+}
+
+// ============================================================================
+// SECTION 3: LayoutAction tiling
+// ============================================================================
+
+/// A display as tiling sees it: design size for Form lookups, live viewport for placement
+#[derive(Debug, Clone)]
+pub struct TileDisplay {
+    pub props: DisplayProps,   // Design dimensions, quirks already applied
+    pub viewport: PixelRect,   // Live symmetric viewport in global coordinates
+}
+
+impl TileDisplay {
+    /// Convert a fractional pane to global pixels (unit conversion only)
+    /// Fractions scale by the design size and offset by the live viewport origin;
+    /// quirks were applied to the design size, so no further correction happens here
+    pub fn realize_pane(&self, frac: &PaneFrac) -> PixelRect {
+        PixelRect {
+            x: self.viewport.x + frac.x * self.props.width,
+            y: self.viewport.y + frac.y * self.props.height,
+            width: frac.width * self.props.width,
+            height: frac.height * self.props.height,
+        }
+    }
+
+    pub fn realize_panes(&self, fracs: &[PaneFrac]) -> Vec<PixelRect> {
+        fracs.iter().map(|f| self.realize_pane(f)).collect()
+    }
+}
+
+/// Why a key has no pane cycle on a display
+/// no_pane_for_key: nothing bound or the layout yields no panes there;
+/// no_usable_pane: panes exist but none passes the MIN_PANE_SIZE floor
+fn no_pane_reason(form: &Form, key: &str, display: &TileDisplay) -> String {
+    match form.panes_for_action(key, &display.props) {
+        Some(panes) if !panes.is_empty() => "no_usable_pane".to_string(),
+        _ => "no_pane_for_key".to_string(),
+    }
+}
+
+/// Tile a window into the next pane of a LayoutAction cycle (advances the chord session)
+/// Returns the pane index and the fallback applied (if any), or the failure reason
+pub fn tile_next_pane<B: WindowBackend>(
+    backend: &B,
+    form: &Mutex<Form>,
+    win: &B::Window,
+    key: &str,
+    display: &TileDisplay,
+    app_id: &str,
+) -> Result<(usize, Option<String>), String> {
+    let min_size = effective_min_size(app_id);
+    let mut form_guard = form.lock().unwrap();
+    let (frac, pane_idx) = form_guard.get_next_pane(key, &display.props, min_size)
+        .ok_or_else(|| no_pane_reason(&form_guard, key, display))?;
+    drop(form_guard);
+
+    // Pane cycle is already filtered to usable panes; convert to pixels
+    let pane = display.realize_pane(&frac);
+    place_in_pane(backend, form, win, &pane, key, pane_idx, min_size, display, app_id)
+        .map(|fallback| (pane_idx, fallback))
+}
+
+/// Tile a window into a fixed pane (0-based cycle index) without advancing the session
+/// Returns the fallback applied (if any), or the failure reason
+pub fn tile_fixed_pane<B: WindowBackend>(
+    backend: &B,
+    form: &Mutex<Form>,
+    win: &B::Window,
+    key: &str,
+    pane: usize,
+    display: &TileDisplay,
+    app_id: &str,
+) -> Result<Option<String>, String> {
+    let min_size = effective_min_size(app_id);
+    let form_guard = form.lock().unwrap();
+    let panes = form_guard.pane_cycle(key, &display.props, min_size)
+        .ok_or_else(|| no_pane_reason(&form_guard, key, display))?;
+    drop(form_guard);
+    let frac = panes.get(pane)
+        .ok_or_else(|| format!("pane_out_of_range(pane={} count={})", pane + 1, panes.len()))?;
+
+    let rect = display.realize_pane(frac);
+    place_in_pane(backend, form, win, &rect, key, pane, min_size, display, app_id)
+}
+
+/// Place a window in a LayoutAction pane, applying the app's fallback policy
+/// if it refuses the pane's size (size write constrained, or the final size differs)
+/// `min_size` is the app minimum the pane cycle (and `pane_idx`) was built with
+/// Returns the fallback outcome for the TILE line, None if the pane was honored
+#[allow(clippy::too_many_arguments)]
+fn place_in_pane<B: WindowBackend>(
+    backend: &B,
+    form: &Mutex<Form>,
+    win: &B::Window,
+    pane: &PixelRect,
+    key: &str,
+    pane_idx: usize,
+    min_size: (f64, f64),
+    display: &TileDisplay,
+    app_id: &str,
+) -> Result<Option<String>, String> {
+    // Size before the set tells a refusal to shrink apart from grid snapping
+    let before = backend.get_rect(win).map(|r| (r.width, r.height)).unwrap_or((0.0, 0.0));
+    let constrained = match set_window_rect(backend, win, pane) {
+        Ok(()) => false,
+        Err(reason) if reason == "size_constrained_or_fullscreen" => true,
+        Err(reason) => return Err(reason),
+    };
+
+    let achieved = match backend.get_rect(win) {
+        Some(a) => a,
+        None => return if constrained { Err("size_constrained_or_fullscreen".to_string()) } else { Ok(None) },
+    };
+    let (_, size_matches) = rects_match(&achieved, pane);
+
+    // Learn from accepted sets only: a refused size leaves the old size, not a minimum
+    if !constrained {
+        learn_from_set(app_id, before, (pane.width, pane.height), (achieved.width, achieved.height), backend.app_tag(app_id));
+    }
+    if !constrained && size_matches {
+        return Ok(None);
+    }
+
+    let policy = form.lock().unwrap().tile_fallback_for_app(app_id);
+    eprintln!("DEBUG: Size refused: wanted {:.0}x{:.0}, got {:.0}x{:.0} (fallback={})",
+             pane.width, pane.height, achieved.width, achieved.height, policy.as_str());
+
+    let viewport = &display.viewport;
+    match policy {
+        FallbackPolicy::None => Ok(Some("none".to_string())),
+        FallbackPolicy::Center => {
+            reposition_window(backend, win, &center_in_pane(pane, achieved.width, achieved.height, viewport))?;
+            Ok(Some("center".to_string()))
+        }
+        FallbackPolicy::AlignEdge => {
+            reposition_window(backend, win, &align_to_pane_edge(pane, achieved.width, achieved.height, viewport))?;
+            Ok(Some("align-edge".to_string()))
+        }
+        FallbackPolicy::NextLarger => {
+            // Same pane list (and order) pane_idx indexes: the minimum learned
+            // from this set above must not reshape it
+            let pane_list = form.lock().unwrap().pane_cycle(key, &display.props, min_size);
+            let realized = pane_list.map(|list| display.realize_panes(&list)).unwrap_or_default();
+
+            match next_pane_fitting(&realized, pane_idx, achieved.width, achieved.height) {
+                Some(idx) => {
+                    set_window_rect(backend, win, &realized[idx])
+                        .or_else(|reason| if reason == "size_constrained_or_fullscreen" { Ok(()) } else { Err(reason) })?;
+                    form.lock().unwrap().skip_layout_session_to(key, idx, realized.len());
+                    Ok(Some(format!("next-larger(pane={})", idx)))
+                }
+                None => {
+                    // Nothing in the cycle fits: keep the requested pane, centered
+                    reposition_window(backend, win, &center_in_pane(pane, achieved.width, achieved.height, viewport))?;
+                    Ok(Some("center(no_larger_pane)".to_string()))
+                }
+            }
+        }
+    }
+}
+
+// ============================================================================
+// SECTION 4: DisplayMove
+// ============================================================================
+
+/// Index of the display whose viewport contains the rect's center, 0 if none does
+pub fn display_index_for_rect(displays: &[DisplayPlacement], rect: &PixelRect) -> usize {
+    let cx = rect.x + rect.width / 2.0;
+    let cy = rect.y + rect.height / 2.0;
+    displays.iter()
+        .position(|d| {
+            let f = &d.frame;
+            cx >= f.x && cx < f.x + f.width && cy >= f.y && cy < f.y + f.height
+        })
+        .unwrap_or(0)
+}
+
+/// Move an app's focused window to the display a DisplayMove binding selects
+/// displays are the live placements in OS order (frame = symmetric viewport)
+/// Returns true if the move was executed, false if no binding, out of range or failed
+///
+/// Size preservation behavior per spec:
+/// 1. First move in chord: store original size
+/// 2. If window fits in target: preserve size ("preserved size")
+/// 3. If window too large:
+///    a. If original size would fit: restore original ("restored original size")
+///    b. Otherwise: resize to full target viewport ("resized to full screen (too large)")
+pub fn move_window_to_display<B: WindowBackend>(
+    backend: &B,
+    form: &Mutex<Form>,
+    key: &str,
+    pid: u32,
+    app_id: &str,
+    displays: &[DisplayPlacement],
+) -> bool {
+    if displays.is_empty() {
+        eprintln!("DISPLAYMOVE: key={} | FAILED reason=no_screens", key);
+        return false;
+    }
+
+    let win = match backend.focused_window(pid) {
+        Ok(w) => w,
+        Err(reason) => {
+            eprintln!("DISPLAYMOVE: key={} | FAILED reason={}", key, reason);
+            return false;
+        }
+    };
+
+    let current_rect = match backend.get_rect(&win) {
+        Some(r) => r,
+        None => {
+            eprintln!("DISPLAYMOVE: key={} | FAILED reason=cannot_get_current_rect", key);
+            return false;
+        }
+    };
+
+    let current_display_index = display_index_for_rect(displays, &current_rect);
+
+    // Look up target display from Form (returns both index and target spec)
+    let mut form = form.lock().unwrap();
+    let (target_display_index, target_spec) = match form.execute_display_move(key, current_display_index, displays) {
+        Some(result) => result,
+        None => {
+            // No binding or out of range (already logged)
+            return false;
+        }
+    };
+
+    // Format target for logging (spec format: "next", "prev", direction, name:, id:, or index number)
+    let target_string: String = match &target_spec.target {
+        DisplayMoveTarget::Next { .. } => "next".to_string(),
+        DisplayMoveTarget::Prev { .. } => "prev".to_string(),
+        DisplayMoveTarget::Index(idx) => idx.to_string(),
+        DisplayMoveTarget::Direction(direction) => direction.as_str().to_string(),
+        DisplayMoveTarget::Name(pattern) => format!("name:{}", pattern),
+        DisplayMoveTarget::Identity(identity) => format!("id:{}", identity),
+    };
+    let mode = target_spec.mode;
+
+    // If already on target display, no-op
+    if target_display_index == current_display_index {
+        eprintln!("DISPLAYMOVE: Already on target display {}", target_display_index);
+        return false;
+    }
+
+    let current_vf = &displays[current_display_index].frame;
+    let target_vf = &displays[target_display_index].frame;
+    if !has_area(current_vf) || !has_area(target_vf) {
+        eprintln!("DISPLAYMOVE: key={} | FAILED reason=no_visible_frame", key);
+        return false;
+    }
+
+    // Current offset within the viewport (relative position) and as fractions
+    let current_offset_x = current_rect.x - current_vf.x;
+    let current_offset_y = current_rect.y - current_vf.y;
+    let current_frac = rect_to_frac(&current_rect, current_vf);
+
+    // Store original size, offset AND fraction on first move in chord
+    form.start_display_move_session(current_rect.width, current_rect.height, current_offset_x, current_offset_y, current_frac.clone());
+    let original_size = form.get_original_size();
+    let original_offset = form.get_original_offset();
+    let original_frac = form.get_original_frac();
+    drop(form); // Release lock before window-system writes
+
+    // Determine final size and position, tracking which case applies
+    // Per spec: restore original when "returning to a display large enough to accommodate it"
+    let (final_w, final_h, offset_x, offset_y, size_action): (f64, f64, f64, f64, &str);
+
+    // Check if original size would fit (prioritize restoring original per spec)
+    let original_fits = original_size.is_some_and(|(ow, oh)| ow <= target_vf.width && oh <= target_vf.height);
+
+    // Check if current size fits in target viewport
+    let current_fits = current_rect.width <= target_vf.width && current_rect.height <= target_vf.height;
+
+    if mode == DisplayMoveMode::Proportional {
+        // Proportional: carry the session's fractional rect into the target viewport
+        let frac = original_frac.unwrap_or(current_frac);
+        let mapped = clamp_rect_to_viewport(&frac_to_rect(&frac, target_vf), target_vf);
+        final_w = mapped.width;
+        final_h = mapped.height;
+        offset_x = mapped.x - target_vf.x;
+        offset_y = mapped.y - target_vf.y;
+        size_action = "proportional";
+    } else if original_fits {
+        // Case 1: Original size fits - restore it with original offset
+        let (ow, oh) = original_size.unwrap();
+        let (ox, oy) = original_offset.unwrap_or((current_offset_x, current_offset_y));
+        final_w = ow;
+        final_h = oh;
+        offset_x = ox;
+        offset_y = oy;
+        size_action = "restored original size";
+    } else if current_fits {
+        // Case 2: Current size fits (but original too large) - preserve current with current offset
+        final_w = current_rect.width;
+        final_h = current_rect.height;
+        offset_x = current_offset_x;
+        offset_y = current_offset_y;
+        size_action = "preserved size";
+    } else {
+        // Case 3: Neither fits - resize to full target viewport, position at origin
+        final_w = target_vf.width;
+        final_h = target_vf.height;
+        offset_x = 0.0;
+        offset_y = 0.0;
+        size_action = "resized to full screen (too large)";
+    }
+
+    // Apply offset, then clamp so the window stays within the target viewport
+    let max_x = target_vf.x + target_vf.width - final_w;
+    let max_y = target_vf.y + target_vf.height - final_h;
+    let new_x = (target_vf.x + offset_x).min(max_x).max(target_vf.x);
+    let new_y = (target_vf.y + offset_y).min(max_y).max(target_vf.y);
+
+    // Apply position first (Position → Size policy)
+    if let Err(e) = backend.set_position(&win, new_x, new_y) {
+        let reason = match e {
+            #[cfg(any(target_os = "macos", test))]
+            WindowError::Permission => "ax_permission_missing_or_revoked",
+            WindowError::Platform(code) => {
+                eprintln!("DEBUG: set_position failed with code {}", code);
+                "ax_error(op=AXSetPosition)"
+            }
+            WindowError::Constrained => "ax_error(op=AXSetPosition)",
+        };
+        eprintln!("DISPLAYMOVE: FAILED target={} | reason={}", target_string, reason);
+        return false;
+    }
+
+    // Apply size if it changed
+    let size_changed = (final_w - current_rect.width).abs() > 1.0 || (final_h - current_rect.height).abs() > 1.0;
+    if size_changed {
+        if let Err(e) = backend.set_size(&win, final_w, final_h) {
+            let reason = match e {
+                #[cfg(any(target_os = "macos", test))]
+                WindowError::Permission => "ax_permission_missing_or_revoked",
+                WindowError::Constrained => "size_constrained_or_fullscreen",
+                WindowError::Platform(code) => {
+                    eprintln!("DEBUG: set_size failed with code {}", code);
+                    "ax_error(op=AXSetSize)"
+                }
+            };
+            eprintln!("DISPLAYMOVE: FAILED target={} | reason={}", target_string, reason);
+            return false;
+        }
+    }
+
+    // Success - log per spec format
+    println!("DISPLAYMOVE: SUCCESS target={} | {}", target_string, size_action);
+    eprintln!(
+        "DEBUG: DISPLAYMOVE app=\"{}\" from_display={} to_display={} (\"{}\" id={}) frame=({:.0},{:.0},{:.0},{:.0})",
+        app_id, current_display_index, target_display_index,
+        displays[target_display_index].name, displays[target_display_index].identity,
+        new_x, new_y, final_w, final_h
+    );
+    true
+}
+
+// ============================================================================
+// SECTION 5: Nudge and Resize
+// ============================================================================
+
+/// Smallest width/height a ResizeAction will shrink a window to
+const MIN_WINDOW_SIZE: f64 = 100.0;
+
+/// Move an app's focused window by the bound step
+/// `viewport_for` maps the window's rect to the symmetric viewport of its display
+/// Returns true if the window moved, false if already at the viewport edge or on failure
+pub fn nudge_focused_window<B: WindowBackend>(
+    backend: &B,
+    pid: u32,
+    spec: NudgeSpec,
+    viewport_for: impl Fn(&PixelRect) -> Option<PixelRect>,
+) -> bool {
+    let dir = spec.direction.as_str();
+
+    let win = match backend.focused_window(pid) {
+        Ok(w) => w,
+        Err(reason) => {
+            eprintln!("NUDGE: direction={} | FAILED reason={}", dir, reason);
+            return false;
+        }
+    };
+    let from = match backend.get_rect(&win) {
+        Some(r) => r,
+        None => {
+            eprintln!("NUDGE: direction={} | FAILED reason=cannot_get_current_rect", dir);
+            return false;
+        }
+    };
+    let viewport = match viewport_for(&from) {
+        Some(v) => v,
+        None => {
+            eprintln!("NUDGE: direction={} | FAILED reason=no_visible_frame", dir);
+            return false;
+        }
+    };
+
+    let to = nudge_rect(&from, &viewport, spec.direction, spec.amount);
+    if rects_match(&to, &from).0 {
+        eprintln!("NUDGE: direction={} | at viewport edge", dir);
+        return false;
+    }
+
+    // Position only - size is unchanged
+    if let Err(reason) = reposition_window(backend, &win, &to) {
+        eprintln!("NUDGE: direction={} | FAILED reason={}", dir, reason);
+        return false;
+    }
+
+    println!("NUDGE: direction={} | SUCCESS pos=({:.0},{:.0})", dir, to.x, to.y);
+    true
+}
+
+/// Move one edge of an app's focused window by the bound step
+/// `viewport_for` maps the window's rect to the symmetric viewport of its display
+/// Returns true if the window changed, false if at a limit or on failure
+pub fn resize_focused_window<B: WindowBackend>(
+    backend: &B,
+    pid: u32,
+    spec: ResizeSpec,
+    viewport_for: impl Fn(&PixelRect) -> Option<PixelRect>,
+) -> bool {
+    let edge = spec.edge.as_str();
+    let op = if spec.grow { "grow" } else { "shrink" };
+
+    let win = match backend.focused_window(pid) {
+        Ok(w) => w,
+        Err(reason) => {
+            eprintln!("RESIZE: edge={} op={} | FAILED reason={}", edge, op, reason);
+            return false;
+        }
+    };
+    let from = match backend.get_rect(&win) {
+        Some(r) => r,
+        None => {
+            eprintln!("RESIZE: edge={} op={} | FAILED reason=cannot_get_current_rect", edge, op);
+            return false;
+        }
+    };
+    let viewport = match viewport_for(&from) {
+        Some(v) => v,
+        None => {
+            eprintln!("RESIZE: edge={} op={} | FAILED reason=no_visible_frame", edge, op);
+            return false;
+        }
+    };
+
+    let to = resize_rect(&from, &viewport, spec.edge, spec.amount, spec.grow, MIN_WINDOW_SIZE);
+    if rects_match(&to, &from) == (true, true) {
+        eprintln!("RESIZE: edge={} op={} | at limit", edge, op);
+        return false;
+    }
+
+    match set_window_rect(backend, &win, &to) {
+        Ok(()) => {
+            println!("RESIZE: edge={} op={} | SUCCESS frame=({:.0},{:.0},{:.0},{:.0})",
+                edge, op, to.x, to.y, to.width, to.height);
+            true
+        }
+        Err(reason) => {
+            eprintln!("RESIZE: edge={} op={} | FAILED reason={}", edge, op, reason);
+            false
+        }
+    }
+}
+
+// ============================================================================
+// SECTION 6: Directional focus
+// ============================================================================
+
+/// Focus the nearest window in a direction from an app's focused window
+/// Candidates are every app's visible windows (pid 0 enumeration), ranked for
+/// ties by the window system's order: this path keeps no MRU history. macOS
+/// ranks by its MRU stack instead (pbmsf_focus).
+/// Returns true if focus moved, false if no candidate or focus failed
+pub fn focus_in_direction<B: WindowBackend>(backend: &B, pid: u32, direction: Direction) -> bool {
+    let dir = direction.as_str();
+
+    let win = match backend.focused_window(pid) {
+        Ok(w) => w,
+        Err(reason) => {
+            eprintln!("FOCUS: direction={} | FAILED reason={}", dir, reason);
+            return false;
+        }
+    };
+    let from = match backend.get_rect(&win) {
+        Some(r) => r,
+        None => {
+            eprintln!("FOCUS: direction={} | FAILED reason=cannot_get_current_rect", dir);
+            return false;
+        }
+    };
+    let from_id = backend.window_id(&win);
+
+    let mut candidates: Vec<(PixelRect, usize)> = Vec::new();
+    let mut windows: Vec<WindowInfo> = Vec::new();
+    for w in backend.enumerate_windows(0) {
+        match &w.rect {
+            Some(r) if !w.minimized && Some(w.window_id) != from_id && has_area(r) => {
+                candidates.push((r.clone(), candidates.len()));
+                windows.push(w);
+            }
+            _ => {}
+        }
+    }
+
+    let target = match window_in_direction(&from, &candidates, direction) {
+        Some(idx) => &windows[idx],
+        None => {
+            eprintln!("FOCUS: direction={} | no window in that direction ({} candidates)", dir, candidates.len());
+            return false;
+        }
+    };
+
+    if backend.raise(0, target.window_id) {
+        println!("FOCUS: direction={} | SUCCESS win=\"{}\"", dir, target.title);
+        true
+    } else {
+        eprintln!("FOCUS: direction={} | FAILED reason=focus_failed win=\"{}\"", dir, target.title);
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pbgft_types::{DisplayIdentity, Edge};
+    use crate::pbgwm_mock::{MockBackend, MockWindow};
+
+    const PID: u32 = 42;
+    const WIN: u32 = 7;
+
+    fn rect(x: f64, y: f64, width: f64, height: f64) -> PixelRect {
+        PixelRect { x, y, width, height }
+    }
+
+    fn form(body: &str) -> Mutex<Form> {
+        let xml = format!("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<Form>\n{}\n</Form>", body);
+        match Form::parse_and_build(&xml, &[]) {
+            Ok((_, form)) => Mutex::new(form),
+            Err(errors) => panic!("form did not build: {:?}", errors),
+        }
+    }
+
+    fn display(width: f64, height: f64) -> TileDisplay {
+        TileDisplay {
+            props: DisplayProps { width, height, name: "Test".to_string() },
+            viewport: rect(0.0, 0.0, width, height),
+        }
+    }
+
+    /// Backend with one focused window
+    fn backend(window: MockWindow) -> MockBackend {
+        let backend = MockBackend::new();
+        backend.add_window(window);
+        backend
+    }
+
+    fn rect_of(backend: &MockBackend) -> (f64, f64, f64, f64) {
+        let r = backend.window(WIN).unwrap().rect;
+        (r.x, r.y, r.width, r.height)
+    }
+
+    /// Three columns, left to right; `columns` gives the x and width of each
+    fn columns(fallback: &str, columns: &[(&str, &str)]) -> Mutex<Form> {
+        let panes: String = columns.iter()
+            .map(|(x, w)| format!(r#"<Pane x="{}" y="0" width="{}" height="1"/>"#, x, w))
+            .collect();
+        let leaves = "<Leaf/>".repeat(columns.len());
+        form(&format!(r#"
+  <TileFallback policy="{}"/>
+  <Frame name="cols">{}</Frame>
+  <Layout name="cols"><Shape frame="cols">{}</Shape></Layout>
+  <LayoutAction key="u" layout="cols"/>"#, fallback, panes, leaves))
+    }
+
+    const THIRDS: [(&str, &str); 3] = [("0", "1/3"), ("1/3", "1/3"), ("2/3", "1/3")];
+
+    /// A window that will not shrink below 600pt wide
+    fn wide_window() -> MockWindow {
+        let mut window = MockWindow::new(PID, WIN, rect(100.0, 100.0, 700.0, 300.0));
+        window.min_size = (600.0, 0.0);
+        window
+    }
+
+    // ------------------------------------------------------------------------
+    // LayoutAction tiling
+    // ------------------------------------------------------------------------
+
+    #[test]
+    fn tile_next_pane_walks_the_cycle() {
+        let form = columns("center", &[("0", "1/2"), ("1/2", "1/2")]);
+        let backend = backend(MockWindow::new(PID, WIN, rect(100.0, 100.0, 300.0, 300.0)));
+        let display = display(1000.0, 800.0);
+
+        assert_eq!(tile_next_pane(&backend, &form, &WIN, "u", &display, "test.walk"), Ok((0, None)));
+        assert_eq!(rect_of(&backend), (0.0, 0.0, 500.0, 800.0));
+        assert_eq!(tile_next_pane(&backend, &form, &WIN, "u", &display, "test.walk"), Ok((1, None)));
+        assert_eq!(rect_of(&backend), (500.0, 0.0, 500.0, 800.0));
+        assert_eq!(tile_next_pane(&backend, &form, &WIN, "u", &display, "test.walk"), Ok((0, None)));
+
+        // Position before size, every time
+        backend.clear_ops();
+        assert_eq!(tile_next_pane(&backend, &form, &WIN, "u", &display, "test.walk"), Ok((1, None)));
+        assert_eq!(backend.ops(), ["position 7 (500,0)", "size 7 500x800"]);
+    }
+
+    #[test]
+    fn tile_next_pane_reports_why_no_pane() {
+        let form = columns("center", &[("0", "1/2"), ("1/2", "1/2")]);
+        let backend = backend(MockWindow::new(PID, WIN, rect(0.0, 0.0, 50.0, 50.0)));
+
+        assert_eq!(tile_next_pane(&backend, &form, &WIN, "x", &display(1000.0, 800.0), "test.reason"),
+            Err("no_pane_for_key".to_string()));
+        // Halves of a 150pt display are below the MIN_PANE_SIZE floor
+        assert_eq!(tile_next_pane(&backend, &form, &WIN, "u", &display(150.0, 150.0), "test.reason"),
+            Err("no_usable_pane".to_string()));
+        assert!(backend.ops().is_empty());
+    }
+
+    #[test]
+    fn tile_fixed_pane_out_of_range() {
+        let form = columns("center", &THIRDS);
+        let backend = backend(MockWindow::new(PID, WIN, rect(0.0, 0.0, 50.0, 50.0)));
+        assert_eq!(tile_fixed_pane(&backend, &form, &WIN, "u", 3, &display(1500.0, 800.0), "test.range"),
+            Err("pane_out_of_range(pane=4 count=3)".to_string()));
+    }
+
+    #[test]
+    fn refused_size_is_centered_on_the_pane() {
+        let form = columns("center", &THIRDS);
+        let backend = backend(wide_window());
+        let result = tile_fixed_pane(&backend, &form, &WIN, "u", 1, &display(1500.0, 800.0), "test.center");
+        assert_eq!(result, Ok(Some("center".to_string())));
+        assert_eq!(rect_of(&backend), (450.0, 0.0, 600.0, 800.0));
+    }
+
+    #[test]
+    fn refused_size_aligns_to_the_display_edge() {
+        let form = columns("align-edge", &THIRDS);
+        let backend = backend(wide_window());
+        let result = tile_fixed_pane(&backend, &form, &WIN, "u", 2, &display(1500.0, 800.0), "test.align");
+        assert_eq!(result, Ok(Some("align-edge".to_string())));
+        assert_eq!(rect_of(&backend), (900.0, 0.0, 600.0, 800.0));
+    }
+
+    #[test]
+    fn refused_size_left_alone_with_policy_none() {
+        let form = columns("none", &THIRDS);
+        let backend = backend(wide_window());
+        let result = tile_fixed_pane(&backend, &form, &WIN, "u", 2, &display(1500.0, 800.0), "test.none");
+        assert_eq!(result, Ok(Some("none".to_string())));
+        assert_eq!(rect_of(&backend), (1000.0, 0.0, 600.0, 800.0));
+    }
+
+    #[test]
+    fn refused_size_moves_to_the_next_larger_pane() {
+        // Cycle (largest first): 2/3 column, then 1/3 column
+        let form = columns("next-larger", &[("0", "2/3"), ("2/3", "1/3")]);
+        let backend = backend(wide_window());
+        let result = tile_fixed_pane(&backend, &form, &WIN, "u", 1, &display(1500.0, 800.0), "test.larger");
+        assert_eq!(result, Ok(Some("next-larger(pane=0)".to_string())));
+        assert_eq!(rect_of(&backend), (0.0, 0.0, 1000.0, 800.0));
+    }
+
+    #[test]
+    fn next_larger_without_a_fitting_pane_centers() {
+        let form = columns("next-larger", &THIRDS);
+        let backend = backend(wide_window());
+        let result = tile_fixed_pane(&backend, &form, &WIN, "u", 1, &display(1500.0, 800.0), "test.nolarger");
+        assert_eq!(result, Ok(Some("center(no_larger_pane)".to_string())));
+        assert_eq!(rect_of(&backend), (450.0, 0.0, 600.0, 800.0));
+    }
+
+    #[test]
+    fn fixed_size_window_keeps_its_size() {
+        let form = columns("center", &[("0", "1/2"), ("1/2", "1/2")]);
+        let mut window = MockWindow::new(PID, WIN, rect(700.0, 700.0, 300.0, 200.0));
+        window.fixed_size = true;
+        let backend = backend(window);
+        let result = tile_next_pane(&backend, &form, &WIN, "u", &display(1000.0, 800.0), "test.fixed");
+        assert_eq!(result, Ok((0, Some("center".to_string()))));
+        assert_eq!(rect_of(&backend), (100.0, 300.0, 300.0, 200.0));
+    }
+
+    #[test]
+    fn denied_writes_fail_with_permission_reason() {
+        let form = columns("center", &[("0", "1/2"), ("1/2", "1/2")]);
+        let backend = backend(MockWindow::new(PID, WIN, rect(0.0, 0.0, 300.0, 300.0)));
+        backend.set_permission_denied(true);
+        let result = tile_next_pane(&backend, &form, &WIN, "u", &display(1000.0, 800.0), "test.denied");
+        assert_eq!(result, Err("ax_permission_missing_or_revoked".to_string()));
+    }
+
+    #[test]
+    fn backend_raise_focuses_and_restores() {
+        let backend = backend(MockWindow::new(PID, WIN, rect(0.0, 0.0, 300.0, 300.0)));
+        let mut other = MockWindow::new(PID, 8, rect(400.0, 0.0, 300.0, 300.0));
+        other.minimized = true;
+        backend.add_window(other);
+        backend.set_focused(PID, WIN);
+
+        let listed: Vec<(u32, bool)> = backend.enumerate_windows(PID).iter().map(|w| (w.window_id, w.minimized)).collect();
+        assert_eq!(listed, [(WIN, false), (8, true)]);
+        assert_eq!(backend.enumerate_windows(PID)[1].title, "<win:8>");
+
+        assert!(backend.raise(PID, 8));
+        assert_eq!(backend.focused_id(PID), Some(8));
+        assert!(!backend.window(8).unwrap().minimized);
+        assert!(!backend.raise(PID + 1, 8));
+
+        backend.remove_window(8);
+        assert!(!backend.window_exists(PID, 8));
+        assert_eq!(backend.focused_window(PID), Err("no_focused_window".to_string()));
+        assert_eq!(backend.window_id(&WIN), Some(WIN));
+    }
+
+    // ------------------------------------------------------------------------
+    // DisplayMove
+    // ------------------------------------------------------------------------
+
+    /// A 1000x800 display with a 1600x900 display to its right
+    fn placements() -> Vec<DisplayPlacement> {
+        vec![
+            DisplayPlacement { frame: rect(0.0, 0.0, 1000.0, 800.0), name: "Built-in".to_string(), identity: DisplayIdentity::default() },
+            DisplayPlacement { frame: rect(1000.0, 0.0, 1600.0, 900.0), name: "External".to_string(), identity: DisplayIdentity::default() },
+        ]
+    }
+
+    fn display_moves() -> Mutex<Form> {
+        form(r#"
+  <DisplayMove key="m" target="next" wrap="true"/>
+  <DisplayMove key="0" target="0"/>
+  <DisplayMove key="p" target="left" mode="proportional"/>"#)
+    }
+
+    #[test]
+    fn display_move_preserves_size_and_offset() {
+        let form = display_moves();
+        let backend = backend(MockWindow::new(PID, WIN, rect(100.0, 50.0, 400.0, 300.0)));
+        assert!(move_window_to_display(&backend, &form, "m", PID, "test.move", &placements()));
+        assert_eq!(rect_of(&backend), (1100.0, 50.0, 400.0, 300.0));
+        assert_eq!(backend.ops(), ["position 7 (1100,50)"]);
+
+        // Already there: no-op
+        assert!(!move_window_to_display(&backend, &form, "m", PID, "test.move", &placements()[1..]));
+    }
+
+    #[test]
+    fn display_move_too_large_fills_target_then_restores() {
+        let form = display_moves();
+        let backend = backend(MockWindow::new(PID, WIN, rect(1100.0, 0.0, 1200.0, 900.0)));
+        assert!(move_window_to_display(&backend, &form, "0", PID, "test.large", &placements()));
+        assert_eq!(rect_of(&backend), (0.0, 0.0, 1000.0, 800.0));
+
+        // Same chord, back to the large display: original size and offset return
+        assert!(move_window_to_display(&backend, &form, "m", PID, "test.large", &placements()));
+        assert_eq!(rect_of(&backend), (1100.0, 0.0, 1200.0, 900.0));
+    }
+
+    #[test]
+    fn display_move_proportional_maps_fractions() {
+        let form = display_moves();
+        let backend = backend(MockWindow::new(PID, WIN, rect(1400.0, 225.0, 800.0, 450.0)));
+        assert!(move_window_to_display(&backend, &form, "p", PID, "test.prop", &placements()));
+        assert_eq!(rect_of(&backend), (250.0, 200.0, 500.0, 400.0));
+    }
+
+    #[test]
+    fn display_move_failures() {
+        let form = display_moves();
+        let backend = backend(MockWindow::new(PID, WIN, rect(100.0, 50.0, 400.0, 300.0)));
+        assert!(!move_window_to_display(&backend, &form, "m", PID, "test.fail", &[]));
+        assert!(!move_window_to_display(&backend, &form, "x", PID, "test.fail", &placements()));
+
+        backend.set_not_ready(PID, 1);
+        assert!(!move_window_to_display(&backend, &form, "m", PID, "test.fail", &placements()));
+        backend.set_permission_denied(true);
+        assert!(!move_window_to_display(&backend, &form, "m", PID, "test.fail", &placements()));
+        assert_eq!(rect_of(&backend), (100.0, 50.0, 400.0, 300.0));
+    }
+
+    // ------------------------------------------------------------------------
+    // Nudge and Resize
+    // ------------------------------------------------------------------------
+
+    fn viewport(_: &PixelRect) -> Option<PixelRect> {
+        Some(rect(0.0, 0.0, 1000.0, 800.0))
+    }
+
+    #[test]
+    fn nudge_steps_by_viewport_fraction_until_the_edge() {
+        let backend = backend(MockWindow::new(PID, WIN, rect(100.0, 100.0, 300.0, 300.0)));
+        let left = NudgeSpec { direction: Direction::Left, amount: 0.06 };
+
+        assert!(nudge_focused_window(&backend, PID, left, viewport));
+        assert_eq!(rect_of(&backend), (40.0, 100.0, 300.0, 300.0));
+        assert!(nudge_focused_window(&backend, PID, left, viewport));
+        assert_eq!(rect_of(&backend), (0.0, 100.0, 300.0, 300.0));
+        assert!(!nudge_focused_window(&backend, PID, left, viewport));
+
+        // Position only, never a size write
+        assert_eq!(backend.ops(), ["position 7 (40,100)", "position 7 (0,100)"]);
+        assert!(!nudge_focused_window(&backend, PID, left, |_| None));
+    }
+
+    #[test]
+    fn resize_moves_one_edge_down_to_the_minimum() {
+        let backend = backend(MockWindow::new(PID, WIN, rect(100.0, 100.0, 300.0, 300.0)));
+        let shrink = ResizeSpec { edge: Edge::Right, amount: 0.15, grow: false };
+
+        assert!(resize_focused_window(&backend, PID, shrink, viewport));
+        assert_eq!(rect_of(&backend), (100.0, 100.0, 150.0, 300.0));
+        assert!(resize_focused_window(&backend, PID, shrink, viewport));
+        assert_eq!(rect_of(&backend), (100.0, 100.0, MIN_WINDOW_SIZE, 300.0));
+        assert!(!resize_focused_window(&backend, PID, shrink, viewport));
+
+        let grow_top = ResizeSpec { edge: Edge::Top, amount: 0.5, grow: true };
+        assert!(resize_focused_window(&backend, PID, grow_top, viewport));
+        assert_eq!(rect_of(&backend), (100.0, 0.0, MIN_WINDOW_SIZE, 400.0));
+
+        let grow_bottom = ResizeSpec { edge: Edge::Bottom, amount: 0.1, grow: true };
+        backend.set_permission_denied(true);
+        assert!(!resize_focused_window(&backend, PID, grow_bottom, viewport));
+        assert_eq!(rect_of(&backend), (100.0, 0.0, MIN_WINDOW_SIZE, 400.0));
+    }
+
+    // ------------------------------------------------------------------------
+    // Directional focus
+    // ------------------------------------------------------------------------
+
+    #[test]
+    fn focus_in_direction_raises_the_nearest_visible_window() {
+        let backend = backend(MockWindow::new(PID, WIN, rect(400.0, 0.0, 200.0, 200.0)));
+        backend.add_window(MockWindow::new(50, 20, rect(0.0, 0.0, 200.0, 200.0)));
+        let mut hidden = MockWindow::new(51, 21, rect(150.0, 0.0, 200.0, 200.0));
+        hidden.minimized = true;
+        backend.add_window(hidden);
+        backend.add_window(MockWindow::new(52, 30, rect(700.0, 0.0, 200.0, 200.0)));
+        backend.set_focused(50, 0);
+
+        // Other apps' windows count; minimized ones do not
+        assert!(focus_in_direction(&backend, PID, Direction::Left));
+        assert_eq!(backend.ops(), ["raise 20"]);
+        assert_eq!(backend.focused_id(50), Some(20));
+
+        assert!(!focus_in_direction(&backend, PID, Direction::Up));
+        backend.set_not_ready(PID, 1);
+        assert!(!focus_in_direction(&backend, PID, Direction::Right));
+        assert_eq!(backend.ops(), ["raise 20"]);
+    }
+}
@@ -0,0 +1,200 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+//! In-Memory Window Backend
+//! Deterministic WindowBackend for exercising window actions without a window system
+//!
+//! Windows are plain records that behave like cooperative apps. A position write
+//! moves the window. A size write is raised to the window's minimum size, the way
+//! apps enforce their own minimums, unless the window is marked fixed-size, which
+//! rejects it as Constrained. Every write is appended to an operation log that
+//! harnesses can compare against expectations. As on the Linux backends, pid 0
+//! matches every app. Nothing here depends on time, threads or the host platform.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::pbgft_types::PixelRect;
+use crate::pbgw_window::{WindowBackend, WindowError, WindowInfo};
+
+/// One simulated window
+#[derive(Debug, Clone)]
+pub struct MockWindow {
+    pub pid: u32,
+    pub window_id: u32,
+    pub title: String,
+    pub rect: PixelRect,
+    pub minimized: bool,
+    pub min_size: (f64, f64),  // Sizes below this are raised to it (app-enforced minimum)
+    pub fixed_size: bool,      // Size writes fail with Constrained
+}
+
+impl MockWindow {
+    pub fn new(pid: u32, window_id: u32, rect: PixelRect) -> Self {
+        MockWindow {
+            pid,
+            window_id,
+            title: format!("<win:{}>", window_id),
+            rect,
+            minimized: false,
+            min_size: (0.0, 0.0),
+            fixed_size: false,
+        }
+    }
+}
+
+#[derive(Default)]
+struct MockState {
+    windows: Vec<MockWindow>,
+    focused: HashMap<u32, u32>,    // pid → focused window_id
+    not_ready: HashMap<u32, u32>,  // pid → focused_window calls left that report not-ready
+    denied: bool,                  // Every write fails with Permission
+    ops: Vec<String>,
+}
+
+/// In-memory windows; the window handle is the window ID
+#[derive(Default)]
+pub struct MockBackend {
+    state: RefCell<MockState>,
+}
+
+impl MockBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a window; the first window of an app becomes its focused window
+    pub fn add_window(&self, window: MockWindow) {
+        let mut state = self.state.borrow_mut();
+        state.focused.entry(window.pid).or_insert(window.window_id);
+        state.windows.push(window);
+    }
+
+    pub fn set_focused(&self, pid: u32, window_id: u32) {
+        self.state.borrow_mut().focused.insert(pid, window_id);
+    }
+
+    /// Report "ax_not_ready_retry_needed" for the next `calls` focused_window queries of an app
+    pub fn set_not_ready(&self, pid: u32, calls: u32) {
+        self.state.borrow_mut().not_ready.insert(pid, calls);
+    }
+
+    /// Make every write fail as if window-system permission were revoked
+    pub fn set_permission_denied(&self, denied: bool) {
+        self.state.borrow_mut().denied = denied;
+    }
+
+    pub fn remove_window(&self, window_id: u32) {
+        let mut state = self.state.borrow_mut();
+        state.windows.retain(|w| w.window_id != window_id);
+        state.focused.retain(|_, focused| *focused != window_id);
+    }
+
+    /// Snapshot of a window's current record
+    pub fn window(&self, window_id: u32) -> Option<MockWindow> {
+        self.state.borrow().windows.iter().find(|w| w.window_id == window_id).cloned()
+    }
+
+    /// Focused window ID of an app
+    pub fn focused_id(&self, pid: u32) -> Option<u32> {
+        self.state.borrow().focused.get(&pid).copied()
+    }
+
+    /// Writes performed so far, in order ("position 7 (10,20)", "size 7 800x600", "raise 7")
+    pub fn ops(&self) -> Vec<String> {
+        self.state.borrow().ops.clone()
+    }
+
+    pub fn clear_ops(&self) {
+        self.state.borrow_mut().ops.clear();
+    }
+}
+
+impl MockState {
+    fn window_mut(&mut self, window_id: u32) -> Option<&mut MockWindow> {
+        self.windows.iter_mut().find(|w| w.window_id == window_id)
+    }
+}
+
+impl WindowBackend for MockBackend {
+    type Window = u32;
+
+    fn focused_window(&self, pid: u32) -> Result<u32, String> {
+        let mut state = self.state.borrow_mut();
+        if let Some(left) = state.not_ready.get_mut(&pid) {
+            if *left > 0 {
+                *left -= 1;
+                return Err("ax_not_ready_retry_needed".to_string());
+            }
+        }
+        state.focused.get(&pid).copied().ok_or_else(|| "no_focused_window".to_string())
+    }
+
+    fn window_by_id(&self, pid: u32, window_id: u32) -> Option<u32> {
+        self.state.borrow().windows.iter()
+            .find(|w| (pid == 0 || w.pid == pid) && w.window_id == window_id)
+            .map(|w| w.window_id)
+    }
+
+    fn enumerate_windows(&self, pid: u32) -> Vec<WindowInfo> {
+        self.state.borrow().windows.iter()
+            .filter(|w| pid == 0 || w.pid == pid)
+            .map(|w| WindowInfo {
+                window_id: w.window_id,
+                title: w.title.clone(),
+                rect: Some(w.rect.clone()),
+                minimized: w.minimized,
+            })
+            .collect()
+    }
+
+    fn window_id(&self, win: &u32) -> Option<u32> {
+        Some(*win)
+    }
+
+    fn get_rect(&self, win: &u32) -> Option<PixelRect> {
+        self.state.borrow().windows.iter().find(|w| w.window_id == *win).map(|w| w.rect.clone())
+    }
+
+    fn set_position(&self, win: &u32, x: f64, y: f64) -> Result<(), WindowError> {
+        let mut state = self.state.borrow_mut();
+        if state.denied {
+            return Err(WindowError::Permission);
+        }
+        let window = state.window_mut(*win).ok_or(WindowError::Platform(-1))?;
+        window.rect.x = x;
+        window.rect.y = y;
+        state.ops.push(format!("position {} ({:.0},{:.0})", win, x, y));
+        Ok(())
+    }
+
+    fn set_size(&self, win: &u32, width: f64, height: f64) -> Result<(), WindowError> {
+        let mut state = self.state.borrow_mut();
+        if state.denied {
+            return Err(WindowError::Permission);
+        }
+        let window = state.window_mut(*win).ok_or(WindowError::Platform(-1))?;
+        if window.fixed_size {
+            return Err(WindowError::Constrained);
+        }
+        window.rect.width = width.max(window.min_size.0);
+        window.rect.height = height.max(window.min_size.1);
+        let achieved = (window.rect.width, window.rect.height);
+        state.ops.push(format!("size {} {:.0}x{:.0}", win, achieved.0, achieved.1));
+        Ok(())
+    }
+
+    fn raise(&self, pid: u32, window_id: u32) -> bool {
+        let mut state = self.state.borrow_mut();
+        let owner = match state.window_mut(window_id) {
+            Some(window) if pid == 0 || window.pid == pid => {
+                window.minimized = false;
+                window.pid
+            }
+            _ => return false,
+        };
+        state.focused.insert(owner, window_id);
+        state.ops.push(format!("raise {}", window_id));
+        true
+    }
+}
@@ -0,0 +1,390 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+#![cfg(target_os = "linux")]
+
+//! X11 Clipboard Monitor (XFixes)
+//! Feeds the clipboard history from X11 selections and serves historic entries
+//!
+//! - Watches CLIPBOARD (and optionally PRIMARY) with XFixes selection-owner
+//!   notifications instead of polling
+//! - Each new owner is asked for UTF8_STRING, falling back to STRING
+//!   (Latin-1); large transfers arrive incrementally (INCR)
+//! - "Paste historic" takes CLIPBOARD ownership and answers requests for
+//!   TARGETS, UTF8_STRING, TEXT and STRING until another client takes it back
+//!
+//! The monitor runs on its own thread and X11 connection; set_clipboard_text
+//! may be called from any thread. On Wayland sessions start_clipboard_monitoring
+//! uses pblbw_clipboard (data-control) instead when the compositor supports it.
+
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use x11rb::connection::{Connection, RequestConnection};
+use x11rb::protocol::xfixes::{ConnectionExt as _, SelectionEventMask};
+use x11rb::protocol::xproto::{
+    Atom, AtomEnum, ConnectionExt as _, CreateWindowAux, EventMask, PropMode, Property, SelectionNotifyEvent,
+    SelectionRequestEvent, Window, WindowClass, SELECTION_NOTIFY_EVENT,
+};
+use x11rb::protocol::Event;
+use x11rb::rust_connection::RustConnection;
+use x11rb::wrapper::ConnectionExt as _;
+use x11rb::CURRENT_TIME;
+
+use crate::pbgcl_clipboard::{clipboard_history_entry, record_clipboard_text};
+use crate::pblbw_clipboard::{set_wayland_clipboard_text, start_wayland_clipboard};
+
+x11rb::atom_manager! {
+    pub ClipAtoms: ClipAtomsCookie {
+        CLIPBOARD,
+        UTF8_STRING,
+        TEXT,
+        TARGETS,
+        INCR,
+        PANEBOARD_CLIP,
+    }
+}
+
+/// Running monitor, once started
+static X11_CLIPBOARD: OnceLock<Arc<X11Clipboard>> = OnceLock::new();
+
+fn x11_err(e: impl std::fmt::Display) -> String {
+    format!("x11_error({})", e)
+}
+
+// ============================================================================
+// SECTION 1: Connection and ownership
+// ============================================================================
+
+/// Selection transfer in progress (one at a time; a newer owner replaces it)
+struct PendingRead {
+    selection: Atom,
+    target: Atom,
+    incr: Option<Vec<u8>>,   // Some while receiving an INCR transfer
+}
+
+/// Hidden window plus the text served while PaneBoard owns CLIPBOARD
+pub struct X11Clipboard {
+    conn: RustConnection,
+    window: Window,
+    atoms: ClipAtoms,
+    selections: Vec<Atom>,
+    owned: Mutex<Option<String>>,
+}
+
+impl X11Clipboard {
+    /// Connect to $DISPLAY, create the selection window and subscribe to owner changes
+    fn connect(watch_primary: bool) -> Result<Self, String> {
+        let (conn, screen_num) = x11rb::connect(None).map_err(|e| format!("x11_connect_failed({})", e))?;
+        let screen = &conn.setup().roots[screen_num];
+        let root = screen.root;
+        let atoms = ClipAtoms::new(&conn).map_err(x11_err)?.reply().map_err(x11_err)?;
+
+        let version = conn.xfixes_query_version(5, 0).map_err(x11_err)?.reply()
+            .map_err(|_| "xfixes_unavailable".to_string())?;
+        if version.major_version < 1 {
+            return Err(format!("xfixes_too_old({}.{})", version.major_version, version.minor_version));
+        }
+
+        // Unmapped input-only window: selection requestor and owner
+        let window = conn.generate_id().map_err(x11_err)?;
+        let aux = CreateWindowAux::new().event_mask(EventMask::PROPERTY_CHANGE);
+        conn.create_window(0, window, root, -10, -10, 1, 1, 0, WindowClass::INPUT_ONLY, 0, &aux)
+            .map_err(x11_err)?;
+
+        let mut selections = vec![atoms.CLIPBOARD];
+        if watch_primary {
+            selections.push(AtomEnum::PRIMARY.into());
+        }
+        let mask = SelectionEventMask::SET_SELECTION_OWNER
+            | SelectionEventMask::SELECTION_WINDOW_DESTROY
+            | SelectionEventMask::SELECTION_CLIENT_CLOSE;
+        for &selection in &selections {
+            conn.xfixes_select_selection_input(window, selection, mask).map_err(x11_err)?;
+        }
+        conn.flush().map_err(x11_err)?;
+
+        Ok(X11Clipboard { conn, window, atoms, selections, owned: Mutex::new(None) })
+    }
+
+    /// Take CLIPBOARD ownership and serve `text` (paste historic)
+    /// Returns false if the server did not grant ownership
+    pub fn set_clipboard_text(&self, text: &str) -> bool {
+        *self.owned.lock().unwrap() = Some(text.to_string());
+        let granted = self.conn.set_selection_owner(self.window, self.atoms.CLIPBOARD, CURRENT_TIME).is_ok()
+            && self.conn.get_selection_owner(self.atoms.CLIPBOARD).ok()
+                .and_then(|c| c.reply().ok())
+                .is_some_and(|r| r.owner == self.window);
+        if !granted {
+            *self.owned.lock().unwrap() = None;
+            eprintln!("CLIP: FAILED to own CLIPBOARD");
+            return false;
+        }
+
+        // Our own ownership is not reported back as a new owner; record it here
+        record_clipboard_text(text.to_string());
+        true
+    }
+
+    fn selection_name(&self, selection: Atom) -> &'static str {
+        if selection == self.atoms.CLIPBOARD { "CLIPBOARD" } else { "PRIMARY" }
+    }
+}
+
+// ============================================================================
+// SECTION 2: Reading selections
+// ============================================================================
+
+impl X11Clipboard {
+    /// Ask the selection owner to convert its contents into our property
+    fn request(&self, selection: Atom, target: Atom, time: u32) -> Option<PendingRead> {
+        self.conn.convert_selection(self.window, selection, target, self.atoms.PANEBOARD_CLIP, time).ok()?;
+        self.conn.flush().ok()?;
+        Some(PendingRead { selection, target, incr: None })
+    }
+
+    /// Read and delete our property (deleting also acknowledges an INCR chunk)
+    fn take_property(&self) -> Option<(Atom, Vec<u8>)> {
+        let reply = self.conn.get_property(true, self.window, self.atoms.PANEBOARD_CLIP, AtomEnum::ANY, 0, u32::MAX / 4)
+            .ok()?.reply().ok()?;
+        self.conn.flush().ok()?;
+        Some((reply.type_, reply.value))
+    }
+
+    /// Conversion answered: text, an INCR start, or a refusal (retry as STRING)
+    fn on_selection_notify(&self, ev: &SelectionNotifyEvent, pending: Option<PendingRead>) -> Option<PendingRead> {
+        let read = pending?;
+        if ev.requestor != self.window || ev.selection != read.selection {
+            return Some(read);
+        }
+        if ev.property == x11rb::NONE {
+            if read.target == self.atoms.UTF8_STRING {
+                return self.request(read.selection, AtomEnum::STRING.into(), ev.time);
+            }
+            return None;   // Owner has no text (image, files, ...)
+        }
+
+        let (ty, value) = self.take_property()?;
+        if ty == self.atoms.INCR {
+            return Some(PendingRead { incr: Some(Vec::new()), ..read });
+        }
+        self.finish(&read, &value);
+        None
+    }
+
+    /// INCR chunk arrived; a zero-length chunk ends the transfer
+    fn on_incr_chunk(&self, mut read: PendingRead) -> Option<PendingRead> {
+        let (_, chunk) = self.take_property()?;
+        let buffer = read.incr.as_mut()?;
+        if !chunk.is_empty() {
+            buffer.extend_from_slice(&chunk);
+            return Some(read);
+        }
+        let data = std::mem::take(buffer);
+        self.finish(&read, &data);
+        None
+    }
+
+    fn finish(&self, read: &PendingRead, data: &[u8]) {
+        let text = if read.target == Atom::from(AtomEnum::STRING) {
+            data.iter().map(|&b| b as char).collect()   // Latin-1
+        } else {
+            String::from_utf8_lossy(data).into_owned()
+        };
+        if text.is_empty() {
+            return;
+        }
+        eprintln!("DEBUG: {} text received | length={}", self.selection_name(read.selection), text.len());
+        record_clipboard_text(text);
+    }
+}
+
+// ============================================================================
+// SECTION 3: Serving ownership
+// ============================================================================
+
+impl X11Clipboard {
+    /// Answer a SelectionRequest from another client while we own CLIPBOARD
+    fn on_selection_request(&self, ev: &SelectionRequestEvent) {
+        // Obsolete clients leave property None: reply in a property named after the target
+        let property = if ev.property == x11rb::NONE { ev.target } else { ev.property };
+        let served = ev.selection == self.atoms.CLIPBOARD && self.serve(ev.requestor, ev.target, property);
+
+        let notify = SelectionNotifyEvent {
+            response_type: SELECTION_NOTIFY_EVENT,
+            sequence: 0,
+            time: ev.time,
+            requestor: ev.requestor,
+            selection: ev.selection,
+            target: ev.target,
+            property: if served { property } else { x11rb::NONE },
+        };
+        let _ = self.conn.send_event(false, ev.requestor, EventMask::NO_EVENT, notify);
+        let _ = self.conn.flush();
+    }
+
+    /// Write the owned text to the requestor in the requested target
+    fn serve(&self, requestor: Window, target: Atom, property: Atom) -> bool {
+        let owned = self.owned.lock().unwrap();
+        let text = match owned.as_deref() {
+            Some(t) => t,
+            None => return false,
+        };
+
+        if target == self.atoms.TARGETS {
+            let targets = [self.atoms.TARGETS, self.atoms.UTF8_STRING, self.atoms.TEXT, AtomEnum::STRING.into()];
+            return self.conn.change_property32(PropMode::REPLACE, requestor, property, AtomEnum::ATOM, &targets).is_ok();
+        }
+
+        let (ty, data): (Atom, Vec<u8>) = if target == self.atoms.UTF8_STRING || target == self.atoms.TEXT {
+            (self.atoms.UTF8_STRING, text.as_bytes().to_vec())
+        } else if target == Atom::from(AtomEnum::STRING) {
+            (AtomEnum::STRING.into(), text.chars().map(|c| if (c as u32) < 0x100 { c as u8 } else { b'?' }).collect())
+        } else {
+            return false;
+        };
+
+        // Single-request transfer only (no outgoing INCR)
+        if data.len() + 64 > self.conn.maximum_request_bytes() {
+            eprintln!("CLIP: text too large to serve | length={}", data.len());
+            return false;
+        }
+        self.conn.change_property8(PropMode::REPLACE, requestor, property, ty, &data).is_ok()
+    }
+}
+
+// ============================================================================
+// SECTION 4: Event loop and entry points
+// ============================================================================
+
+impl X11Clipboard {
+    fn run(&self) {
+        let mut pending: Option<PendingRead> = None;
+        loop {
+            let event = match self.conn.wait_for_event() {
+                Ok(e) => e,
+                Err(e) => {
+                    eprintln!("CLIP: monitor stopped ({})", e);
+                    return;
+                }
+            };
+
+            match event {
+                Event::XfixesSelectionNotify(ev)
+                    if ev.owner != x11rb::NONE && ev.owner != self.window && self.selections.contains(&ev.selection) =>
+                {
+                    pending = self.request(ev.selection, self.atoms.UTF8_STRING, ev.selection_timestamp);
+                }
+                Event::SelectionNotify(ev) => {
+                    pending = self.on_selection_notify(&ev, pending.take());
+                }
+                Event::PropertyNotify(ev) => {
+                    let chunk_ready = ev.window == self.window
+                        && ev.atom == self.atoms.PANEBOARD_CLIP
+                        && ev.state == Property::NEW_VALUE;
+                    pending = match pending.take() {
+                        Some(read) if chunk_ready && read.incr.is_some() => self.on_incr_chunk(read),
+                        other => other,
+                    };
+                }
+                Event::SelectionRequest(ev) => self.on_selection_request(&ev),
+                Event::SelectionClear(ev) if ev.selection == self.atoms.CLIPBOARD => {
+                    let was_owned = self.owned.lock().unwrap().take().is_some();
+                    if was_owned {
+                        eprintln!("DEBUG: CLIPBOARD ownership passed to another client");
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Start X11 monitoring on a background thread (idempotent)
+fn start_x11_clipboard(watch_primary: bool) -> Result<(), String> {
+    if X11_CLIPBOARD.get().is_some() {
+        return Ok(());
+    }
+    let clipboard = Arc::new(X11Clipboard::connect(watch_primary)?);
+    let monitor = clipboard.clone();
+    std::thread::spawn(move || monitor.run());
+    let _ = X11_CLIPBOARD.set(clipboard);
+    eprintln!("DEBUG: Clipboard monitoring started (X11 XFixes{})", if watch_primary { ", with PRIMARY" } else { "" });
+    Ok(())
+}
+
+/// Start clipboard monitoring on a background thread (idempotent)
+/// Wayland sessions use data-control when the compositor has it, X11 otherwise
+pub fn start_clipboard_monitoring(watch_primary: bool) -> Result<(), String> {
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        match start_wayland_clipboard(watch_primary) {
+            Ok(()) => return Ok(()),
+            Err(reason) if std::env::var_os("DISPLAY").is_some() => {
+                eprintln!("CLIP: Wayland data-control unavailable (reason={}), using X11", reason);
+            }
+            Err(reason) => return Err(reason),
+        }
+    }
+    start_x11_clipboard(watch_primary)
+}
+
+/// Set clipboard text (paste historic); false if monitoring is not running
+pub fn set_clipboard_text(text: &str) -> bool {
+    match X11_CLIPBOARD.get() {
+        Some(c) => c.set_clipboard_text(text),
+        None => set_wayland_clipboard_text(text),
+    }
+}
+
+/// Run `clipboard [--primary] [--paste INDEX] [--timeout SECONDS]`: monitor and log
+/// the history; with --paste, serve entry INDEX once the history reaches it
+/// Exit code: 0 on timeout, 1 if monitoring could not start, 2 on usage errors
+pub fn run_clipboard_cli(args: &[String]) -> i32 {
+    const USAGE: &str = "Usage: paneboard-poc clipboard [--primary] [--paste INDEX] [--timeout SECONDS]";
+    let mut watch_primary = false;
+    let mut paste: Option<usize> = None;
+    let mut timeout: Option<Duration> = None;
+    let mut rest = args.iter();
+    while let Some(flag) = rest.next() {
+        match flag.as_str() {
+            "--primary" => watch_primary = true,
+            "--paste" => match rest.next().and_then(|v| v.parse().ok()) {
+                Some(index) => paste = Some(index),
+                None => {
+                    eprintln!("ERROR: --paste requires a history index");
+                    return 2;
+                }
+            },
+            "--timeout" => match rest.next().and_then(|v| v.parse::<f64>().ok()) {
+                Some(secs) if secs > 0.0 => timeout = Some(Duration::from_secs_f64(secs)),
+                _ => {
+                    eprintln!("ERROR: --timeout value must be a positive number");
+                    return 2;
+                }
+            },
+            _ => {
+                eprintln!("{}", USAGE);
+                return 2;
+            }
+        }
+    }
+
+    if let Err(reason) = start_clipboard_monitoring(watch_primary) {
+        eprintln!("CLIP: FAILED reason={}", reason);
+        return 1;
+    }
+
+    let started = Instant::now();
+    while timeout.is_none_or(|t| started.elapsed() < t) {
+        if let Some(index) = paste {
+            if let Some(text) = clipboard_history_entry(index) {
+                if set_clipboard_text(&text) {
+                    println!("CLIP: pasted historic | index={} | length={}", index, text.len());
+                }
+                paste = None;
+            }
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    0
+}
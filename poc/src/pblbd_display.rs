@@ -0,0 +1,374 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+#![cfg(target_os = "linux")]
+
+//! X11 Display Enumeration (RandR)
+//! Describes the displays tiling and DisplayMove target on X11
+//!
+//! Displays come from RandR monitors (1.5 GetMonitors, or active CRTCs on
+//! older servers), primary first, then left-to-right/top-to-bottom. Each
+//! display carries:
+//! - Name: EDID product name, else the output name ("DP-1")
+//! - Identity: EDID vendor/model/serial (matches <Display identity="..."/>)
+//! - Rotation: CRTC rotation in degrees, logged (geometry already reflects it)
+//!
+//! Usable area per monitor is the monitor rect minus docked panel struts
+//! (_NET_WM_STRUT_PARTIAL / _NET_WM_STRUT). Without struts, the monitor is
+//! clipped to _NET_WORKAREA instead. The symmetric viewport principle is then
+//! applied as on macOS: the inset reserved at the top is also reserved at the
+//! bottom. Design size is the symmetric viewport size.
+//!
+//! Without RandR, the whole screen is one display. The i3/sway IPC backend
+//! builds its displays from outputs and workspace rects (display_from_area).
+
+use x11rb::connection::RequestConnection;
+use x11rb::protocol::randr::{self, ConnectionExt as _, Output, Rotation};
+use x11rb::protocol::xproto::{AtomEnum, ConnectionExt as _};
+use x11rb::CURRENT_TIME;
+
+use crate::pbgft_types::{DisplayIdentity, DisplayInfo, DisplayPlacement, PixelRect};
+use crate::pbgw_window::TileDisplay;
+use crate::pblbx_x11::X11Backend;
+
+/// One display with its live viewport
+#[derive(Debug, Clone)]
+pub struct LinuxDisplay {
+    pub info: DisplayInfo,
+    pub viewport: PixelRect,   // Symmetric viewport in root coordinates (top-left origin)
+}
+
+impl LinuxDisplay {
+    pub fn as_placement(&self) -> DisplayPlacement {
+        DisplayPlacement {
+            frame: self.viewport.clone(),
+            name: self.info.name.clone(),
+            identity: self.info.identity,
+        }
+    }
+
+    pub fn as_tile_display(&self) -> TileDisplay {
+        TileDisplay {
+            props: self.info.as_props(),
+            viewport: self.viewport.clone(),
+        }
+    }
+}
+
+// ============================================================================
+// SECTION 1: RandR monitors
+// ============================================================================
+
+/// Monitor as reported by RandR, before work-area adjustment
+struct Monitor {
+    name: String,
+    rect: PixelRect,
+    primary: bool,
+    outputs: Vec<Output>,
+}
+
+/// RandR monitors; empty when the extension is missing or reports nothing
+fn randr_monitors(x11: &X11Backend) -> Vec<Monitor> {
+    let present = x11.conn.extension_information(randr::X11_EXTENSION_NAME).ok().flatten().is_some();
+    if !present {
+        eprintln!("DISPLAY: RandR not available");
+        return Vec::new();
+    }
+    let version = match x11.conn.randr_query_version(1, 5).ok().and_then(|c| c.reply().ok()) {
+        Some(v) => (v.major_version, v.minor_version),
+        None => return Vec::new(),
+    };
+
+    if version >= (1, 5) {
+        monitors_from_get_monitors(x11)
+    } else if version >= (1, 2) {
+        monitors_from_crtcs(x11)
+    } else {
+        eprintln!("DISPLAY: RandR {}.{} too old for output enumeration", version.0, version.1);
+        Vec::new()
+    }
+}
+
+/// RandR 1.5: logical monitors (includes xrandr --setmonitor splits)
+fn monitors_from_get_monitors(x11: &X11Backend) -> Vec<Monitor> {
+    let reply = match x11.conn.randr_get_monitors(x11.root, true).ok().and_then(|c| c.reply().ok()) {
+        Some(r) => r,
+        None => return Vec::new(),
+    };
+    reply.monitors.into_iter()
+        .filter(|m| m.width > 0 && m.height > 0)
+        .map(|m| Monitor {
+            name: atom_name(x11, m.name).unwrap_or_else(|| "Monitor".to_string()),
+            rect: PixelRect { x: m.x as f64, y: m.y as f64, width: m.width as f64, height: m.height as f64 },
+            primary: m.primary,
+            outputs: m.outputs,
+        })
+        .collect()
+}
+
+/// RandR 1.2-1.4: one monitor per active CRTC
+fn monitors_from_crtcs(x11: &X11Backend) -> Vec<Monitor> {
+    let resources = match x11.conn.randr_get_screen_resources_current(x11.root).ok().and_then(|c| c.reply().ok()) {
+        Some(r) => r,
+        None => return Vec::new(),
+    };
+    let primary = x11.conn.randr_get_output_primary(x11.root).ok()
+        .and_then(|c| c.reply().ok())
+        .map_or(x11rb::NONE, |r| r.output);
+
+    resources.crtcs.iter()
+        .filter_map(|&crtc| x11.conn.randr_get_crtc_info(crtc, resources.config_timestamp).ok()?.reply().ok())
+        .filter(|info| info.mode != x11rb::NONE && !info.outputs.is_empty())
+        .map(|info| Monitor {
+            name: output_name(x11, info.outputs[0]).unwrap_or_else(|| "Monitor".to_string()),
+            rect: PixelRect { x: info.x as f64, y: info.y as f64, width: info.width as f64, height: info.height as f64 },
+            primary: info.outputs.contains(&primary),
+            outputs: info.outputs,
+        })
+        .collect()
+}
+
+fn atom_name(x11: &X11Backend, atom: u32) -> Option<String> {
+    let reply = x11.conn.get_atom_name(atom).ok()?.reply().ok()?;
+    Some(String::from_utf8_lossy(&reply.name).into_owned())
+}
+
+fn output_name(x11: &X11Backend, output: Output) -> Option<String> {
+    let info = x11.conn.randr_get_output_info(output, CURRENT_TIME).ok()?.reply().ok()?;
+    Some(String::from_utf8_lossy(&info.name).into_owned())
+}
+
+/// CRTC rotation of an output in degrees (0 when not driven by a CRTC)
+fn output_rotation(x11: &X11Backend, output: Output) -> u16 {
+    let crtc = match x11.conn.randr_get_output_info(output, CURRENT_TIME).ok().and_then(|c| c.reply().ok()) {
+        Some(info) if info.crtc != x11rb::NONE => info.crtc,
+        _ => return 0,
+    };
+    let rotation = match x11.conn.randr_get_crtc_info(crtc, CURRENT_TIME).ok().and_then(|c| c.reply().ok()) {
+        Some(info) => info.rotation,
+        None => return 0,
+    };
+    if rotation.contains(Rotation::ROTATE90) {
+        90
+    } else if rotation.contains(Rotation::ROTATE180) {
+        180
+    } else if rotation.contains(Rotation::ROTATE270) {
+        270
+    } else {
+        0
+    }
+}
+
+// ============================================================================
+// SECTION 2: EDID identity
+// ============================================================================
+
+/// Raw EDID block of an output (empty when the driver does not expose one)
+fn output_edid(x11: &X11Backend, output: Output) -> Vec<u8> {
+    x11.conn.randr_get_output_property(output, x11.atoms.EDID, AtomEnum::INTEGER, 0, 64, false, false)
+        .ok()
+        .and_then(|c| c.reply().ok())
+        .map(|r| r.data)
+        .unwrap_or_default()
+}
+
+/// Vendor/model/serial from the EDID header (same fields CoreGraphics reports)
+fn edid_identity(edid: &[u8]) -> DisplayIdentity {
+    if edid.len() < 16 || edid[0..8] != [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00] {
+        return DisplayIdentity::default();
+    }
+    DisplayIdentity {
+        vendor: u16::from_be_bytes([edid[8], edid[9]]) as u32,
+        model: u16::from_le_bytes([edid[10], edid[11]]) as u32,
+        serial: u32::from_le_bytes([edid[12], edid[13], edid[14], edid[15]]),
+    }
+}
+
+/// Product name from the EDID display descriptors (tag 0xFC)
+fn edid_product_name(edid: &[u8]) -> Option<String> {
+    if edid.len() < 128 {
+        return None;
+    }
+    edid[54..126].chunks_exact(18)
+        .find(|d| d[0..3] == [0, 0, 0] && d[3] == 0xFC)
+        .map(|d| {
+            let text = &d[5..18];
+            let end = text.iter().position(|&b| b == b'\n').unwrap_or(text.len());
+            String::from_utf8_lossy(&text[..end]).trim().to_string()
+        })
+        .filter(|name| !name.is_empty())
+}
+
+// ============================================================================
+// SECTION 3: Usable area and symmetric viewport
+// ============================================================================
+
+/// Space reserved at each edge of a monitor (left, right, top, bottom)
+#[derive(Debug, Clone, Copy, Default)]
+struct Insets {
+    left: f64,
+    right: f64,
+    top: f64,
+    bottom: f64,
+}
+
+/// Strut of one panel in root-edge terms (_NET_WM_STRUT_PARTIAL layout)
+/// [left, right, top, bottom, left_start_y, left_end_y, right_start_y, right_end_y,
+///  top_start_x, top_end_x, bottom_start_x, bottom_end_x]
+fn panel_struts(x11: &X11Backend, root: &PixelRect) -> Vec<[f64; 12]> {
+    x11.client_list().into_iter()
+        .filter_map(|win| {
+            let partial = x11.prop32(win, x11.atoms._NET_WM_STRUT_PARTIAL, AtomEnum::CARDINAL.into());
+            if partial.len() >= 12 {
+                let mut strut = [0.0; 12];
+                for (dst, src) in strut.iter_mut().zip(&partial) {
+                    *dst = *src as f64;
+                }
+                return Some(strut);
+            }
+            // Legacy _NET_WM_STRUT spans the full root edge
+            match x11.prop32(win, x11.atoms._NET_WM_STRUT, AtomEnum::CARDINAL.into())[..] {
+                [l, r, t, b, ..] => {
+                    let (w, h) = (root.width - 1.0, root.height - 1.0);
+                    Some([l as f64, r as f64, t as f64, b as f64, 0.0, h, 0.0, h, 0.0, w, 0.0, w])
+                }
+                _ => None,
+            }
+        })
+        .filter(|s| s[0..4].iter().any(|&v| v > 0.0))
+        .collect()
+}
+
+/// Edge reservations a set of struts makes on one monitor
+fn monitor_insets(monitor: &PixelRect, root: &PixelRect, struts: &[[f64; 12]]) -> Insets {
+    // Inclusive start/end ranges overlap the monitor's span on that axis
+    let spans = |start: f64, end: f64, min: f64, len: f64| start < min + len && end >= min;
+    let mut insets = Insets::default();
+
+    for s in struts {
+        if s[0] > 0.0 && spans(s[4], s[5], monitor.y, monitor.height) {
+            insets.left = insets.left.max(s[0] - monitor.x);
+        }
+        if s[1] > 0.0 && spans(s[6], s[7], monitor.y, monitor.height) {
+            insets.right = insets.right.max(monitor.x + monitor.width - (root.width - s[1]));
+        }
+        if s[2] > 0.0 && spans(s[8], s[9], monitor.x, monitor.width) {
+            insets.top = insets.top.max(s[2] - monitor.y);
+        }
+        if s[3] > 0.0 && spans(s[10], s[11], monitor.x, monitor.width) {
+            insets.bottom = insets.bottom.max(monitor.y + monitor.height - (root.height - s[3]));
+        }
+    }
+
+    // Struts reaching past the far edge belong to another monitor
+    let clamp = |v: f64, len: f64| if v > 0.0 && v < len { v } else { 0.0 };
+    Insets {
+        left: clamp(insets.left, monitor.width),
+        right: clamp(insets.right, monitor.width),
+        top: clamp(insets.top, monitor.height),
+        bottom: clamp(insets.bottom, monitor.height),
+    }
+}
+
+/// Insets implied by clipping a monitor to the WM work area
+fn work_area_insets(monitor: &PixelRect, area: &PixelRect) -> Insets {
+    let left = (area.x - monitor.x).max(0.0);
+    let top = (area.y - monitor.y).max(0.0);
+    let right = ((monitor.x + monitor.width) - (area.x + area.width)).max(0.0);
+    let bottom = ((monitor.y + monitor.height) - (area.y + area.height)).max(0.0);
+    // A work area that misses the monitor entirely says nothing about it
+    if left + right >= monitor.width || top + bottom >= monitor.height {
+        return Insets::default();
+    }
+    Insets { left, right, top, bottom }
+}
+
+/// Apply the symmetric viewport principle (see DisplayInfo::live_viewport on macOS):
+/// the usable area loses the top inset once more at the bottom
+fn symmetric_viewport(monitor: &PixelRect, insets: &Insets) -> PixelRect {
+    PixelRect {
+        x: monitor.x + insets.left,
+        y: monitor.y + insets.top,
+        width: monitor.width - insets.left - insets.right,
+        height: (monitor.height - insets.top - insets.bottom - insets.top).max(1.0),
+    }
+}
+
+// ============================================================================
+// SECTION 4: Display list
+// ============================================================================
+
+/// Gather every display in index order (primary first)
+pub fn gather_displays(x11: &X11Backend) -> Vec<LinuxDisplay> {
+    let root = x11.root_rect();
+    let area = x11.work_area();
+    let struts = panel_struts(x11, &root);
+
+    let mut monitors = randr_monitors(x11);
+    if monitors.is_empty() {
+        monitors.push(Monitor { name: "X11 Screen".to_string(), rect: root.clone(), primary: true, outputs: Vec::new() });
+    }
+    monitors.sort_by(|a, b| {
+        b.primary.cmp(&a.primary)
+            .then(a.rect.x.total_cmp(&b.rect.x))
+            .then(a.rect.y.total_cmp(&b.rect.y))
+    });
+
+    eprintln!("DISPLAY: X11 screen {:.0}x{:.0}, {} monitor(s), {} panel strut(s), work area ({:.0},{:.0}) {:.0}x{:.0}",
+        root.width, root.height, monitors.len(), struts.len(), area.x, area.y, area.width, area.height);
+
+    monitors.into_iter().enumerate().map(|(index, monitor)| {
+        let insets = if struts.is_empty() {
+            work_area_insets(&monitor.rect, &area)
+        } else {
+            monitor_insets(&monitor.rect, &root, &struts)
+        };
+        let viewport = symmetric_viewport(&monitor.rect, &insets);
+
+        let edid = monitor.outputs.first().map(|&o| output_edid(x11, o)).unwrap_or_default();
+        let identity = edid_identity(&edid);
+        let name = edid_product_name(&edid).unwrap_or(monitor.name);
+        let rotation = monitor.outputs.first().map_or(0, |&o| output_rotation(x11, o));
+
+        eprintln!("DISPLAY: [{}] \"{}\"{} frame=({:.0},{:.0}) {:.0}x{:.0} rotation={} insets=l{:.0} r{:.0} t{:.0} b{:.0} viewport=({:.0},{:.0}) {:.0}x{:.0} id={}:{}:{}",
+            index, name, if monitor.primary { " primary" } else { "" },
+            monitor.rect.x, monitor.rect.y, monitor.rect.width, monitor.rect.height, rotation,
+            insets.left, insets.right, insets.top, insets.bottom,
+            viewport.x, viewport.y, viewport.width, viewport.height,
+            identity.vendor, identity.model, identity.serial);
+
+        LinuxDisplay {
+            info: DisplayInfo {
+                index,
+                design_width: viewport.width,
+                design_height: viewport.height,
+                name,
+                identity,
+            },
+            viewport,
+        }
+    }).collect()
+}
+
+/// Display from a monitor rect and the usable area a window manager reports for it
+/// (i3/sway workspace rects exclude bars); no EDID, so identity is 0:0:0
+pub fn display_from_area(index: usize, name: String, monitor: &PixelRect, area: &PixelRect) -> LinuxDisplay {
+    let insets = work_area_insets(monitor, area);
+    let viewport = symmetric_viewport(monitor, &insets);
+
+    eprintln!("DISPLAY: [{}] \"{}\" frame=({:.0},{:.0}) {:.0}x{:.0} insets=l{:.0} r{:.0} t{:.0} b{:.0} viewport=({:.0},{:.0}) {:.0}x{:.0}",
+        index, name, monitor.x, monitor.y, monitor.width, monitor.height,
+        insets.left, insets.right, insets.top, insets.bottom,
+        viewport.x, viewport.y, viewport.width, viewport.height);
+
+    LinuxDisplay {
+        info: DisplayInfo {
+            index,
+            design_width: viewport.width,
+            design_height: viewport.height,
+            name,
+            identity: DisplayIdentity::default(),
+        },
+        viewport,
+    }
+}
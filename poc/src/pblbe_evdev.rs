@@ -0,0 +1,559 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+#![cfg(target_os = "linux")]
+
+//! evdev Chord Capture
+//! Global Control+Shift+Alt chords below the display server (X11, Wayland, console)
+//!
+//! Every keyboard under /dev/input is grabbed (EVIOCGRAB), so nothing else
+//! sees its events. Events are tracked in a KeyState by HID usage, and:
+//! - Chord keys (Control+Shift+Alt held, no Meta, key has an XML name) are
+//!   swallowed together with their repeats and release, then dispatched
+//! - Everything else is re-emitted frame by frame through one uinput virtual
+//!   keyboard ("PaneBoard passthrough"), which the display server reads like
+//!   any other keyboard
+//!
+//! Keyboards are rescanned every second, so hotplugged and uinput-created
+//! keyboards are picked up. A keyboard is only grabbed while none of its keys
+//! is down (a press grabbed mid-stroke would never see its release).
+//! Devices with relative axes (keyboard/mouse combos) are left alone.
+//! Keys still down on an unplugged keyboard count as released, and after a
+//! kernel buffer overrun (SYN_DROPPED) the key state is re-read from the device.
+//!
+//! Needs read access to /dev/input/event* and write access to /dev/uinput
+//! (typically the `input` group plus a uinput udev rule, or root).
+
+use std::collections::{HashMap, HashSet};
+use std::os::fd::AsRawFd;
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+use evdev::uinput::VirtualDevice;
+use evdev::raw_stream::{self, RawDevice};
+use evdev::{AttributeSet, EventType, InputEvent, KeyCode, SynchronizationCode};
+
+use crate::pbgc_core::KeyState;
+use crate::pbgk_keylog::{update_key_state, KEY_LOGGING_ENABLED};
+use crate::pblbk_keymap::{evdev_to_hid_usage, evdev_to_xml_key, xml_key_to_evdev};
+
+/// Name of the uinput device carrying passed-through events (never grabbed)
+pub const PASSTHROUGH_NAME: &str = "PaneBoard passthrough";
+
+/// Name of the uinput keyboard created by `inject`
+pub const TEST_KEYBOARD_NAME: &str = "PaneBoard test keyboard";
+
+const RESCAN_INTERVAL: Duration = Duration::from_secs(1);
+const POLL_TIMEOUT_MS: i32 = 250;
+
+/// evdev key event values
+const KEY_RELEASE: i32 = 0;
+const KEY_PRESS: i32 = 1;
+const KEY_REPEAT: i32 = 2;
+
+/// Receiver for chord keys (runs on the capture thread, between events)
+pub trait ChordHandler {
+    /// A chord key went down (or auto-repeated, when accepted)
+    fn chord_key(&mut self, key: &'static str, is_repeat: bool);
+    /// True if the key's binding wants auto-repeat (NudgeAction/ResizeAction)
+    fn accepts_autorepeat(&self, key: &str) -> bool;
+    /// The Control+Shift+Alt chord was released (end of a pane cycle)
+    fn chord_released(&mut self);
+}
+
+// ============================================================================
+// SECTION 1: Chord filter
+// ============================================================================
+
+/// Decides per key event whether it passes through or belongs to a chord
+/// Devices are identified by the capture loop's stable keyboard id
+#[derive(Default)]
+pub struct ChordFilter {
+    state: KeyState,
+    held: HashMap<u32, HashSet<u16>>,              // Keys down per device, by evdev code
+    swallowed: HashMap<(u32, u16), &'static str>,  // Chord keys still down, by device and code
+    chord_was_held: bool,
+    warned_unknown: bool,
+}
+
+impl ChordFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Process one EV_KEY event (value 0 release, 1 press, 2 repeat) from a device
+    /// Returns true if the event should be passed through
+    pub fn key_event(&mut self, device: u32, code: u16, value: i32, handler: &mut dyn ChordHandler) -> bool {
+        if value != KEY_REPEAT {
+            self.track(device, code, value == KEY_PRESS);
+        }
+
+        let pass = match value {
+            KEY_RELEASE => self.swallowed.remove(&(device, code)).is_none(),
+            KEY_REPEAT => match self.swallowed.get(&(device, code)) {
+                Some(&key) => {
+                    if handler.accepts_autorepeat(key) {
+                        handler.chord_key(key, true);
+                    }
+                    false
+                }
+                None => true,
+            },
+            _ => !self.chord_press(device, code, handler),
+        };
+
+        self.check_chord_released(handler);
+        pass
+    }
+
+    /// Bring a device's keys in line with what the kernel reports as down
+    /// (after SYN_DROPPED, events in between are lost). Keys pressed in the gap
+    /// are not dispatched; chord keys among them are swallowed until released.
+    /// Returns (code, value) key events the passthrough keyboard needs to match
+    pub fn resync(&mut self, device: u32, down: &HashSet<u16>, handler: &mut dyn ChordHandler) -> Vec<(u16, i32)> {
+        let mut events = Vec::new();
+        let held = self.held.get(&device).cloned().unwrap_or_default();
+
+        for &code in held.difference(down) {
+            self.track(device, code, false);
+            if self.swallowed.remove(&(device, code)).is_none() {
+                events.push((code, KEY_RELEASE));
+            }
+        }
+        for &code in down.difference(&held) {
+            self.track(device, code, true);
+            match evdev_to_xml_key(code) {
+                Some(key) if self.state.chord_held() => {
+                    self.swallowed.insert((device, code), key);
+                }
+                _ => events.push((code, KEY_PRESS)),
+            }
+        }
+
+        self.check_chord_released(handler);
+        events
+    }
+
+    /// Forget a device that went away: its held keys count as released
+    /// Returns the releases the passthrough keyboard needs for keys it passed
+    pub fn device_removed(&mut self, device: u32, handler: &mut dyn ChordHandler) -> Vec<(u16, i32)> {
+        let events = self.resync(device, &HashSet::new(), handler);
+        self.held.remove(&device);
+        events
+    }
+
+    /// Record a press or release in the chord state and the per-device key set
+    fn track(&mut self, device: u32, code: u16, pressed: bool) {
+        if let Some(usage) = evdev_to_hid_usage(code) {
+            self.state.apply(usage, pressed);
+            if KEY_LOGGING_ENABLED.load(Ordering::Acquire) {
+                update_key_state(usage, pressed);
+            }
+        }
+        let keys = self.held.entry(device).or_default();
+        if pressed {
+            keys.insert(code);
+        } else {
+            keys.remove(&code);
+        }
+    }
+
+    /// Chord released: reset pane cycles, as on macOS
+    fn check_chord_released(&mut self, handler: &mut dyn ChordHandler) {
+        let chord_held = self.state.chord_held();
+        if self.chord_was_held && !chord_held {
+            handler.chord_released();
+        }
+        self.chord_was_held = chord_held;
+    }
+
+    /// Key press while the chord may be held; returns true if consumed
+    fn chord_press(&mut self, device: u32, code: u16, handler: &mut dyn ChordHandler) -> bool {
+        if !self.state.chord_held() {
+            return false;
+        }
+        let key = match evdev_to_xml_key(code) {
+            Some(key) => key,
+            None => {
+                let is_modifier = evdev_to_hid_usage(code).is_some_and(|u| (0xE0..=0xE7).contains(&u));
+                if !is_modifier && !self.warned_unknown {
+                    self.warned_unknown = true;
+                    eprintln!("NOTE: ctrl+shift+alt on unmapped key code={}. This key is not configured in form.xml.", code);
+                }
+                return false;
+            }
+        };
+
+        println!("BLOCKED: ctrl+shift+alt+{}", key);
+        self.swallowed.insert((device, code), key);
+        handler.chord_key(key, false);
+        true
+    }
+}
+
+// ============================================================================
+// SECTION 2: Devices
+// ============================================================================
+
+/// A grabbed keyboard and its partially read event frame
+struct Keyboard {
+    id: u32,          // Stable for the capture's lifetime (ChordFilter device key)
+    path: PathBuf,
+    name: String,
+    device: RawDevice,
+    frame: Vec<InputEvent>,
+    dropped: bool,    // SYN_DROPPED seen: skip events until the next SYN_REPORT, then resync
+}
+
+/// Keyboards have letter and Enter keys; pointers (relative axes) are excluded
+fn is_keyboard(device: &RawDevice) -> bool {
+    if device.name() == Some(PASSTHROUGH_NAME) {
+        return false;
+    }
+    if device.supported_relative_axes().is_some_and(|axes| axes.iter().next().is_some()) {
+        return false;
+    }
+    device.supported_keys().is_some_and(|keys| {
+        keys.contains(KeyCode::KEY_A) && keys.contains(KeyCode::KEY_Z) && keys.contains(KeyCode::KEY_ENTER)
+    })
+}
+
+/// Open and grab keyboards not yet in `known`; held-down keyboards wait for the next scan
+fn grab_new_keyboards(known: &HashSet<PathBuf>, waiting: &mut HashSet<PathBuf>, next_id: &mut u32) -> Vec<Keyboard> {
+    let mut grabbed = Vec::new();
+    for (path, mut device) in raw_stream::enumerate() {
+        if known.contains(&path) || !is_keyboard(&device) {
+            continue;
+        }
+        let name = device.name().unwrap_or("unnamed").to_string();
+
+        let keys_down = device.get_key_state().map_or(0, |keys| keys.iter().count());
+        if keys_down > 0 {
+            if waiting.insert(path.clone()) {
+                eprintln!("INPUT: {} \"{}\" has {} key(s) down, grabbing once released", path.display(), name, keys_down);
+            }
+            continue;
+        }
+        waiting.remove(&path);
+
+        if let Err(e) = device.grab() {
+            eprintln!("INPUT: cannot grab {} \"{}\": {}", path.display(), name, e);
+            continue;
+        }
+        eprintln!("INPUT: grabbed {} \"{}\"", path.display(), name);
+        grabbed.push(Keyboard { id: *next_id, path, name, device, frame: Vec::new(), dropped: false });
+        *next_id += 1;
+    }
+    grabbed
+}
+
+/// Virtual keyboard for passed-through events (all keyboard key codes, no buttons)
+fn create_passthrough() -> Result<VirtualDevice, String> {
+    let mut keys = AttributeSet::<KeyCode>::new();
+    for code in 1..0x300u16 {
+        // 0x100-0x15F are mouse/joystick/digitizer buttons
+        if !(0x100..0x160).contains(&code) {
+            keys.insert(KeyCode(code));
+        }
+    }
+    VirtualDevice::builder()
+        .and_then(|b| b.name(PASSTHROUGH_NAME).with_keys(&keys))
+        .and_then(|b| b.build())
+        .map_err(|e| format!("uinput_unavailable({})", e))
+}
+
+// ============================================================================
+// SECTION 3: Capture loop
+// ============================================================================
+
+/// Grab keyboards and run until the timeout (forever if None) or a fatal error
+pub fn run_capture(handler: &mut dyn ChordHandler, timeout: Option<Duration>) -> Result<(), String> {
+    let mut passthrough = create_passthrough()?;
+    let mut filter = ChordFilter::new();
+    let mut keyboards: Vec<Keyboard> = Vec::new();
+    let mut waiting = HashSet::new();
+    let mut next_id = 0;
+    let started = Instant::now();
+    let mut next_scan = started;
+    let (mut passed, mut swallowed) = (0u64, 0u64);
+
+    eprintln!("INPUT: evdev capture running (chord: ctrl+shift+alt)");
+
+    loop {
+        if timeout.is_some_and(|t| started.elapsed() >= t) {
+            eprintln!("INPUT: exit | passed={} swallowed={}", passed, swallowed);
+            return Ok(());
+        }
+
+        if Instant::now() >= next_scan {
+            let known: HashSet<PathBuf> = keyboards.iter().map(|k| k.path.clone()).collect();
+            keyboards.extend(grab_new_keyboards(&known, &mut waiting, &mut next_id));
+            next_scan = Instant::now() + RESCAN_INTERVAL;
+        }
+
+        let mut fds: Vec<libc::pollfd> = keyboards.iter()
+            .map(|k| libc::pollfd { fd: k.device.as_raw_fd(), events: libc::POLLIN, revents: 0 })
+            .collect();
+        let ready = unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, POLL_TIMEOUT_MS) };
+        if ready < 0 {
+            let err = std::io::Error::last_os_error();
+            if err.kind() == std::io::ErrorKind::Interrupted {
+                continue;
+            }
+            return Err(format!("poll_failed({})", err));
+        }
+
+        let mut unplugged = Vec::new();
+        for (idx, pfd) in fds.iter().enumerate() {
+            if pfd.revents == 0 {
+                continue;
+            }
+            let keyboard = &mut keyboards[idx];
+            if pfd.revents & (libc::POLLERR | libc::POLLHUP) != 0 {
+                unplugged.push(idx);
+                continue;
+            }
+
+            let events: Vec<InputEvent> = match keyboard.device.fetch_events() {
+                Ok(events) => events.collect(),
+                Err(_) => {
+                    unplugged.push(idx);
+                    continue;
+                }
+            };
+
+            for event in events {
+                // Events up to the SYN_REPORT after SYN_DROPPED are incomplete
+                if keyboard.dropped {
+                    if event.event_type() == EventType::SYNCHRONIZATION && event.code() == SynchronizationCode::SYN_REPORT.0 {
+                        keyboard.dropped = false;
+                        let down: HashSet<u16> = keyboard.device.get_key_state()
+                            .map(|keys| keys.iter().map(|k| k.0).collect())
+                            .unwrap_or_default();
+                        let fixes = filter.resync(keyboard.id, &down, handler);
+                        eprintln!("INPUT: {} \"{}\" dropped events, resynced {} key(s)",
+                            keyboard.path.display(), keyboard.name, fixes.len());
+                        emit_keys(&mut passthrough, &fixes);
+                    }
+                    continue;
+                }
+
+                match event.event_type() {
+                    EventType::KEY => {
+                        if filter.key_event(keyboard.id, event.code(), event.value(), handler) {
+                            keyboard.frame.push(event);
+                            passed += 1;
+                        } else {
+                            swallowed += 1;
+                        }
+                    }
+                    EventType::SYNCHRONIZATION
+                        if event.code() == SynchronizationCode::SYN_REPORT.0 && !keyboard.frame.is_empty() =>
+                    {
+                        if let Err(e) = passthrough.emit(&keyboard.frame) {
+                            eprintln!("INPUT: passthrough emit failed: {}", e);
+                        }
+                        keyboard.frame.clear();
+                    }
+                    EventType::SYNCHRONIZATION if event.code() == SynchronizationCode::SYN_DROPPED.0 => {
+                        keyboard.frame.clear();
+                        keyboard.dropped = true;
+                    }
+                    // Scan codes and LED echoes are not forwarded
+                    _ => {}
+                }
+            }
+        }
+
+        for idx in unplugged.into_iter().rev() {
+            let keyboard = keyboards.remove(idx);
+            emit_keys(&mut passthrough, &filter.device_removed(keyboard.id, handler));
+            eprintln!("INPUT: released {} \"{}\" (unplugged)", keyboard.path.display(), keyboard.name);
+        }
+    }
+}
+
+/// Emit synthetic key events (resync, unplug) on the passthrough keyboard as one frame
+fn emit_keys(passthrough: &mut VirtualDevice, keys: &[(u16, i32)]) {
+    if keys.is_empty() {
+        return;
+    }
+    let frame: Vec<InputEvent> = keys.iter().map(|&(code, value)| InputEvent::new(EventType::KEY.0, code, value)).collect();
+    if let Err(e) = passthrough.emit(&frame) {
+        eprintln!("INPUT: passthrough emit failed: {}", e);
+    }
+}
+
+// ============================================================================
+// SECTION 4: Test keyboard
+// ============================================================================
+
+/// Run `inject <key>...`: type Control+Shift+Alt+<key> for each key on a new
+/// uinput keyboard, so a running capture can be exercised without hardware
+/// Exit code: 0 on success, 1 if uinput is unavailable, 2 on usage errors
+pub fn run_inject_cli(keys: &[String]) -> i32 {
+    if keys.is_empty() {
+        eprintln!("Usage: paneboard-poc inject <key>...");
+        return 2;
+    }
+    let codes: Vec<u16> = match keys.iter().map(|k| xml_key_to_evdev(k).ok_or(k)).collect() {
+        Ok(codes) => codes,
+        Err(key) => {
+            eprintln!("INPUT: unknown key \"{}\"", key);
+            return 2;
+        }
+    };
+
+    let modifiers = [KeyCode::KEY_LEFTCTRL.0, KeyCode::KEY_LEFTSHIFT.0, KeyCode::KEY_LEFTALT.0];
+    let mut attrs = AttributeSet::<KeyCode>::new();
+    for code in modifiers.iter().chain(&codes).chain(&[KeyCode::KEY_A.0, KeyCode::KEY_Z.0, KeyCode::KEY_ENTER.0]) {
+        attrs.insert(KeyCode(*code));
+    }
+    let built = VirtualDevice::builder()
+        .and_then(|b| b.name(TEST_KEYBOARD_NAME).with_keys(&attrs))
+        .and_then(|b| b.build());
+    let mut device = match built {
+        Ok(d) => d,
+        Err(e) => {
+            eprintln!("INPUT: FAILED reason=uinput_unavailable({})", e);
+            return 1;
+        }
+    };
+
+    // Give udev and a running capture's rescan time to grab the new keyboard
+    std::thread::sleep(RESCAN_INTERVAL + Duration::from_millis(500));
+
+    let key = |code: u16, value: i32| InputEvent::new(EventType::KEY.0, code, value);
+    let step = Duration::from_millis(20);
+    for (name, &code) in keys.iter().zip(&codes) {
+        let mut frames: Vec<InputEvent> = modifiers.iter().map(|&m| key(m, KEY_PRESS)).collect();
+        frames.push(key(code, KEY_PRESS));
+        frames.push(key(code, KEY_RELEASE));
+        frames.extend(modifiers.iter().rev().map(|&m| key(m, KEY_RELEASE)));
+        for event in frames {
+            if let Err(e) = device.emit(&[event]) {
+                eprintln!("INPUT: FAILED reason=emit({})", e);
+                return 1;
+            }
+            std::thread::sleep(step);
+        }
+        eprintln!("INPUT: injected ctrl+shift+alt+{}", name);
+    }
+
+    // Let the capture drain the events before the device disappears
+    std::thread::sleep(Duration::from_millis(200));
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CTRL: u16 = KeyCode::KEY_LEFTCTRL.0;
+    const SHIFT: u16 = KeyCode::KEY_LEFTSHIFT.0;
+    const ALT: u16 = KeyCode::KEY_LEFTALT.0;
+    const U: u16 = KeyCode::KEY_U.0;
+    const A: u16 = KeyCode::KEY_A.0;
+
+    /// Records what the filter dispatches
+    #[derive(Default)]
+    struct Recorder {
+        keys: Vec<(&'static str, bool)>,
+        released: usize,
+        repeats: bool,
+    }
+
+    impl ChordHandler for Recorder {
+        fn chord_key(&mut self, key: &'static str, is_repeat: bool) {
+            self.keys.push((key, is_repeat));
+        }
+        fn accepts_autorepeat(&self, _key: &str) -> bool {
+            self.repeats
+        }
+        fn chord_released(&mut self) {
+            self.released += 1;
+        }
+    }
+
+    /// Press Control+Shift+Alt on a device; returns what passed through
+    fn hold_chord(filter: &mut ChordFilter, device: u32, handler: &mut Recorder) -> Vec<bool> {
+        [CTRL, SHIFT, ALT].iter().map(|&m| filter.key_event(device, m, KEY_PRESS, handler)).collect()
+    }
+
+    fn sorted(mut events: Vec<(u16, i32)>) -> Vec<(u16, i32)> {
+        events.sort();
+        events
+    }
+
+    #[test]
+    fn chord_key_is_swallowed_with_its_repeats_and_release() {
+        let mut filter = ChordFilter::new();
+        let mut handler = Recorder::default();
+        assert_eq!(hold_chord(&mut filter, 0, &mut handler), [true, true, true]);
+
+        assert!(!filter.key_event(0, U, KEY_PRESS, &mut handler));
+        assert!(!filter.key_event(0, U, KEY_REPEAT, &mut handler));
+        handler.repeats = true;
+        assert!(!filter.key_event(0, U, KEY_REPEAT, &mut handler));
+        assert!(!filter.key_event(0, U, KEY_RELEASE, &mut handler));
+        assert_eq!(handler.keys, [("u", false), ("u", true)]);
+
+        assert!(filter.key_event(0, ALT, KEY_RELEASE, &mut handler));
+        assert_eq!(handler.released, 1);
+        // Chord gone: the key passes through again
+        assert!(filter.key_event(0, U, KEY_PRESS, &mut handler));
+    }
+
+    #[test]
+    fn unplug_releases_the_devices_keys() {
+        let mut filter = ChordFilter::new();
+        let mut handler = Recorder::default();
+        filter.key_event(1, A, KEY_PRESS, &mut handler);
+        hold_chord(&mut filter, 0, &mut handler);
+        filter.key_event(0, U, KEY_PRESS, &mut handler);
+
+        // Passed-through modifiers need releases; the swallowed chord key does not
+        let events = filter.device_removed(0, &mut handler);
+        assert_eq!(sorted(events), sorted(vec![(CTRL, KEY_RELEASE), (SHIFT, KEY_RELEASE), (ALT, KEY_RELEASE)]));
+        assert_eq!(handler.released, 1);
+        assert!(filter.swallowed.is_empty());
+        assert!(!filter.held.contains_key(&0));
+
+        // Other devices keep their keys; the same key elsewhere is not a chord
+        assert_eq!(filter.held[&1], HashSet::from([A]));
+        assert!(filter.key_event(1, U, KEY_PRESS, &mut handler));
+        assert!(filter.key_event(1, U, KEY_RELEASE, &mut handler));
+        assert_eq!(handler.keys, [("u", false)]);
+    }
+
+    #[test]
+    fn resync_releases_keys_lost_in_a_drop() {
+        let mut filter = ChordFilter::new();
+        let mut handler = Recorder::default();
+        hold_chord(&mut filter, 0, &mut handler);
+        filter.key_event(0, U, KEY_PRESS, &mut handler);
+
+        // Shift, Alt and U came up and A went down while events were dropped
+        let events = filter.resync(0, &HashSet::from([CTRL, A]), &mut handler);
+        assert_eq!(sorted(events), sorted(vec![(SHIFT, KEY_RELEASE), (ALT, KEY_RELEASE), (A, KEY_PRESS)]));
+        assert_eq!(handler.released, 1);
+        assert!(filter.swallowed.is_empty());
+        assert_eq!(filter.held[&0], HashSet::from([CTRL, A]));
+        assert_eq!(handler.keys, [("u", false)]);
+    }
+
+    #[test]
+    fn resync_swallows_chord_keys_pressed_in_a_drop() {
+        let mut filter = ChordFilter::new();
+        let mut handler = Recorder::default();
+        hold_chord(&mut filter, 0, &mut handler);
+
+        // U went down unseen: not dispatched, but its release stays swallowed
+        let events = filter.resync(0, &HashSet::from([CTRL, SHIFT, ALT, U]), &mut handler);
+        assert!(events.is_empty());
+        assert!(handler.keys.is_empty());
+        assert!(!filter.key_event(0, U, KEY_RELEASE, &mut handler));
+        assert_eq!(handler.released, 0);
+    }
+}
@@ -0,0 +1,223 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+#![cfg(target_os = "linux")]
+
+//! X11 Hotkey Capture (XGrabKey)
+//! Control+Shift+Alt chords for the Form's bound keys, without input-device access
+//!
+//! The alternative to evdev capture (pblbe_evdev) for machines where the user
+//! is not in the `input` group. Works inside an X11 session only.
+//!
+//! - Each bound key is grabbed on the root window as Control+Shift+Alt, once
+//!   per CapsLock/NumLock combination (X11 grabs match modifier state exactly)
+//! - Keys are physical: X keycode = evdev code + 8, so the keyboard layout
+//!   does not change which key a binding means (as with macOS keycodes)
+//! - Chord release comes from XInput2 raw key events on the root, which are
+//!   delivered regardless of grabs, so modifier releases the focused app
+//!   receives still reset layout sessions
+//! - XKB detectable auto-repeat makes held keys arrive as repeated presses
+//!   without synthetic releases in between; held state comes from the raw
+//!   releases either way, so repeats are told apart without it
+//!
+//! A key another client already grabbed (in any lock combination) is reported
+//! and skipped, with none of its combinations left grabbed.
+
+use std::collections::HashSet;
+use std::os::fd::AsRawFd;
+use std::time::{Duration, Instant};
+
+use x11rb::connection::{Connection, RequestConnection};
+use x11rb::protocol::xinput::{self, ConnectionExt as _, XIEventMask};
+use x11rb::protocol::xkb::{self, ConnectionExt as _, PerClientFlag};
+use x11rb::protocol::xproto::{ConnectionExt as _, GrabMode, Keycode, ModMask, Window};
+use x11rb::protocol::Event;
+use x11rb::rust_connection::RustConnection;
+
+use crate::pbgc_core::KeyState;
+use crate::pblbe_evdev::ChordHandler;
+use crate::pblbk_keymap::{evdev_to_hid_usage, evdev_to_xml_key, xml_key_to_evdev};
+
+/// X keycodes are evdev codes offset by 8 (xf86-input-evdev, libinput, XWayland)
+const EVDEV_KEYCODE_OFFSET: u16 = 8;
+
+/// XIAllMasterDevices
+const XI_ALL_MASTER_DEVICES: u16 = 1;
+
+const POLL_TIMEOUT_MS: i32 = 250;
+
+fn x11_err(e: impl std::fmt::Display) -> String {
+    format!("x11_error({})", e)
+}
+
+// ============================================================================
+// SECTION 1: Modifiers and grabs
+// ============================================================================
+
+/// Modifier masks resolved from the server's modifier mapping
+struct ChordMasks {
+    chord: u16,     // Control | Shift | (modifier holding Alt_L)
+    numlock: u16,   // Modifier holding Num_Lock (0 if unmapped)
+}
+
+/// Find which ModN carries Alt and NumLock (Mod1 and Mod2 on most setups)
+fn chord_masks(conn: &RustConnection) -> Result<ChordMasks, String> {
+    let mapping = conn.get_modifier_mapping().map_err(x11_err)?.reply().map_err(x11_err)?;
+    let per_modifier = (mapping.keycodes.len() / 8).max(1);
+    let modifier_for = |evdev_code: u16| -> Option<u16> {
+        let keycode = (evdev_code + EVDEV_KEYCODE_OFFSET) as Keycode;
+        mapping.keycodes.iter().position(|&k| k == keycode).map(|idx| 1u16 << (idx / per_modifier))
+    };
+
+    let alt = modifier_for(evdev::KeyCode::KEY_LEFTALT.0).unwrap_or(u16::from(ModMask::M1));
+    let numlock = modifier_for(evdev::KeyCode::KEY_NUMLOCK.0).unwrap_or(0);
+    Ok(ChordMasks {
+        chord: u16::from(ModMask::CONTROL) | u16::from(ModMask::SHIFT) | alt,
+        numlock,
+    })
+}
+
+/// Grab Control+Shift+Alt+key on the root for every lock-modifier combination
+/// All or nothing: if one variant fails (usually another client holds it), the
+/// variants already grabbed are released and Err reports how far it got
+fn grab_chord(conn: &RustConnection, root: Window, keycode: Keycode, masks: &ChordMasks) -> Result<(), String> {
+    let lock = u16::from(ModMask::LOCK);
+    let mut variants = vec![(0, "none"), (lock, "capslock")];
+    if masks.numlock != 0 {
+        variants.extend([(masks.numlock, "numlock"), (masks.numlock | lock, "capslock+numlock")]);
+    }
+
+    for (taken, &(extra, label)) in variants.iter().enumerate() {
+        let modifiers = ModMask::from(masks.chord | extra);
+        let grabbed = conn.grab_key(false, root, modifiers, keycode, GrabMode::ASYNC, GrabMode::ASYNC)
+            .map_err(x11_err)
+            .and_then(|cookie| cookie.check().map_err(x11_err));
+        if let Err(reason) = grabbed {
+            for &(held, _) in &variants[..taken] {
+                if let Err(e) = conn.ungrab_key(keycode, root, ModMask::from(masks.chord | held)) {
+                    eprintln!("HOTKEY: keycode={} ungrab failed: {}", keycode, x11_err(e));
+                }
+            }
+            return Err(format!("{} on locks={} ({} of {} variants grabbed, released)",
+                reason, label, taken, variants.len()));
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// SECTION 2: Capture loop
+// ============================================================================
+
+/// Grab `keys` as chords on $DISPLAY and run until the timeout (forever if None)
+pub fn run_grab_capture(handler: &mut dyn ChordHandler, keys: &[String], timeout: Option<Duration>) -> Result<(), String> {
+    let (conn, screen_num) = x11rb::connect(None).map_err(|e| format!("x11_connect_failed({})", e))?;
+    let root = conn.setup().roots[screen_num].root;
+
+    // XInput2 raw events (2.1+ delivers them to the root even during grabs)
+    if conn.extension_information(xinput::X11_EXTENSION_NAME).map_err(x11_err)?.is_none() {
+        return Err("xinput2_unavailable".to_string());
+    }
+    let version = conn.xinput_xi_query_version(2, 2).map_err(x11_err)?.reply().map_err(x11_err)?;
+    if (version.major_version, version.minor_version) < (2, 1) {
+        return Err(format!("xinput2_too_old({}.{})", version.major_version, version.minor_version));
+    }
+    let mask = xinput::EventMask {
+        deviceid: XI_ALL_MASTER_DEVICES,
+        mask: vec![XIEventMask::RAW_KEY_PRESS | XIEventMask::RAW_KEY_RELEASE],
+    };
+    conn.xinput_xi_select_events(root, &[mask]).map_err(x11_err)?.check().map_err(x11_err)?;
+
+    // Held keys repeat as presses only (no synthetic release between repeats)
+    // Without it each repeat also sends a core release; core releases are ignored,
+    // a key stays held until its XI2 raw release
+    let detectable_repeat = conn.extension_information(xkb::X11_EXTENSION_NAME).map_err(x11_err)?.is_some()
+        && conn.xkb_use_extension(1, 0).ok().and_then(|c| c.reply().ok()).is_some_and(|r| r.supported)
+        && conn.xkb_per_client_flags(
+                xkb::ID::USE_CORE_KBD.into(),
+                PerClientFlag::DETECTABLE_AUTO_REPEAT,
+                PerClientFlag::DETECTABLE_AUTO_REPEAT,
+                0u32.into(), 0u32.into(), 0u32.into(),
+            ).ok().and_then(|c| c.reply().ok())
+            .is_some_and(|r| r.value.contains(PerClientFlag::DETECTABLE_AUTO_REPEAT));
+    if !detectable_repeat {
+        eprintln!("HOTKEY: XKB detectable auto-repeat unavailable, repeats tracked from raw releases");
+    }
+
+    let masks = chord_masks(&conn)?;
+    let mut grabbed = 0;
+    for key in keys {
+        let code = match xml_key_to_evdev(key) {
+            Some(code) => code,
+            None => {
+                eprintln!("HOTKEY: key={} has no physical key code, skipped", key);
+                continue;
+            }
+        };
+        match grab_chord(&conn, root, (code + EVDEV_KEYCODE_OFFSET) as Keycode, &masks) {
+            Ok(()) => grabbed += 1,
+            Err(reason) => eprintln!("HOTKEY: ctrl+shift+alt+{} not grabbed, skipped: {}", key, reason),
+        }
+    }
+    conn.flush().map_err(x11_err)?;
+    eprintln!("HOTKEY: XGrabKey capture running ({} of {} bound keys grabbed, chord: ctrl+shift+alt)", grabbed, keys.len());
+
+    let mut state = KeyState::default();
+    let mut chord_was_held = false;
+    let mut held_chord_keys: HashSet<Keycode> = HashSet::new();
+    let started = Instant::now();
+
+    loop {
+        if timeout.is_some_and(|t| started.elapsed() >= t) {
+            eprintln!("HOTKEY: exit");
+            return Ok(());
+        }
+
+        while let Some(event) = conn.poll_for_event().map_err(x11_err)? {
+            match event {
+                Event::KeyPress(press) => {
+                    let key = match evdev_to_xml_key((press.detail as u16).saturating_sub(EVDEV_KEYCODE_OFFSET)) {
+                        Some(key) => key,
+                        None => continue,
+                    };
+                    let is_repeat = !held_chord_keys.insert(press.detail);
+                    if is_repeat {
+                        if handler.accepts_autorepeat(key) {
+                            handler.chord_key(key, true);
+                        }
+                    } else {
+                        println!("BLOCKED: ctrl+shift+alt+{}", key);
+                        handler.chord_key(key, false);
+                    }
+                }
+                Event::XinputRawKeyPress(raw) | Event::XinputRawKeyRelease(raw) => {
+                    let is_pressed = raw.event_type == xinput::RAW_KEY_PRESS_EVENT;
+                    let code = (raw.detail as u16).saturating_sub(EVDEV_KEYCODE_OFFSET);
+                    if let Some(usage) = evdev_to_hid_usage(code) {
+                        state.apply(usage, is_pressed);
+                    }
+                    if !is_pressed {
+                        held_chord_keys.remove(&(raw.detail as Keycode));
+                    }
+
+                    // Chord released: reset pane cycles, as on macOS
+                    let chord_held = state.chord_held();
+                    if chord_was_held && !chord_held {
+                        handler.chord_released();
+                    }
+                    chord_was_held = chord_held;
+                }
+                _ => {}
+            }
+        }
+
+        let mut pfd = libc::pollfd { fd: conn.stream().as_raw_fd(), events: libc::POLLIN, revents: 0 };
+        let ready = unsafe { libc::poll(&mut pfd, 1, POLL_TIMEOUT_MS) };
+        if ready < 0 {
+            let err = std::io::Error::last_os_error();
+            if err.kind() != std::io::ErrorKind::Interrupted {
+                return Err(format!("poll_failed({})", err));
+            }
+        }
+    }
+}
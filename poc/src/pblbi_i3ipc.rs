@@ -0,0 +1,355 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+#![cfg(target_os = "linux")]
+
+//! i3/sway IPC Window Backend
+//! WindowBackend over the i3 IPC protocol (sway speaks the same protocol)
+//!
+//! - Socket: $SWAYSOCK, else $I3SOCK (both set for processes the WM starts)
+//! - Windows are leaf containers from GET_TREE, addressed by con_id
+//! - App identity: the container's pid and /proc/<pid>/comm (sway), else the
+//!   X11 WM_CLASS instance (i3 does not report pids)
+//! - Geometry: container rects, which include borders and title bars, the
+//!   same frame-inclusive convention as AX and the EWMH backend
+//! - Placement floats the window first, then runs `move absolute position`
+//!   and `resize set` with pixel values, so the WM's own tiling leaves
+//!   PaneBoard's fractional panes alone
+//! - Displays: active outputs, with the visible workspace rect as usable area
+//!   (bars excluded)
+//!
+//! Commands apply asynchronously in sway (transactions). Writes wait (bounded)
+//! until the tree shows the new geometry, as the EWMH backend does.
+
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::pbgft_types::PixelRect;
+use crate::pbgw_window::{WindowBackend, WindowError, WindowInfo};
+use crate::pblbd_display::{display_from_area, LinuxDisplay};
+use crate::pblbx_x11::{process_name, FrontmostInfo};
+
+/// IPC framing: magic, payload length, message type (native byte order)
+const IPC_MAGIC: &[u8; 6] = b"i3-ipc";
+const IPC_HEADER_LEN: usize = 14;
+
+/// Message types used here
+const RUN_COMMAND: u32 = 0;
+const GET_WORKSPACES: u32 = 1;
+const GET_OUTPUTS: u32 = 3;
+const GET_TREE: u32 = 4;
+
+/// Workspace holding scratchpad windows (hidden, the closest thing to minimized)
+const SCRATCHPAD_WORKSPACE: &str = "__i3_scratch";
+
+/// Poll interval and bound while waiting for the WM to apply a command
+const COMMAND_POLL_MS: u64 = 10;
+const COMMAND_MAX_POLLS: u32 = 10;
+
+// ============================================================================
+// SECTION 1: IPC connection
+// ============================================================================
+
+/// IPC socket of the running i3 or sway, if any
+pub fn ipc_socket_path() -> Option<PathBuf> {
+    std::env::var_os("SWAYSOCK")
+        .or_else(|| std::env::var_os("I3SOCK"))
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+}
+
+fn ipc_err(e: impl std::fmt::Display) -> String {
+    format!("ipc_error({})", e)
+}
+
+/// One IPC connection (no event subscriptions, so every message is a reply)
+pub struct I3Backend {
+    stream: UnixStream,
+}
+
+impl I3Backend {
+    pub fn connect(socket: &PathBuf) -> Result<Self, String> {
+        let stream = UnixStream::connect(socket)
+            .map_err(|e| format!("ipc_connect_failed({}: {})", socket.display(), e))?;
+        stream.set_read_timeout(Some(Duration::from_secs(2))).map_err(ipc_err)?;
+        eprintln!("I3: connected to {}", socket.display());
+        Ok(I3Backend { stream })
+    }
+
+    /// Send one message and parse its JSON reply
+    fn message(&self, message_type: u32, payload: &str) -> Result<Value, String> {
+        let mut request = Vec::with_capacity(IPC_HEADER_LEN + payload.len());
+        request.extend_from_slice(IPC_MAGIC);
+        request.extend_from_slice(&(payload.len() as u32).to_ne_bytes());
+        request.extend_from_slice(&message_type.to_ne_bytes());
+        request.extend_from_slice(payload.as_bytes());
+        (&self.stream).write_all(&request).map_err(ipc_err)?;
+
+        let mut header = [0u8; IPC_HEADER_LEN];
+        (&self.stream).read_exact(&mut header).map_err(ipc_err)?;
+        if &header[..6] != IPC_MAGIC {
+            return Err("ipc_bad_magic".to_string());
+        }
+        let length = u32::from_ne_bytes([header[6], header[7], header[8], header[9]]) as usize;
+        let mut reply = vec![0u8; length];
+        (&self.stream).read_exact(&mut reply).map_err(ipc_err)?;
+        serde_json::from_slice(&reply).map_err(|e| format!("ipc_bad_json({})", e))
+    }
+
+    /// Run a command list; false (and logged) if any command failed
+    fn run_command(&self, command: &str) -> bool {
+        let results = match self.message(RUN_COMMAND, command) {
+            Ok(Value::Array(results)) => results,
+            Ok(_) => Vec::new(),
+            Err(reason) => {
+                eprintln!("I3: command failed | cmd={} | reason={}", command, reason);
+                return false;
+            }
+        };
+        for result in &results {
+            if !result["success"].as_bool().unwrap_or(false) {
+                eprintln!("I3: command failed | cmd={} | error={}", command, result["error"].as_str().unwrap_or("unknown"));
+                return false;
+            }
+        }
+        true
+    }
+}
+
+// ============================================================================
+// SECTION 2: Tree queries
+// ============================================================================
+
+fn json_rect(v: &Value) -> Option<PixelRect> {
+    Some(PixelRect {
+        x: v["x"].as_f64()?,
+        y: v["y"].as_f64()?,
+        width: v["width"].as_f64()?,
+        height: v["height"].as_f64()?,
+    })
+}
+
+/// Leaf container holding an application window
+struct Leaf<'a> {
+    node: &'a Value,
+    workspace: &'a str,
+}
+
+impl Leaf<'_> {
+    fn id(&self) -> i64 {
+        self.node["id"].as_i64().unwrap_or(0)
+    }
+
+    fn pid(&self) -> u32 {
+        self.node["pid"].as_u64().unwrap_or(0) as u32
+    }
+
+    fn app_id(&self) -> String {
+        match self.pid() {
+            0 => self.node["window_properties"]["instance"].as_str()
+                .or_else(|| self.node["app_id"].as_str())
+                .unwrap_or("unknown")
+                .to_string(),
+            pid => process_name(pid),
+        }
+    }
+
+    fn title(&self) -> String {
+        self.node["name"].as_str().map_or_else(|| format!("<con:{}>", self.id()), str::to_string)
+    }
+
+    fn rect(&self) -> Option<PixelRect> {
+        json_rect(&self.node["rect"])
+    }
+
+    fn fullscreen(&self) -> bool {
+        self.node["fullscreen_mode"].as_u64().unwrap_or(0) != 0
+    }
+}
+
+/// Every window leaf in tree order (tiling children before floating ones)
+fn leaves<'a>(node: &'a Value, workspace: &'a str, out: &mut Vec<Leaf<'a>>) {
+    let workspace = if node["type"] == "workspace" { node["name"].as_str().unwrap_or("") } else { workspace };
+    let children: Vec<&Value> = ["nodes", "floating_nodes"].iter()
+        .filter_map(|key| node[*key].as_array())
+        .flatten()
+        .collect();
+
+    let is_window = node["pid"].is_u64() || node["window"].is_u64() || node["app_id"].is_string();
+    if children.is_empty() && is_window && !workspace.is_empty() {
+        out.push(Leaf { node, workspace });
+    }
+    for child in children {
+        leaves(child, workspace, out);
+    }
+}
+
+impl I3Backend {
+    fn tree(&self) -> Option<Value> {
+        match self.message(GET_TREE, "") {
+            Ok(tree) => Some(tree),
+            Err(reason) => {
+                eprintln!("I3: GET_TREE failed | reason={}", reason);
+                None
+            }
+        }
+    }
+
+    /// Look up one leaf by con_id and map it
+    fn with_leaf<T>(&self, con_id: i64, f: impl FnOnce(&Leaf) -> T) -> Option<T> {
+        let tree = self.tree()?;
+        let mut all = Vec::new();
+        leaves(&tree, "", &mut all);
+        all.iter().find(|l| l.id() == con_id).map(f)
+    }
+
+    fn focused_leaf<T>(&self, f: impl FnOnce(&Leaf) -> T) -> Option<T> {
+        let tree = self.tree()?;
+        let mut all = Vec::new();
+        leaves(&tree, "", &mut all);
+        all.iter().find(|l| l.node["focused"].as_bool().unwrap_or(false)).map(f)
+    }
+
+    /// App of the focused window
+    pub fn frontmost(&self) -> Option<FrontmostInfo> {
+        self.focused_leaf(|leaf| FrontmostInfo { pid: leaf.pid(), app_id: leaf.app_id() })
+    }
+
+    /// Active outputs in index order (primary first, then left-to-right/top-to-bottom)
+    pub fn gather_displays(&self) -> Vec<LinuxDisplay> {
+        let outputs = match self.message(GET_OUTPUTS, "") {
+            Ok(Value::Array(outputs)) => outputs,
+            _ => Vec::new(),
+        };
+        let workspaces = match self.message(GET_WORKSPACES, "") {
+            Ok(Value::Array(workspaces)) => workspaces,
+            _ => Vec::new(),
+        };
+
+        let mut active: Vec<(&Value, PixelRect)> = outputs.iter()
+            .filter(|o| o["active"].as_bool().unwrap_or(false))
+            .filter_map(|o| Some((o, json_rect(&o["rect"])?)))
+            .collect();
+        active.sort_by(|(a, ar), (b, br)| {
+            let primary = |o: &Value| o["primary"].as_bool().unwrap_or(false);
+            primary(b).cmp(&primary(a))
+                .then(ar.x.total_cmp(&br.x))
+                .then(ar.y.total_cmp(&br.y))
+        });
+        eprintln!("DISPLAY: {} active output(s) over IPC", active.len());
+
+        active.into_iter().enumerate().map(|(index, (output, rect))| {
+            let name = output["name"].as_str().unwrap_or("unknown");
+            let area = workspaces.iter()
+                .find(|w| w["visible"].as_bool().unwrap_or(false) && w["output"].as_str() == Some(name))
+                .and_then(|w| json_rect(&w["rect"]))
+                .unwrap_or_else(|| rect.clone());
+
+            // sway reports make/model; prefer them, as EDID names are preferred on X11
+            let product = match (output["make"].as_str(), output["model"].as_str()) {
+                (Some(make), Some(model)) if make != "Unknown" => format!("{} {}", make, model),
+                _ => name.to_string(),
+            };
+            display_from_area(index, product, &rect, &area)
+        }).collect()
+    }
+
+    /// Wait until the container's rect satisfies `done`
+    fn wait_for_geometry(&self, con_id: i64, done: impl Fn(&PixelRect) -> bool) {
+        for _ in 0..COMMAND_MAX_POLLS {
+            if self.with_leaf(con_id, |l| l.rect()).flatten().is_some_and(|r| done(&r)) {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(COMMAND_POLL_MS));
+        }
+    }
+}
+
+// ============================================================================
+// SECTION 3: WindowBackend
+// ============================================================================
+
+impl WindowBackend for I3Backend {
+    type Window = i64;
+
+    /// PID 0 accepts any focused window (i3 reports no pids)
+    fn focused_window(&self, pid: u32) -> Result<i64, String> {
+        let (con_id, leaf_pid, title) = self.focused_leaf(|l| (l.id(), l.pid(), l.title()))
+            .ok_or_else(|| "no_focused_window".to_string())?;
+        if pid != 0 && leaf_pid != 0 && leaf_pid != pid {
+            return Err("no_focused_window".to_string());
+        }
+        eprintln!("DEBUG: Focused window: \"{}\"", title);
+        Ok(con_id)
+    }
+
+    fn window_by_id(&self, pid: u32, window_id: u32) -> Option<i64> {
+        self.with_leaf(window_id as i64, |l| (l.id(), l.pid()))
+            .filter(|&(_, leaf_pid)| pid == 0 || leaf_pid == 0 || leaf_pid == pid)
+            .map(|(id, _)| id)
+    }
+
+    fn enumerate_windows(&self, pid: u32) -> Vec<WindowInfo> {
+        let tree = match self.tree() {
+            Some(t) => t,
+            None => return Vec::new(),
+        };
+        let mut all = Vec::new();
+        leaves(&tree, "", &mut all);
+        all.iter()
+            .filter(|l| pid == 0 || l.pid() == pid)
+            .map(|l| WindowInfo {
+                window_id: l.id() as u32,
+                title: l.title(),
+                rect: l.rect(),
+                minimized: l.workspace == SCRATCHPAD_WORKSPACE,
+            })
+            .collect()
+    }
+
+    fn window_id(&self, win: &i64) -> Option<u32> {
+        Some(*win as u32)
+    }
+
+    fn get_rect(&self, win: &i64) -> Option<PixelRect> {
+        self.with_leaf(*win, |l| l.rect()).flatten()
+    }
+
+    fn set_position(&self, win: &i64, x: f64, y: f64) -> Result<(), WindowError> {
+        let command = format!("[con_id={}] floating enable, move absolute position {:.0} px {:.0} px", win, x, y);
+        if !self.run_command(&command) {
+            return Err(WindowError::Platform(-1));
+        }
+        self.wait_for_geometry(*win, |r| (r.x - x).abs() < 1.0 && (r.y - y).abs() < 1.0);
+        Ok(())
+    }
+
+    fn set_size(&self, win: &i64, width: f64, height: f64) -> Result<(), WindowError> {
+        if self.with_leaf(*win, |l| l.fullscreen()).unwrap_or(false) {
+            return Err(WindowError::Constrained);
+        }
+        let before = self.get_rect(win);
+        let command = format!("[con_id={}] floating enable, resize set width {:.0} px height {:.0} px", win, width, height);
+        if !self.run_command(&command) {
+            return Err(WindowError::Constrained);
+        }
+        self.wait_for_geometry(*win, |now| {
+            let reached = (now.width - width).abs() < 1.0 && (now.height - height).abs() < 1.0;
+            let changed = before.as_ref().is_some_and(|b| (now.width - b.width).abs() >= 1.0 || (now.height - b.height).abs() >= 1.0);
+            reached || changed
+        });
+        Ok(())
+    }
+
+    fn raise(&self, pid: u32, window_id: u32) -> bool {
+        match self.window_by_id(pid, window_id) {
+            // Focusing a scratchpad window shows it
+            Some(con_id) => self.run_command(&format!("[con_id={}] focus", con_id)),
+            None => false,
+        }
+    }
+}
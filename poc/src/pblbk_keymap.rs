@@ -0,0 +1,235 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+#![cfg(target_os = "linux")]
+
+//! evdev Key Mapping
+//! Linux input event codes (input-event-codes.h) to HID usages and XML key names
+//!
+//! Mirrors pbmbk_keymap for macOS virtual keycodes: the same HID usages feed
+//! KeyState, and the same KeyType names (pbxs_schema.xsd) select Form bindings.
+
+use evdev::KeyCode;
+
+/// Map evdev key code to XML key name (per KeyType in pbxs_schema.xsd)
+/// Returns None for unmapped or modifier keys
+pub fn evdev_to_xml_key(code: u16) -> Option<&'static str> {
+    match KeyCode(code) {
+        // Letters (lowercase, per schema)
+        KeyCode::KEY_A => Some("a"),
+        KeyCode::KEY_B => Some("b"),
+        KeyCode::KEY_C => Some("c"),
+        KeyCode::KEY_D => Some("d"),
+        KeyCode::KEY_E => Some("e"),
+        KeyCode::KEY_F => Some("f"),
+        KeyCode::KEY_G => Some("g"),
+        KeyCode::KEY_H => Some("h"),
+        KeyCode::KEY_I => Some("i"),
+        KeyCode::KEY_J => Some("j"),
+        KeyCode::KEY_K => Some("k"),
+        KeyCode::KEY_L => Some("l"),
+        KeyCode::KEY_M => Some("m"),
+        KeyCode::KEY_N => Some("n"),
+        KeyCode::KEY_O => Some("o"),
+        KeyCode::KEY_P => Some("p"),
+        KeyCode::KEY_Q => Some("q"),
+        KeyCode::KEY_R => Some("r"),
+        KeyCode::KEY_S => Some("s"),
+        KeyCode::KEY_T => Some("t"),
+        KeyCode::KEY_U => Some("u"),
+        KeyCode::KEY_V => Some("v"),
+        KeyCode::KEY_W => Some("w"),
+        KeyCode::KEY_X => Some("x"),
+        KeyCode::KEY_Y => Some("y"),
+        KeyCode::KEY_Z => Some("z"),
+
+        // Numbers (top row)
+        KeyCode::KEY_0 => Some("0"),
+        KeyCode::KEY_1 => Some("1"),
+        KeyCode::KEY_2 => Some("2"),
+        KeyCode::KEY_3 => Some("3"),
+        KeyCode::KEY_4 => Some("4"),
+        KeyCode::KEY_5 => Some("5"),
+        KeyCode::KEY_6 => Some("6"),
+        KeyCode::KEY_7 => Some("7"),
+        KeyCode::KEY_8 => Some("8"),
+        KeyCode::KEY_9 => Some("9"),
+
+        // Function keys
+        KeyCode::KEY_F1 => Some("f1"),
+        KeyCode::KEY_F2 => Some("f2"),
+        KeyCode::KEY_F3 => Some("f3"),
+        KeyCode::KEY_F4 => Some("f4"),
+        KeyCode::KEY_F5 => Some("f5"),
+        KeyCode::KEY_F6 => Some("f6"),
+        KeyCode::KEY_F7 => Some("f7"),
+        KeyCode::KEY_F8 => Some("f8"),
+        KeyCode::KEY_F9 => Some("f9"),
+        KeyCode::KEY_F10 => Some("f10"),
+        KeyCode::KEY_F11 => Some("f11"),
+        KeyCode::KEY_F12 => Some("f12"),
+
+        // Arrow keys
+        KeyCode::KEY_UP => Some("up"),
+        KeyCode::KEY_DOWN => Some("down"),
+        KeyCode::KEY_LEFT => Some("left"),
+        KeyCode::KEY_RIGHT => Some("right"),
+
+        // Navigation keys
+        KeyCode::KEY_HOME => Some("home"),
+        KeyCode::KEY_END => Some("end"),
+        KeyCode::KEY_PAGEUP => Some("pageup"),
+        KeyCode::KEY_PAGEDOWN => Some("pagedown"),
+
+        // Special keys
+        KeyCode::KEY_ESC => Some("escape"),
+        KeyCode::KEY_TAB => Some("tab"),
+        KeyCode::KEY_SPACE => Some("space"),
+        KeyCode::KEY_ENTER => Some("enter"),
+        KeyCode::KEY_BACKSPACE => Some("backspace"),
+        KeyCode::KEY_DELETE => Some("delete"),
+
+        // Punctuation
+        KeyCode::KEY_GRAVE => Some("grave"),
+        KeyCode::KEY_MINUS => Some("minus"),
+        KeyCode::KEY_EQUAL => Some("equals"),
+        KeyCode::KEY_LEFTBRACE => Some("leftbracket"),
+        KeyCode::KEY_RIGHTBRACE => Some("rightbracket"),
+        KeyCode::KEY_BACKSLASH => Some("backslash"),
+        KeyCode::KEY_SEMICOLON => Some("semicolon"),
+        KeyCode::KEY_APOSTROPHE => Some("apostrophe"),
+        KeyCode::KEY_COMMA => Some("comma"),
+        KeyCode::KEY_DOT => Some("period"),
+        KeyCode::KEY_SLASH => Some("slash"),
+
+        // Numpad
+        KeyCode::KEY_KP0 => Some("numpad0"),
+        KeyCode::KEY_KP1 => Some("numpad1"),
+        KeyCode::KEY_KP2 => Some("numpad2"),
+        KeyCode::KEY_KP3 => Some("numpad3"),
+        KeyCode::KEY_KP4 => Some("numpad4"),
+        KeyCode::KEY_KP5 => Some("numpad5"),
+        KeyCode::KEY_KP6 => Some("numpad6"),
+        KeyCode::KEY_KP7 => Some("numpad7"),
+        KeyCode::KEY_KP8 => Some("numpad8"),
+        KeyCode::KEY_KP9 => Some("numpad9"),
+        KeyCode::KEY_KPPLUS => Some("numpadplus"),
+        KeyCode::KEY_KPMINUS => Some("numpadminus"),
+        KeyCode::KEY_KPASTERISK => Some("numpadmultiply"),
+        KeyCode::KEY_KPSLASH => Some("numpaddivide"),
+        KeyCode::KEY_KPEQUAL => Some("numpadequals"),
+        KeyCode::KEY_KPDOT => Some("numpadperiod"),
+        KeyCode::KEY_KPENTER => Some("numpadenter"),
+
+        _ => None,
+    }
+}
+
+/// Reverse of evdev_to_xml_key (XML key names only use codes below 0x100)
+pub fn xml_key_to_evdev(key: &str) -> Option<u16> {
+    (1..0x100u16).find(|&code| evdev_to_xml_key(code) == Some(key))
+}
+
+/// Map evdev key code to USB HID usage code (keyboard page 0x07)
+/// Used for KeyState tracking, same usages as vk_to_hid_usage on macOS
+pub fn evdev_to_hid_usage(code: u16) -> Option<u32> {
+    match KeyCode(code) {
+        // Letters
+        KeyCode::KEY_A => Some(0x04),
+        KeyCode::KEY_B => Some(0x05),
+        KeyCode::KEY_C => Some(0x06),
+        KeyCode::KEY_D => Some(0x07),
+        KeyCode::KEY_E => Some(0x08),
+        KeyCode::KEY_F => Some(0x09),
+        KeyCode::KEY_G => Some(0x0A),
+        KeyCode::KEY_H => Some(0x0B),
+        KeyCode::KEY_I => Some(0x0C),
+        KeyCode::KEY_J => Some(0x0D),
+        KeyCode::KEY_K => Some(0x0E),
+        KeyCode::KEY_L => Some(0x0F),
+        KeyCode::KEY_M => Some(0x10),
+        KeyCode::KEY_N => Some(0x11),
+        KeyCode::KEY_O => Some(0x12),
+        KeyCode::KEY_P => Some(0x13),
+        KeyCode::KEY_Q => Some(0x14),
+        KeyCode::KEY_R => Some(0x15),
+        KeyCode::KEY_S => Some(0x16),
+        KeyCode::KEY_T => Some(0x17),
+        KeyCode::KEY_U => Some(0x18),
+        KeyCode::KEY_V => Some(0x19),
+        KeyCode::KEY_W => Some(0x1A),
+        KeyCode::KEY_X => Some(0x1B),
+        KeyCode::KEY_Y => Some(0x1C),
+        KeyCode::KEY_Z => Some(0x1D),
+
+        // Numbers
+        KeyCode::KEY_1 => Some(0x1E),
+        KeyCode::KEY_2 => Some(0x1F),
+        KeyCode::KEY_3 => Some(0x20),
+        KeyCode::KEY_4 => Some(0x21),
+        KeyCode::KEY_5 => Some(0x22),
+        KeyCode::KEY_6 => Some(0x23),
+        KeyCode::KEY_7 => Some(0x24),
+        KeyCode::KEY_8 => Some(0x25),
+        KeyCode::KEY_9 => Some(0x26),
+        KeyCode::KEY_0 => Some(0x27),
+
+        // Special keys
+        KeyCode::KEY_ENTER => Some(0x28),
+        KeyCode::KEY_ESC => Some(0x29),
+        KeyCode::KEY_BACKSPACE => Some(0x2A),
+        KeyCode::KEY_TAB => Some(0x2B),
+        KeyCode::KEY_SPACE => Some(0x2C),
+        KeyCode::KEY_MINUS => Some(0x2D),
+        KeyCode::KEY_EQUAL => Some(0x2E),
+        KeyCode::KEY_LEFTBRACE => Some(0x2F),
+        KeyCode::KEY_RIGHTBRACE => Some(0x30),
+        KeyCode::KEY_BACKSLASH => Some(0x31),
+        KeyCode::KEY_SEMICOLON => Some(0x33),
+        KeyCode::KEY_APOSTROPHE => Some(0x34),
+        KeyCode::KEY_GRAVE => Some(0x35),
+        KeyCode::KEY_COMMA => Some(0x36),
+        KeyCode::KEY_DOT => Some(0x37),
+        KeyCode::KEY_SLASH => Some(0x38),
+
+        // Function keys
+        KeyCode::KEY_F1 => Some(0x3A),
+        KeyCode::KEY_F2 => Some(0x3B),
+        KeyCode::KEY_F3 => Some(0x3C),
+        KeyCode::KEY_F4 => Some(0x3D),
+        KeyCode::KEY_F5 => Some(0x3E),
+        KeyCode::KEY_F6 => Some(0x3F),
+        KeyCode::KEY_F7 => Some(0x40),
+        KeyCode::KEY_F8 => Some(0x41),
+        KeyCode::KEY_F9 => Some(0x42),
+        KeyCode::KEY_F10 => Some(0x43),
+        KeyCode::KEY_F11 => Some(0x44),
+        KeyCode::KEY_F12 => Some(0x45),
+
+        // Extended keys
+        KeyCode::KEY_INSERT => Some(0x49),
+        KeyCode::KEY_HOME => Some(0x4A),
+        KeyCode::KEY_PAGEUP => Some(0x4B),
+        KeyCode::KEY_DELETE => Some(0x4C),
+        KeyCode::KEY_END => Some(0x4D),
+        KeyCode::KEY_PAGEDOWN => Some(0x4E),
+
+        // Arrow keys
+        KeyCode::KEY_RIGHT => Some(0x4F),
+        KeyCode::KEY_LEFT => Some(0x50),
+        KeyCode::KEY_DOWN => Some(0x51),
+        KeyCode::KEY_UP => Some(0x52),
+
+        // Modifiers (HID usage codes 0xE0-0xE7; Meta is the Command position)
+        KeyCode::KEY_LEFTCTRL => Some(0xE0),
+        KeyCode::KEY_LEFTSHIFT => Some(0xE1),
+        KeyCode::KEY_LEFTALT => Some(0xE2),
+        KeyCode::KEY_LEFTMETA => Some(0xE3),
+        KeyCode::KEY_RIGHTCTRL => Some(0xE4),
+        KeyCode::KEY_RIGHTSHIFT => Some(0xE5),
+        KeyCode::KEY_RIGHTALT => Some(0xE6),
+        KeyCode::KEY_RIGHTMETA => Some(0xE7),
+
+        _ => None, // Unknown or untracked key code
+    }
+}
@@ -0,0 +1,546 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+#![cfg(target_os = "linux")]
+
+//! Linux Overlays (software rendered)
+//! Alt-Tab and clipboard overlays drawn on the CPU, for X11 and Wayland
+//!
+//! Same model and layout as the macOS OverlayManager/OverlayContentView
+//! (pbmbo_observer.swift): per display, a panel half the usable height and
+//! min(90%, width - 100) wide at the bottom center, with one row per entry.
+//!
+//! - Rendering: tiny-skia rasterizes, ab_glyph lays out glyphs from the
+//!   fontconfig monospace/sans fonts; no GPU or toolkit involved
+//! - Icons: the app's PNG from the hicolor theme or /usr/share/pixmaps,
+//!   else a disc with the app's initial
+//! - Surfaces: wlr layer-shell surfaces on Wayland (pblbow_surface), else
+//!   override-redirect windows on X11 (pblbox_surface); both ignore input
+//!
+//! The overlay runs on its own thread; show/update/hide post to it and return
+//! immediately, as the Swift entry points dispatch to the main run loop.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use ab_glyph::{Font, FontVec, PxScale, ScaleFont};
+use tiny_skia::{Color, FillRule, Paint, PathBuilder, Pixmap, PixmapPaint, Rect, Transform};
+
+use crate::pblbow_surface::LayerOverlay;
+use crate::pblbox_surface::X11Overlay;
+
+/// Panel colors (NSColor(white:alpha:) in the Swift overlay)
+const BACKGROUND: (f32, f32) = (0.1, 0.9);
+const HIGHLIGHT: (f32, f32) = (0.3, 0.8);
+const ICON_FALLBACK: (f32, f32) = (0.45, 1.0);
+
+/// Row layout (points = pixels at scale 1)
+const PADDING: f32 = 20.0;
+const ALT_TAB_LINE_HEIGHT: f32 = 44.0;
+const ALT_TAB_FONT_SIZE: f32 = 20.0;
+const ICON_SIZE: f32 = 32.0;
+const ICON_TEXT_SPACING: f32 = 10.0;
+const CLIPBOARD_LINE_HEIGHT: f32 = 34.0;
+const CLIPBOARD_FONT_SIZE: f32 = 16.0;
+const EMPTY_FONT_SIZE: f32 = 16.0;
+
+/// Clipboard entries longer than this are cut (and "..." appended)
+const CLIPBOARD_DISPLAY_CHARS: usize = 100;
+
+/// Overlay thread wakes this often to service the window system
+const PUMP_INTERVAL_MS: u64 = 50;
+
+/// Used when fontconfig is not installed
+const FALLBACK_FONTS: [&str; 3] = [
+    "/usr/share/fonts/truetype/dejavu/DejaVuSansMono.ttf",
+    "/usr/share/fonts/TTF/DejaVuSansMono.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSansMono.ttf",
+];
+
+// ============================================================================
+// SECTION 1: Model
+// ============================================================================
+
+/// Entry state shown in brackets on Alt-Tab rows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryState {
+    Known,       // Confirmed by activation
+    Guess,       // Discovered, not yet confirmed
+    Clipboard,   // Clipboard history row
+}
+
+impl EntryState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntryState::Known => "KNOWN",
+            EntryState::Guess => "GUESS",
+            EntryState::Clipboard => "CLIPBOARD",
+        }
+    }
+}
+
+/// One overlay row (OverlayEntry in the Swift overlay)
+#[derive(Debug, Clone)]
+pub struct OverlayEntry {
+    pub bundle_id: String,   // App id (process name or desktop id) on Linux
+    pub title: String,
+    pub activation_state: EntryState,
+}
+
+/// Clipboard history as overlay rows: cut to 100 characters, newlines flattened
+pub fn clipboard_entries(history: &[String]) -> Vec<OverlayEntry> {
+    history.iter().map(|text| {
+        let display: String = if text.chars().count() > CLIPBOARD_DISPLAY_CHARS {
+            text.chars().take(CLIPBOARD_DISPLAY_CHARS).chain("...".chars()).collect()
+        } else {
+            text.clone()
+        };
+        OverlayEntry {
+            bundle_id: "clipboard".to_string(),
+            title: display.replace('\n', " "),
+            activation_state: EntryState::Clipboard,
+        }
+    }).collect()
+}
+
+/// Panel size for a display's usable area
+pub fn panel_size(area_width: u32, area_height: u32) -> (u32, u32) {
+    let width = (area_width as f32 * 0.9).min(area_width as f32 - 100.0).max(1.0);
+    let height = (area_height / 2).max(1);
+    (width as u32, height)
+}
+
+// ============================================================================
+// SECTION 2: Fonts, text and icons
+// ============================================================================
+
+/// Fonts used by the overlay rows and the empty message
+pub struct OverlayFonts {
+    mono: FontVec,
+    sans: FontVec,
+}
+
+/// Font file for a fontconfig pattern ("monospace", "sans-serif")
+fn fontconfig_match(pattern: &str) -> Option<PathBuf> {
+    let output = std::process::Command::new("fc-match").args(["-f", "%{file}", pattern]).output().ok()?;
+    let path = PathBuf::from(String::from_utf8(output.stdout).ok()?);
+    if path.is_file() { Some(path) } else { None }
+}
+
+fn load_font(path: &Path) -> Option<FontVec> {
+    let data = std::fs::read(path).ok()?;
+    FontVec::try_from_vec(data).ok()
+}
+
+impl OverlayFonts {
+    pub fn load() -> Result<Self, String> {
+        let mono_path = fontconfig_match("monospace")
+            .or_else(|| FALLBACK_FONTS.iter().map(PathBuf::from).find(|p| p.is_file()))
+            .ok_or_else(|| "no_monospace_font".to_string())?;
+        let mono = load_font(&mono_path).ok_or_else(|| format!("bad_font({})", mono_path.display()))?;
+        let sans = fontconfig_match("sans-serif").and_then(|p| load_font(&p))
+            .unwrap_or_else(|| load_font(&mono_path).expect("font loaded above"));
+        eprintln!("DEBUG: Overlay font {}", mono_path.display());
+        Ok(OverlayFonts { mono, sans })
+    }
+}
+
+/// Pixel scale for a font size in points (em size, as NSFont sizes are)
+fn em_scale(font: &FontVec, size: f32) -> PxScale {
+    let units_per_em = font.units_per_em().unwrap_or(1000.0);
+    PxScale::from(size * font.height_unscaled() / units_per_em)
+}
+
+/// Width of a single line of text
+fn text_width(font: &FontVec, scale: PxScale, text: &str) -> f32 {
+    let scaled = font.as_scaled(scale);
+    text.chars().map(|c| scaled.h_advance(font.glyph_id(c))).sum()
+}
+
+/// Cut text so it fits max_width, ending in an ellipsis (byTruncatingTail)
+fn truncate_tail(font: &FontVec, scale: PxScale, text: &str, max_width: f32) -> String {
+    if text_width(font, scale, text) <= max_width {
+        return text.to_string();
+    }
+    let scaled = font.as_scaled(scale);
+    let budget = max_width - scaled.h_advance(font.glyph_id('…'));
+    let mut width = 0.0;
+    let mut cut = String::new();
+    for c in text.chars() {
+        width += scaled.h_advance(font.glyph_id(c));
+        if width > budget {
+            break;
+        }
+        cut.push(c);
+    }
+    cut.push('…');
+    cut
+}
+
+/// Draw white text with its top at `top` (line box = ascent + descent)
+fn draw_text(pixmap: &mut Pixmap, font: &FontVec, scale: PxScale, x: f32, top: f32, text: &str) {
+    let scaled = font.as_scaled(scale);
+    let baseline = top + scaled.ascent();
+    let (width, height) = (pixmap.width() as i32, pixmap.height() as i32);
+    let pixels = pixmap.pixels_mut();
+
+    let mut pen = x;
+    for c in text.chars() {
+        let id = font.glyph_id(c);
+        let glyph = id.with_scale_and_position(scale, ab_glyph::point(pen, baseline));
+        pen += scaled.h_advance(id);
+        let outlined = match font.outline_glyph(glyph) {
+            Some(o) => o,
+            None => continue,   // Space or missing glyph
+        };
+        let bounds = outlined.px_bounds();
+        outlined.draw(|gx, gy, coverage| {
+            let px = bounds.min.x as i32 + gx as i32;
+            let py = bounds.min.y as i32 + gy as i32;
+            if px < 0 || py < 0 || px >= width || py >= height {
+                return;
+            }
+            let dst = &mut pixels[(py * width + px) as usize];
+            let cov = coverage.clamp(0.0, 1.0);
+            let blend = |d: u8| (255.0 * cov + d as f32 * (1.0 - cov)).round() as u8;
+            if let Some(c) = tiny_skia::PremultipliedColorU8::from_rgba(blend(dst.red()), blend(dst.green()), blend(dst.blue()), blend(dst.alpha())) {
+                *dst = c;
+            }
+        });
+    }
+}
+
+/// Line box height of a font at a scale
+fn line_height(font: &FontVec, scale: PxScale) -> f32 {
+    let scaled = font.as_scaled(scale);
+    scaled.ascent() - scaled.descent()
+}
+
+lazy_static::lazy_static! {
+    /// App icons by app id (None cached too: lookups hit the filesystem)
+    static ref ICON_CACHE: Mutex<HashMap<String, Option<Pixmap>>> = Mutex::new(HashMap::new());
+}
+
+/// PNG icon for an app id from the hicolor theme or /usr/share/pixmaps
+fn app_icon(app_id: &str) -> Option<Pixmap> {
+    let mut cache = ICON_CACHE.lock().unwrap();
+    cache.entry(app_id.to_string()).or_insert_with(|| {
+        let names = [app_id.to_string(), app_id.to_lowercase()];
+        let dirs = ["/usr/share/icons/hicolor/32x32/apps", "/usr/share/icons/hicolor/48x48/apps",
+                    "/usr/share/icons/hicolor/64x64/apps", "/usr/share/icons/hicolor/128x128/apps",
+                    "/usr/share/icons/hicolor/256x256/apps", "/usr/share/pixmaps"];
+        dirs.iter()
+            .flat_map(|dir| names.iter().map(move |name| Path::new(dir).join(format!("{}.png", name))))
+            .find_map(|path| Pixmap::load_png(&path).ok())
+    }).clone()
+}
+
+fn gray(white: f32, alpha: f32) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color(Color::from_rgba(white, white, white, alpha).unwrap_or(Color::BLACK));
+    paint.anti_alias = true;
+    paint
+}
+
+/// Icon scaled into a square, or a disc with the app's initial
+fn draw_icon(pixmap: &mut Pixmap, fonts: &OverlayFonts, app_id: &str, x: f32, y: f32) {
+    if let Some(icon) = app_icon(app_id) {
+        let scale = ICON_SIZE / icon.width().max(icon.height()) as f32;
+        let paint = PixmapPaint { quality: tiny_skia::FilterQuality::Bicubic, ..PixmapPaint::default() };
+        pixmap.draw_pixmap(0, 0, icon.as_ref(), &paint, Transform::from_row(scale, 0.0, 0.0, scale, x, y), None);
+        return;
+    }
+
+    let radius = ICON_SIZE / 2.0;
+    if let Some(disc) = PathBuilder::from_circle(x + radius, y + radius, radius) {
+        pixmap.fill_path(&disc, &gray(ICON_FALLBACK.0, ICON_FALLBACK.1), FillRule::Winding, Transform::identity(), None);
+    }
+    let name = app_id.rsplit('.').next().unwrap_or(app_id);   // "org.mozilla.firefox" -> "firefox"
+    let initial: String = name.chars().find(|c| c.is_alphanumeric()).map(|c| c.to_uppercase().collect())
+        .unwrap_or_else(|| "?".to_string());
+    let scale = em_scale(&fonts.sans, ALT_TAB_FONT_SIZE);
+    let w = text_width(&fonts.sans, scale, &initial);
+    let h = line_height(&fonts.sans, scale);
+    draw_text(pixmap, &fonts.sans, scale, x + radius - w / 2.0, y + radius - h / 2.0, &initial);
+}
+
+// ============================================================================
+// SECTION 3: Rendering
+// ============================================================================
+
+fn fill_rect(pixmap: &mut Pixmap, x: f32, y: f32, w: f32, h: f32, color: (f32, f32)) {
+    if let Some(rect) = Rect::from_xywh(x, y, w, h) {
+        pixmap.fill_rect(rect, &gray(color.0, color.1), Transform::identity(), None);
+    }
+}
+
+/// Draw the overlay panel (width x height pixels) for the entries
+/// Layout is top-down with the first entry on top, as in the Swift view
+pub fn render_overlay(fonts: &OverlayFonts, entries: &[OverlayEntry], highlight: usize, width: u32, height: u32) -> Option<Pixmap> {
+    let mut pixmap = Pixmap::new(width, height)?;
+    pixmap.fill(Color::from_rgba(BACKGROUND.0, BACKGROUND.0, BACKGROUND.0, BACKGROUND.1)?);
+    let (w, h) = (width as f32, height as f32);
+
+    if entries.is_empty() {
+        let scale = em_scale(&fonts.sans, EMPTY_FONT_SIZE);
+        let message = "(no items)";
+        let x = (w - text_width(&fonts.sans, scale, message)) / 2.0;
+        let y = (h - line_height(&fonts.sans, scale)) / 2.0;
+        draw_text(&mut pixmap, &fonts.sans, scale, x, y, message);
+        return Some(pixmap);
+    }
+
+    let clipboard = entries[0].activation_state == EntryState::Clipboard;
+    let (row_height, font_size) = if clipboard {
+        (CLIPBOARD_LINE_HEIGHT, CLIPBOARD_FONT_SIZE)
+    } else {
+        (ALT_TAB_LINE_HEIGHT, ALT_TAB_FONT_SIZE)
+    };
+    let scale = em_scale(&fonts.mono, font_size);
+    let text_height = line_height(&fonts.mono, scale);
+
+    let mut top = PADDING;
+    for (index, entry) in entries.iter().enumerate() {
+        if top + row_height > h - PADDING {
+            break;   // Out of space
+        }
+        if index == highlight {
+            fill_rect(&mut pixmap, PADDING, top, w - 2.0 * PADDING, row_height, HIGHLIGHT);
+        }
+
+        let (text, text_x) = if clipboard {
+            (format!("{}. {}", index + 1, entry.title), PADDING + 10.0)
+        } else {
+            draw_icon(&mut pixmap, fonts, &entry.bundle_id, PADDING + 10.0, top + (row_height - ICON_SIZE) / 2.0);
+            let reversed: Vec<&str> = entry.bundle_id.split('.').rev().collect();
+            (format!("{}. {} | {} [{}]", index + 1, entry.title, reversed.join("."), entry.activation_state.as_str()),
+             PADDING + 10.0 + ICON_SIZE + ICON_TEXT_SPACING)
+        };
+        let max_width = w - text_x - PADDING - 10.0;
+        let fitted = truncate_tail(&fonts.mono, scale, &text, max_width);
+        draw_text(&mut pixmap, &fonts.mono, scale, text_x, top + (row_height - text_height) / 2.0, &fitted);
+
+        top += row_height;
+    }
+    Some(pixmap)
+}
+
+// ============================================================================
+// SECTION 4: Overlay thread
+// ============================================================================
+
+/// Window-system side of the overlay: one surface per display
+/// `render` draws a panel of the given size; surfaces pick the sizes
+pub trait OverlaySurfaces {
+    fn show(&mut self, render: &dyn Fn(u32, u32) -> Option<Pixmap>) -> Result<(), String>;
+
+    /// Redraw the visible surfaces in place
+    fn update(&mut self, render: &dyn Fn(u32, u32) -> Option<Pixmap>) -> Result<(), String>;
+
+    fn hide(&mut self);
+
+    /// Service window-system events (called between commands)
+    fn pump(&mut self) {}
+}
+
+enum OverlayCommand {
+    Show(Vec<OverlayEntry>, usize),
+    Update(Vec<OverlayEntry>, usize),
+    Hide,
+}
+
+static OVERLAY: OnceLock<Mutex<Sender<OverlayCommand>>> = OnceLock::new();
+
+/// Wayland layer shell when available, else X11 (XWayland included)
+fn open_surfaces() -> Result<Box<dyn OverlaySurfaces>, String> {
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        match LayerOverlay::connect() {
+            Ok(layer) => return Ok(Box::new(layer)),
+            Err(reason) if std::env::var_os("DISPLAY").is_some() => {
+                eprintln!("OVERLAY: layer shell unavailable (reason={}), using X11", reason);
+            }
+            Err(reason) => return Err(reason),
+        }
+    }
+    X11Overlay::connect().map(|x11| Box::new(x11) as Box<dyn OverlaySurfaces>)
+}
+
+fn run_overlay_thread(commands: Receiver<OverlayCommand>) {
+    let fonts = match OverlayFonts::load() {
+        Ok(f) => f,
+        Err(reason) => {
+            eprintln!("OVERLAY: FAILED reason={}", reason);
+            return;
+        }
+    };
+    let mut surfaces = match open_surfaces() {
+        Ok(s) => s,
+        Err(reason) => {
+            eprintln!("OVERLAY: FAILED reason={}", reason);
+            return;
+        }
+    };
+
+    loop {
+        let command = match commands.recv_timeout(Duration::from_millis(PUMP_INTERVAL_MS)) {
+            Ok(c) => c,
+            Err(RecvTimeoutError::Timeout) => {
+                surfaces.pump();
+                continue;
+            }
+            Err(RecvTimeoutError::Disconnected) => return,
+        };
+        let result = match &command {
+            OverlayCommand::Show(entries, highlight) => {
+                surfaces.show(&|w, h| render_overlay(&fonts, entries, *highlight, w, h))
+            }
+            OverlayCommand::Update(entries, highlight) => {
+                surfaces.update(&|w, h| render_overlay(&fonts, entries, *highlight, w, h))
+            }
+            OverlayCommand::Hide => {
+                surfaces.hide();
+                Ok(())
+            }
+        };
+        if let Err(reason) = result {
+            eprintln!("OVERLAY: FAILED reason={}", reason);
+        }
+    }
+}
+
+fn post(command: OverlayCommand) {
+    let sender = OVERLAY.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || run_overlay_thread(receiver));
+        Mutex::new(sender)
+    });
+    let _ = sender.lock().unwrap().send(command);
+}
+
+fn log_entries(heading: &str, entries: &[OverlayEntry], highlight: usize) {
+    println!("=== {} ===", heading);
+    for (index, entry) in entries.iter().enumerate() {
+        let marker = if index == highlight { " <--" } else { "" };
+        println!("{}. {} | \"{}\" [{}]{}", index + 1, entry.bundle_id, entry.title, entry.activation_state.as_str(), marker);
+    }
+}
+
+/// Show the overlay on every display (replaces any visible overlay)
+pub fn show_overlay(entries: Vec<OverlayEntry>, highlight: usize) {
+    if entries.first().is_none_or(|e| e.activation_state != EntryState::Clipboard) {
+        log_entries("ALT-TAB OVERLAY (showing)", &entries, highlight);
+    }
+    post(OverlayCommand::Show(entries, highlight));
+}
+
+/// Redraw the visible overlay with a new highlight
+pub fn update_overlay_highlight(entries: Vec<OverlayEntry>, highlight: usize) {
+    if entries.first().is_none_or(|e| e.activation_state != EntryState::Clipboard) {
+        log_entries("ALT-TAB OVERLAY (highlight update)", &entries, highlight);
+    }
+    post(OverlayCommand::Update(entries, highlight));
+}
+
+pub fn hide_overlay() {
+    post(OverlayCommand::Hide);
+}
+
+// ============================================================================
+// SECTION 5: CLI
+// ============================================================================
+
+/// Run `overlay [--clipboard] [--png PATH] [--timeout SECONDS] [ENTRY...]`
+/// Alt-Tab entries are "app_id=title" (the first is KNOWN, the rest GUESS);
+/// with --clipboard each ENTRY is history text, or the live history if none.
+/// Shows the overlay and moves the highlight every half second until the
+/// timeout; --png renders one 1728x540 panel to a file instead (no display needed)
+/// Exit code: 0 on success, 1 if rendering failed, 2 on usage errors
+pub fn run_overlay_cli(args: &[String]) -> i32 {
+    const USAGE: &str = "Usage: paneboard-poc overlay [--clipboard] [--png PATH] [--timeout SECONDS] [ENTRY...]";
+    let mut clipboard = false;
+    let mut png: Option<PathBuf> = None;
+    let mut timeout = Duration::from_secs(3);
+    let mut items: Vec<String> = Vec::new();
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--clipboard" => clipboard = true,
+            "--png" => match rest.next() {
+                Some(path) => png = Some(PathBuf::from(path)),
+                None => {
+                    eprintln!("{}", USAGE);
+                    return 2;
+                }
+            },
+            "--timeout" => match rest.next().and_then(|v| v.parse::<f64>().ok()) {
+                Some(secs) if secs > 0.0 => timeout = Duration::from_secs_f64(secs),
+                _ => {
+                    eprintln!("ERROR: --timeout value must be a positive number");
+                    return 2;
+                }
+            },
+            flag if flag.starts_with("--") => {
+                eprintln!("{}", USAGE);
+                return 2;
+            }
+            item => items.push(item.to_string()),
+        }
+    }
+
+    let entries = if clipboard {
+        if items.is_empty() {
+            items = crate::pbgcl_clipboard::CLIPBOARD_HISTORY.lock().unwrap().clone();
+        }
+        clipboard_entries(&items)
+    } else {
+        items.iter().enumerate().map(|(index, item)| {
+            let (app_id, title) = item.split_once('=').unwrap_or((item.as_str(), item.as_str()));
+            OverlayEntry {
+                bundle_id: app_id.to_string(),
+                title: title.to_string(),
+                activation_state: if index == 0 { EntryState::Known } else { EntryState::Guess },
+            }
+        }).collect()
+    };
+
+    if let Some(path) = png {
+        let fonts = match OverlayFonts::load() {
+            Ok(f) => f,
+            Err(reason) => {
+                eprintln!("OVERLAY: FAILED reason={}", reason);
+                return 1;
+            }
+        };
+        let (width, height) = panel_size(1920, 1080);
+        let saved = render_overlay(&fonts, &entries, 0, width, height)
+            .ok_or_else(|| "empty_panel".to_string())
+            .and_then(|p| p.save_png(&path).map_err(|e| e.to_string()));
+        return match saved {
+            Ok(()) => {
+                println!("OVERLAY: rendered {}x{} to {}", width, height, path.display());
+                0
+            }
+            Err(reason) => {
+                eprintln!("OVERLAY: FAILED reason={}", reason);
+                1
+            }
+        };
+    }
+
+    show_overlay(entries.clone(), 0);
+    let started = Instant::now();
+    let mut highlight = 0;
+    while started.elapsed() < timeout {
+        std::thread::sleep(Duration::from_millis(500));
+        if !entries.is_empty() {
+            highlight = (highlight + 1) % entries.len();
+            update_overlay_highlight(entries.clone(), highlight);
+        }
+    }
+    hide_overlay();
+    std::thread::sleep(Duration::from_millis(2 * PUMP_INTERVAL_MS));
+    0
+}
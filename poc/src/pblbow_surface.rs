@@ -0,0 +1,285 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+#![cfg(target_os = "linux")]
+
+//! Wayland Overlay Surfaces (wlr layer shell)
+//! One layer-shell surface per output for the software overlay
+//!
+//! Works on compositors with zwlr_layer_shell_v1 (sway, Hyprland, KDE, river,
+//! labwc). Each output gets a surface on the overlay layer:
+//! - First anchored to all edges with size 0, so the compositor's configure
+//!   reports the usable area (outputs minus panels' exclusive zones)
+//! - Then resized to the panel and anchored to the bottom edge only, which
+//!   centers it horizontally, and given an empty input region
+//!
+//! Panels are premultiplied ARGB8888 shm buffers in a memfd, one per frame;
+//! the compositor's release destroys them. Rendering is at scale 1.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::os::fd::{AsFd, AsRawFd, FromRawFd};
+use std::time::{Duration, Instant};
+
+use tiny_skia::Pixmap;
+use wayland_client::globals::{registry_queue_init, GlobalListContents};
+use wayland_client::protocol::{
+    wl_buffer::{self, WlBuffer}, wl_compositor::WlCompositor, wl_output::WlOutput, wl_region::WlRegion,
+    wl_registry::WlRegistry, wl_shm::{self, WlShm}, wl_shm_pool::WlShmPool, wl_surface::WlSurface,
+};
+use wayland_client::{Connection, Dispatch, EventQueue, Proxy, QueueHandle};
+use wayland_protocols_wlr::layer_shell::v1::client::{
+    zwlr_layer_shell_v1::{self, ZwlrLayerShellV1},
+    zwlr_layer_surface_v1::{self, Anchor, KeyboardInteractivity, ZwlrLayerSurfaceV1},
+};
+
+use crate::pblbo_overlay::{panel_size, OverlaySurfaces};
+
+/// Layer-shell namespace (compositor rules can match it)
+const NAMESPACE: &str = "paneboard-overlay";
+
+/// Give up on a compositor that does not configure a surface
+const CONFIGURE_TIMEOUT: Duration = Duration::from_secs(1);
+
+fn wayland_err(e: impl std::fmt::Display) -> String {
+    format!("wayland_error({})", e)
+}
+
+// ============================================================================
+// SECTION 1: Event handling
+// ============================================================================
+
+/// Last configure (or closed) per surface, keyed by output index
+#[derive(Default)]
+struct LayerState {
+    configured: HashMap<usize, (u32, u32)>,
+    closed: Vec<usize>,
+}
+
+impl Dispatch<WlRegistry, GlobalListContents> for LayerState {
+    fn event(_: &mut Self, _: &WlRegistry, _: <WlRegistry as Proxy>::Event, _: &GlobalListContents, _: &Connection, _: &QueueHandle<Self>) {}
+}
+
+impl Dispatch<ZwlrLayerSurfaceV1, usize> for LayerState {
+    fn event(state: &mut Self, surface: &ZwlrLayerSurfaceV1, event: zwlr_layer_surface_v1::Event, index: &usize, _: &Connection, _: &QueueHandle<Self>) {
+        match event {
+            zwlr_layer_surface_v1::Event::Configure { serial, width, height } => {
+                surface.ack_configure(serial);
+                state.configured.insert(*index, (width, height));
+            }
+            zwlr_layer_surface_v1::Event::Closed => state.closed.push(*index),
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<WlBuffer, ()> for LayerState {
+    fn event(_: &mut Self, buffer: &WlBuffer, event: wl_buffer::Event, _: &(), _: &Connection, _: &QueueHandle<Self>) {
+        if let wl_buffer::Event::Release = event {
+            buffer.destroy();
+        }
+    }
+}
+
+/// Objects whose events the overlay ignores
+macro_rules! ignore_events {
+    ($($Proxy:ty),*) => {$(
+        impl Dispatch<$Proxy, ()> for LayerState {
+            fn event(_: &mut Self, _: &$Proxy, _: <$Proxy as Proxy>::Event, _: &(), _: &Connection, _: &QueueHandle<Self>) {}
+        }
+    )*};
+}
+
+ignore_events!(WlCompositor, WlShm, WlShmPool, WlSurface, WlRegion, WlOutput, ZwlrLayerShellV1);
+
+// ============================================================================
+// SECTION 2: Surfaces
+// ============================================================================
+
+/// One visible panel
+struct Panel {
+    index: usize,
+    surface: WlSurface,
+    layer: ZwlrLayerSurfaceV1,
+    width: u32,
+    height: u32,
+}
+
+pub struct LayerOverlay {
+    conn: Connection,
+    queue: EventQueue<LayerState>,
+    qh: QueueHandle<LayerState>,
+    state: LayerState,
+    compositor: WlCompositor,
+    shm: WlShm,
+    layer_shell: ZwlrLayerShellV1,
+    outputs: Vec<WlOutput>,
+    panels: Vec<Panel>,
+}
+
+impl LayerOverlay {
+    /// Connect to $WAYLAND_DISPLAY and bind the layer shell and every output
+    pub fn connect() -> Result<Self, String> {
+        let conn = Connection::connect_to_env().map_err(|e| format!("wayland_connect_failed({})", e))?;
+        let (globals, queue) = registry_queue_init::<LayerState>(&conn).map_err(wayland_err)?;
+        let qh = queue.handle();
+
+        let layer_shell: ZwlrLayerShellV1 = globals.bind(&qh, 1..=4, ()).map_err(|_| "layer_shell_unavailable".to_string())?;
+        let compositor: WlCompositor = globals.bind(&qh, 1..=4, ()).map_err(|_| "no_compositor".to_string())?;
+        let shm: WlShm = globals.bind(&qh, 1..=1, ()).map_err(|_| "no_shm".to_string())?;
+        let outputs: Vec<WlOutput> = globals.contents().with_list(|list| {
+            list.iter()
+                .filter(|g| g.interface == WlOutput::interface().name)
+                .map(|g| globals.registry().bind(g.name, g.version.min(4), &qh, ()))
+                .collect()
+        });
+        if outputs.is_empty() {
+            return Err("no_outputs".to_string());
+        }
+
+        eprintln!("DEBUG: Overlay using Wayland layer shell ({} output(s))", outputs.len());
+        Ok(LayerOverlay {
+            conn, queue, qh, state: LayerState::default(),
+            compositor, shm, layer_shell, outputs, panels: Vec::new(),
+        })
+    }
+
+    /// Dispatch until the compositor configures `index` (or closes it)
+    fn wait_configure(&mut self, index: usize) -> Result<(u32, u32), String> {
+        let started = Instant::now();
+        self.state.configured.remove(&index);
+        while started.elapsed() < CONFIGURE_TIMEOUT {
+            self.queue.roundtrip(&mut self.state).map_err(wayland_err)?;
+            if self.state.closed.contains(&index) {
+                return Err("surface_closed".to_string());
+            }
+            if let Some(size) = self.state.configured.get(&index) {
+                return Ok(*size);
+            }
+        }
+        Err("configure_timeout".to_string())
+    }
+
+    /// Attach a rendered panel as a new shm buffer and commit
+    fn present(&self, surface: &WlSurface, panel: &Pixmap) -> Result<(), String> {
+        let (width, height) = (panel.width() as i32, panel.height() as i32);
+        let stride = width * 4;
+
+        // ARGB8888 is little-endian: bytes B, G, R, A (premultiplied, as tiny-skia)
+        let mut data = Vec::with_capacity(panel.data().len());
+        for rgba in panel.data().chunks_exact(4) {
+            data.extend([rgba[2], rgba[1], rgba[0], rgba[3]]);
+        }
+
+        let fd = unsafe { libc::memfd_create(c"paneboard-overlay".as_ptr(), libc::MFD_CLOEXEC) };
+        if fd < 0 {
+            return Err(format!("memfd_failed({})", std::io::Error::last_os_error()));
+        }
+        let mut file = unsafe { File::from_raw_fd(fd) };
+        file.write_all(&data).map_err(|e| format!("memfd_failed({})", e))?;
+
+        let pool = self.shm.create_pool(file.as_fd(), data.len() as i32, &self.qh, ());
+        let buffer = pool.create_buffer(0, width, height, stride, wl_shm::Format::Argb8888, &self.qh, ());
+        pool.destroy();   // Buffer keeps the memory alive
+
+        surface.attach(Some(&buffer), 0, 0);
+        surface.damage_buffer(0, 0, width, height);
+        surface.commit();
+        Ok(())
+    }
+
+    /// Create, size and draw the panel for one output
+    fn show_on_output(&mut self, index: usize, render: &dyn Fn(u32, u32) -> Option<Pixmap>) -> Result<(), String> {
+        let surface = self.compositor.create_surface(&self.qh, ());
+        let layer = self.layer_shell.get_layer_surface(&surface, Some(&self.outputs[index]),
+            zwlr_layer_shell_v1::Layer::Overlay, NAMESPACE.to_string(), &self.qh, index);
+        layer.set_keyboard_interactivity(KeyboardInteractivity::None);
+
+        let region = self.compositor.create_region(&self.qh, ());
+        surface.set_input_region(Some(&region));
+        region.destroy();
+
+        // Probe: stretched to all edges, the configure size is the usable area
+        layer.set_anchor(Anchor::Top | Anchor::Bottom | Anchor::Left | Anchor::Right);
+        layer.set_size(0, 0);
+        surface.commit();
+        let probed = self.wait_configure(index);
+        let (area_width, area_height) = match probed {
+            Ok(size) if size.0 > 0 && size.1 > 0 => size,
+            other => {
+                layer.destroy();
+                surface.destroy();
+                return Err(other.err().unwrap_or_else(|| "empty_output".to_string()));
+            }
+        };
+
+        let (width, height) = panel_size(area_width, area_height);
+        layer.set_anchor(Anchor::Bottom);
+        layer.set_size(width, height);
+        surface.commit();
+        let sized = self.wait_configure(index);
+
+        let image = render(width, height);
+        let panel = Panel { index, surface, layer, width, height };
+        if let (Ok(_), Some(image)) = (sized, image) {
+            self.present(&panel.surface, &image)?;
+            eprintln!("OVERLAY: layer panel on output {} {}x{} (usable {}x{})", index, width, height, area_width, area_height);
+        }
+        self.panels.push(panel);
+        Ok(())
+    }
+}
+
+impl OverlaySurfaces for LayerOverlay {
+    fn show(&mut self, render: &dyn Fn(u32, u32) -> Option<Pixmap>) -> Result<(), String> {
+        self.hide();
+        self.state.closed.clear();
+        let mut failure = None;
+        for index in 0..self.outputs.len() {
+            if let Err(reason) = self.show_on_output(index, render) {
+                eprintln!("OVERLAY: output {} skipped (reason={})", index, reason);
+                failure = Some(reason);
+            }
+        }
+        self.conn.flush().map_err(wayland_err)?;
+        match failure {
+            Some(reason) if self.panels.is_empty() => Err(reason),
+            _ => Ok(()),
+        }
+    }
+
+    fn update(&mut self, render: &dyn Fn(u32, u32) -> Option<Pixmap>) -> Result<(), String> {
+        for panel in &self.panels {
+            if self.state.closed.contains(&panel.index) {
+                continue;
+            }
+            if let Some(image) = render(panel.width, panel.height) {
+                self.present(&panel.surface, &image)?;
+            }
+        }
+        self.conn.flush().map_err(wayland_err)
+    }
+
+    fn hide(&mut self) {
+        for panel in self.panels.drain(..) {
+            panel.layer.destroy();
+            panel.surface.destroy();
+        }
+        let _ = self.conn.flush();
+    }
+
+    fn pump(&mut self) {
+        // Read whatever arrived (buffer releases, closes) without blocking
+        let _ = self.conn.flush();
+        if let Some(guard) = self.queue.prepare_read() {
+            let mut poll_fd = libc::pollfd { fd: guard.connection_fd().as_raw_fd(), events: libc::POLLIN, revents: 0 };
+            if unsafe { libc::poll(&mut poll_fd, 1, 0) } > 0 {
+                let _ = guard.read();
+            }
+        }
+        if let Err(e) = self.queue.dispatch_pending(&mut self.state) {
+            eprintln!("OVERLAY: Wayland dispatch failed ({})", e);
+        }
+    }
+}
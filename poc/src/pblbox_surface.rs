@@ -0,0 +1,166 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+#![cfg(target_os = "linux")]
+
+//! X11 Overlay Surfaces
+//! One override-redirect window per display for the software overlay
+//!
+//! Windows sit at the bottom center of each display's viewport, bypass the
+//! window manager (override-redirect, never focused) and have an empty XFixes
+//! input shape so clicks fall through, like ignoresMouseEvents on macOS.
+//!
+//! Panels are uploaded with PutImage into a server pixmap that is set as the
+//! window background, so the server repaints exposures without a round trip.
+//! Uses the root visual: without a compositor the panel is drawn opaque.
+
+use tiny_skia::Pixmap;
+use x11rb::connection::{Connection, RequestConnection};
+use x11rb::protocol::shape;
+use x11rb::protocol::xfixes::ConnectionExt as _;
+use x11rb::protocol::xproto::{
+    ChangeWindowAttributesAux, ConnectionExt as _, CreateWindowAux, ImageFormat, ImageOrder, WindowClass,
+};
+
+use crate::pblbd_display::gather_displays;
+use crate::pblbo_overlay::{panel_size, OverlaySurfaces};
+use crate::pblbx_x11::X11Backend;
+
+fn x11_err(e: impl std::fmt::Display) -> String {
+    format!("x11_error({})", e)
+}
+
+/// One visible panel
+struct Panel {
+    window: u32,
+    pixmap: u32,
+    width: u16,
+    height: u16,
+}
+
+pub struct X11Overlay {
+    x11: X11Backend,
+    depth: u8,
+    lsb_first: bool,   // Server image byte order
+    gc: u32,
+    panels: Vec<Panel>,
+}
+
+impl X11Overlay {
+    /// Connect to $DISPLAY; needs XFixes 2+ and a 32 bits-per-pixel root depth
+    pub fn connect() -> Result<Self, String> {
+        let x11 = X11Backend::connect()?;
+        let setup = x11.conn.setup();
+        let depth = setup.roots.iter().find(|s| s.root == x11.root).map_or(0, |s| s.root_depth);
+        let bpp = setup.pixmap_formats.iter().find(|f| f.depth == depth).map_or(0, |f| f.bits_per_pixel);
+        if depth < 24 || bpp != 32 {
+            return Err(format!("unsupported_visual(depth={} bpp={})", depth, bpp));
+        }
+        let lsb_first = setup.image_byte_order == ImageOrder::LSB_FIRST;
+
+        let version = x11.conn.xfixes_query_version(5, 0).map_err(x11_err)?.reply()
+            .map_err(|_| "xfixes_unavailable".to_string())?;
+        if version.major_version < 2 {
+            return Err(format!("xfixes_too_old({}.{})", version.major_version, version.minor_version));
+        }
+
+        let gc = x11.conn.generate_id().map_err(x11_err)?;
+        x11.conn.create_gc(gc, x11.root, &Default::default()).map_err(x11_err)?;
+        Ok(X11Overlay { x11, depth, lsb_first, gc, panels: Vec::new() })
+    }
+
+    /// Upload a rendered panel into a server pixmap, in bands under the request limit
+    fn upload(&self, drawable: u32, panel: &Pixmap) -> Result<(), String> {
+        let width = panel.width() as usize;
+        let row_bytes = width * 4;
+        let max_request = self.x11.conn.maximum_request_bytes().saturating_sub(64);
+        let band_rows = (max_request / row_bytes).max(1);
+
+        // tiny-skia is premultiplied RGBA; X11 wants the pixel as 0xAARRGGBB in server order
+        let mut data = Vec::with_capacity(panel.data().len());
+        for rgba in panel.data().chunks_exact(4) {
+            let pixel = [rgba[3], rgba[0], rgba[1], rgba[2]];
+            if self.lsb_first {
+                data.extend(pixel.iter().rev());
+            } else {
+                data.extend(pixel);
+            }
+        }
+
+        for (band, bytes) in data.chunks(band_rows * row_bytes).enumerate() {
+            let rows = bytes.len() / row_bytes;
+            self.x11.conn.put_image(ImageFormat::Z_PIXMAP, drawable, self.gc, width as u16, rows as u16,
+                0, (band * band_rows) as i16, 0, self.depth, bytes).map_err(x11_err)?;
+        }
+        Ok(())
+    }
+}
+
+impl OverlaySurfaces for X11Overlay {
+    fn show(&mut self, render: &dyn Fn(u32, u32) -> Option<Pixmap>) -> Result<(), String> {
+        self.hide();
+        let conn = &self.x11.conn;
+
+        // Empty input region, shared by all panels
+        let region = conn.generate_id().map_err(x11_err)?;
+        conn.xfixes_create_region(region, &[]).map_err(x11_err)?;
+
+        for display in gather_displays(&self.x11) {
+            let area = &display.viewport;
+            let (width, height) = panel_size(area.width as u32, area.height as u32);
+            let panel = match render(width, height) {
+                Some(p) => p,
+                None => continue,
+            };
+            let x = area.x + (area.width - width as f64) / 2.0;
+            let y = area.y + area.height - height as f64;
+
+            let pixmap = conn.generate_id().map_err(x11_err)?;
+            conn.create_pixmap(self.depth, pixmap, self.x11.root, width as u16, height as u16).map_err(x11_err)?;
+            self.upload(pixmap, &panel)?;
+
+            let window = conn.generate_id().map_err(x11_err)?;
+            let aux = CreateWindowAux::new().override_redirect(1).background_pixmap(pixmap);
+            conn.create_window(self.depth, window, self.x11.root, x as i16, y as i16, width as u16, height as u16,
+                0, WindowClass::INPUT_OUTPUT, 0, &aux).map_err(x11_err)?;
+            conn.xfixes_set_window_shape_region(window, shape::SK::INPUT, 0, 0, region).map_err(x11_err)?;
+            conn.map_window(window).map_err(x11_err)?;
+
+            eprintln!("OVERLAY: X11 panel on display {} at ({:.0},{:.0}) {}x{}", display.info.index, x, y, width, height);
+            self.panels.push(Panel { window, pixmap, width: width as u16, height: height as u16 });
+        }
+
+        conn.xfixes_destroy_region(region).map_err(x11_err)?;
+        conn.flush().map_err(x11_err)
+    }
+
+    fn update(&mut self, render: &dyn Fn(u32, u32) -> Option<Pixmap>) -> Result<(), String> {
+        for panel in &self.panels {
+            if let Some(image) = render(panel.width as u32, panel.height as u32) {
+                self.upload(panel.pixmap, &image)?;
+                // Re-setting the background makes the server repaint from the new contents
+                let aux = ChangeWindowAttributesAux::new().background_pixmap(panel.pixmap);
+                self.x11.conn.change_window_attributes(panel.window, &aux).map_err(x11_err)?;
+                self.x11.conn.clear_area(false, panel.window, 0, 0, 0, 0).map_err(x11_err)?;
+            }
+        }
+        self.x11.conn.flush().map_err(x11_err)
+    }
+
+    fn hide(&mut self) {
+        for panel in self.panels.drain(..) {
+            let _ = self.x11.conn.destroy_window(panel.window);
+            let _ = self.x11.conn.free_pixmap(panel.pixmap);
+        }
+        let _ = self.x11.conn.flush();
+    }
+
+    fn pump(&mut self) {
+        // No event mask is selected; drain errors from asynchronous requests
+        while let Ok(Some(event)) = self.x11.conn.poll_for_event() {
+            if let x11rb::protocol::Event::Error(e) = event {
+                eprintln!("OVERLAY: X11 error {:?}", e.error_kind);
+            }
+        }
+    }
+}
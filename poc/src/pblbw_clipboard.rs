@@ -0,0 +1,408 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+#![cfg(target_os = "linux")]
+
+//! Wayland Clipboard Monitor (data-control)
+//! Feeds the clipboard history from Wayland selections and serves historic entries
+//!
+//! - Speaks ext-data-control-v1, or zwlr_data_control_manager_v1 on
+//!   compositors without it (wlroots before ext support, KDE)
+//! - Every new selection is an offer listing MIME types; the first text type
+//!   in TEXT_MIME_TYPES is received through a pipe (UTF-8 preferred)
+//! - "Paste historic" creates a data source holding the text and sets it as
+//!   the selection; the compositor cancels it when another client copies
+//!
+//! Our own sources also offer PANEBOARD_MIME, so their selection echo is not
+//! read back (the read would wait on this same event queue).
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::os::fd::{AsFd, AsRawFd, OwnedFd};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use wayland_client::backend::ObjectId;
+use wayland_client::globals::{registry_queue_init, GlobalListContents};
+use wayland_client::protocol::{wl_registry::WlRegistry, wl_seat::WlSeat};
+use wayland_client::{event_created_child, Connection, Dispatch, Proxy, QueueHandle};
+use wayland_protocols::ext::data_control::v1::client::{
+    ext_data_control_device_v1, ext_data_control_manager_v1, ext_data_control_offer_v1, ext_data_control_source_v1,
+};
+use wayland_protocols_wlr::data_control::v1::client::{
+    zwlr_data_control_device_v1, zwlr_data_control_manager_v1, zwlr_data_control_offer_v1,
+    zwlr_data_control_source_v1,
+};
+
+use crate::pbgcl_clipboard::record_clipboard_text;
+
+/// Text MIME types in order of preference
+const TEXT_MIME_TYPES: [&str; 5] = ["text/plain;charset=utf-8", "UTF8_STRING", "text/plain", "TEXT", "STRING"];
+
+/// Marks selections PaneBoard set itself
+const PANEBOARD_MIME: &str = "application/x-paneboard-history";
+
+/// Give up on an owner that does not finish writing its selection
+const RECEIVE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Running monitor, once started
+static WAYLAND_CLIPBOARD: OnceLock<WaylandClipboard> = OnceLock::new();
+
+fn wayland_err(e: impl std::fmt::Display) -> String {
+    format!("wayland_error({})", e)
+}
+
+// ============================================================================
+// SECTION 1: Protocol objects (ext or wlr)
+// ============================================================================
+
+enum Manager {
+    Ext(ext_data_control_manager_v1::ExtDataControlManagerV1),
+    Wlr(zwlr_data_control_manager_v1::ZwlrDataControlManagerV1),
+}
+
+enum Device {
+    Ext(ext_data_control_device_v1::ExtDataControlDeviceV1),
+    Wlr(zwlr_data_control_device_v1::ZwlrDataControlDeviceV1),
+}
+
+enum Offer {
+    Ext(ext_data_control_offer_v1::ExtDataControlOfferV1),
+    Wlr(zwlr_data_control_offer_v1::ZwlrDataControlOfferV1),
+}
+
+enum Source {
+    Ext(ext_data_control_source_v1::ExtDataControlSourceV1),
+    Wlr(zwlr_data_control_source_v1::ZwlrDataControlSourceV1),
+}
+
+impl Manager {
+    fn name(&self) -> &'static str {
+        match self {
+            Manager::Ext(_) => "ext-data-control-v1",
+            Manager::Wlr(_) => "wlr-data-control-unstable-v1",
+        }
+    }
+
+    fn get_data_device(&self, seat: &WlSeat, qh: &QueueHandle<ClipState>) -> Device {
+        match self {
+            Manager::Ext(m) => Device::Ext(m.get_data_device(seat, qh, ())),
+            Manager::Wlr(m) => Device::Wlr(m.get_data_device(seat, qh, ())),
+        }
+    }
+
+    /// The source's user data is the text it serves
+    fn create_data_source(&self, text: String, qh: &QueueHandle<ClipState>) -> Source {
+        match self {
+            Manager::Ext(m) => Source::Ext(m.create_data_source(qh, text)),
+            Manager::Wlr(m) => Source::Wlr(m.create_data_source(qh, text)),
+        }
+    }
+}
+
+impl Device {
+    fn set_selection(&self, source: &Source) {
+        match (self, source) {
+            (Device::Ext(d), Source::Ext(s)) => d.set_selection(Some(s)),
+            (Device::Wlr(d), Source::Wlr(s)) => d.set_selection(Some(s)),
+            _ => unreachable!("source and device come from the same manager"),
+        }
+    }
+}
+
+impl Offer {
+    fn id(&self) -> ObjectId {
+        match self {
+            Offer::Ext(o) => o.id(),
+            Offer::Wlr(o) => o.id(),
+        }
+    }
+
+    fn receive(&self, mime_type: &str, fd: std::os::fd::BorrowedFd<'_>) {
+        match self {
+            Offer::Ext(o) => o.receive(mime_type.to_string(), fd),
+            Offer::Wlr(o) => o.receive(mime_type.to_string(), fd),
+        }
+    }
+
+    fn destroy(&self) {
+        match self {
+            Offer::Ext(o) => o.destroy(),
+            Offer::Wlr(o) => o.destroy(),
+        }
+    }
+}
+
+impl Source {
+    fn offer(&self, mime_type: &str) {
+        match self {
+            Source::Ext(s) => s.offer(mime_type.to_string()),
+            Source::Wlr(s) => s.offer(mime_type.to_string()),
+        }
+    }
+}
+
+// ============================================================================
+// SECTION 2: Reading selections
+// ============================================================================
+
+/// Event-queue state (owned by the monitor thread)
+struct ClipState {
+    conn: Connection,
+    watch_primary: bool,
+    mime_types: HashMap<ObjectId, Vec<String>>,   // Per live offer
+    current: [Option<Offer>; 2],                   // CLIPBOARD, PRIMARY
+}
+
+impl ClipState {
+    fn offer_mime_type(&mut self, offer: ObjectId, mime_type: String) {
+        self.mime_types.entry(offer).or_default().push(mime_type);
+    }
+
+    /// New selection (None when cleared); the previous offer is destroyed
+    fn selection(&mut self, offer: Option<Offer>, primary: bool) {
+        let slot = usize::from(primary);
+        if let Some(old) = self.current[slot].take() {
+            self.mime_types.remove(&old.id());
+            old.destroy();
+        }
+        let offer = match offer {
+            Some(o) => o,
+            None => return,
+        };
+
+        if !primary || self.watch_primary {
+            self.read_offer(&offer, if primary { "PRIMARY" } else { "CLIPBOARD" });
+        }
+        self.current[slot] = Some(offer);
+    }
+
+    fn read_offer(&self, offer: &Offer, selection: &str) {
+        let offered = self.mime_types.get(&offer.id()).map(Vec::as_slice).unwrap_or_default();
+        if offered.iter().any(|m| m == PANEBOARD_MIME) {
+            return;   // Our own paste historic
+        }
+        let mime_type = match TEXT_MIME_TYPES.iter().find(|t| offered.iter().any(|m| m == *t)) {
+            Some(t) => *t,
+            None => return,   // Non-text content (ignore, as on macOS)
+        };
+
+        let (reader, writer) = match std::io::pipe() {
+            Ok(pipe) => pipe,
+            Err(e) => {
+                eprintln!("CLIP: FAILED to create pipe ({})", e);
+                return;
+            }
+        };
+        offer.receive(mime_type, writer.as_fd());
+        drop(writer);   // The owner's copy is the only writer left
+        if let Err(e) = self.conn.flush() {
+            eprintln!("CLIP: FAILED to request {} ({})", selection, e);
+            return;
+        }
+
+        let data = match read_with_timeout(reader, RECEIVE_TIMEOUT) {
+            Ok(data) => data,
+            Err(reason) => {
+                eprintln!("CLIP: {} read failed | mime={} | reason={}", selection, mime_type, reason);
+                return;
+            }
+        };
+        let text = if mime_type == "STRING" {
+            data.iter().map(|&b| b as char).collect()   // Latin-1
+        } else {
+            String::from_utf8_lossy(&data).into_owned()
+        };
+        if text.is_empty() {
+            return;
+        }
+        eprintln!("DEBUG: {} text received | mime={} | length={}", selection, mime_type, text.len());
+        record_clipboard_text(text);
+    }
+}
+
+/// Read a pipe to EOF, failing if the writer stalls past `timeout`
+fn read_with_timeout(mut reader: std::io::PipeReader, timeout: Duration) -> Result<Vec<u8>, String> {
+    let deadline = Instant::now() + timeout;
+    let mut data = Vec::new();
+    let mut chunk = [0u8; 8192];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let mut pfd = libc::pollfd { fd: reader.as_raw_fd(), events: libc::POLLIN, revents: 0 };
+        let ready = unsafe { libc::poll(&mut pfd, 1, remaining.as_millis() as i32) };
+        if ready == 0 {
+            return Err("timeout".to_string());
+        }
+        if ready < 0 {
+            let err = std::io::Error::last_os_error();
+            if err.kind() == std::io::ErrorKind::Interrupted {
+                continue;
+            }
+            return Err(format!("poll_failed({})", err));
+        }
+        match reader.read(&mut chunk) {
+            Ok(0) => return Ok(data),
+            Ok(n) => data.extend_from_slice(&chunk[..n]),
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(format!("read_failed({})", e)),
+        }
+    }
+}
+
+// ============================================================================
+// SECTION 3: Serving paste historic
+// ============================================================================
+
+/// Write a source's text to a requesting client (the fd closes on return)
+fn serve_text(text: &str, mime_type: &str, fd: OwnedFd) {
+    let data: Vec<u8> = if mime_type == "STRING" {
+        text.chars().map(|c| if (c as u32) < 0x100 { c as u8 } else { b'?' }).collect()
+    } else {
+        text.as_bytes().to_vec()
+    };
+    if let Err(e) = std::fs::File::from(fd).write_all(&data) {
+        eprintln!("CLIP: FAILED to serve selection | mime={} | reason={}", mime_type, e);
+    }
+}
+
+// ============================================================================
+// SECTION 4: Event dispatch
+// ============================================================================
+
+impl Dispatch<WlRegistry, GlobalListContents> for ClipState {
+    fn event(_: &mut Self, _: &WlRegistry, _: <WlRegistry as Proxy>::Event, _: &GlobalListContents, _: &Connection, _: &QueueHandle<Self>) {}
+}
+
+impl Dispatch<WlSeat, ()> for ClipState {
+    fn event(_: &mut Self, _: &WlSeat, _: <WlSeat as Proxy>::Event, _: &(), _: &Connection, _: &QueueHandle<Self>) {}
+}
+
+/// Identical handlers for the ext and wlr interface sets
+macro_rules! data_control_dispatch {
+    ($variant:ident, $manager:ident::$Manager:ident, $device:ident::$Device:ident,
+     $offer:ident::$Offer:ident, $source:ident::$Source:ident) => {
+        impl Dispatch<$manager::$Manager, ()> for ClipState {
+            fn event(_: &mut Self, _: &$manager::$Manager, _: $manager::Event, _: &(), _: &Connection, _: &QueueHandle<Self>) {}
+        }
+
+        impl Dispatch<$device::$Device, ()> for ClipState {
+            fn event(state: &mut Self, _: &$device::$Device, event: $device::Event, _: &(), _: &Connection, _: &QueueHandle<Self>) {
+                match event {
+                    $device::Event::DataOffer { id } => {
+                        state.mime_types.insert(id.id(), Vec::new());
+                    }
+                    $device::Event::Selection { id } => state.selection(id.map(Offer::$variant), false),
+                    $device::Event::PrimarySelection { id } => state.selection(id.map(Offer::$variant), true),
+                    $device::Event::Finished => eprintln!("CLIP: data-control device finished, monitoring stopped"),
+                    _ => {}
+                }
+            }
+
+            event_created_child!(ClipState, $device::$Device, [
+                $device::EVT_DATA_OFFER_OPCODE => ($offer::$Offer, ()),
+            ]);
+        }
+
+        impl Dispatch<$offer::$Offer, ()> for ClipState {
+            fn event(state: &mut Self, offer: &$offer::$Offer, event: $offer::Event, _: &(), _: &Connection, _: &QueueHandle<Self>) {
+                if let $offer::Event::Offer { mime_type } = event {
+                    state.offer_mime_type(offer.id(), mime_type);
+                }
+            }
+        }
+
+        impl Dispatch<$source::$Source, String> for ClipState {
+            fn event(_: &mut Self, source: &$source::$Source, event: $source::Event, text: &String, _: &Connection, _: &QueueHandle<Self>) {
+                match event {
+                    $source::Event::Send { mime_type, fd } => serve_text(text, &mime_type, fd),
+                    $source::Event::Cancelled => source.destroy(),
+                    _ => {}
+                }
+            }
+        }
+    };
+}
+
+data_control_dispatch!(Ext,
+    ext_data_control_manager_v1::ExtDataControlManagerV1,
+    ext_data_control_device_v1::ExtDataControlDeviceV1,
+    ext_data_control_offer_v1::ExtDataControlOfferV1,
+    ext_data_control_source_v1::ExtDataControlSourceV1);
+
+data_control_dispatch!(Wlr,
+    zwlr_data_control_manager_v1::ZwlrDataControlManagerV1,
+    zwlr_data_control_device_v1::ZwlrDataControlDeviceV1,
+    zwlr_data_control_offer_v1::ZwlrDataControlOfferV1,
+    zwlr_data_control_source_v1::ZwlrDataControlSourceV1);
+
+// ============================================================================
+// SECTION 5: Entry points
+// ============================================================================
+
+/// Handles shared with callers on other threads (proxies are thread-safe)
+struct WaylandClipboard {
+    conn: Connection,
+    qh: QueueHandle<ClipState>,
+    manager: Manager,
+    device: Device,
+}
+
+/// Connect to $WAYLAND_DISPLAY and start monitoring on a background thread (idempotent)
+pub fn start_wayland_clipboard(watch_primary: bool) -> Result<(), String> {
+    if WAYLAND_CLIPBOARD.get().is_some() {
+        return Ok(());
+    }
+
+    let conn = Connection::connect_to_env().map_err(|e| format!("wayland_connect_failed({})", e))?;
+    let (globals, mut queue) = registry_queue_init::<ClipState>(&conn).map_err(wayland_err)?;
+    let qh = queue.handle();
+
+    // Prefer the standardized protocol; wlr v2 adds primary selection
+    let manager = match globals.bind(&qh, 1..=1, ()) {
+        Ok(m) => Manager::Ext(m),
+        Err(_) => Manager::Wlr(globals.bind(&qh, 1..=2, ()).map_err(|_| "data_control_unavailable".to_string())?),
+    };
+    let seat: WlSeat = globals.bind(&qh, 1..=1, ()).map_err(|_| "no_seat".to_string())?;
+    let device = manager.get_data_device(&seat, &qh);
+    conn.flush().map_err(wayland_err)?;
+
+    let mut state = ClipState {
+        conn: conn.clone(),
+        watch_primary,
+        mime_types: HashMap::new(),
+        current: [None, None],
+    };
+    eprintln!("DEBUG: Clipboard monitoring started (Wayland {}{})", manager.name(), if watch_primary { ", with PRIMARY" } else { "" });
+    let _ = WAYLAND_CLIPBOARD.set(WaylandClipboard { conn, qh, manager, device });
+
+    std::thread::spawn(move || loop {
+        if let Err(e) = queue.blocking_dispatch(&mut state) {
+            eprintln!("CLIP: monitor stopped ({})", e);
+            return;
+        }
+    });
+    Ok(())
+}
+
+/// Set the Wayland selection to `text` (paste historic)
+/// Returns false if monitoring is not running or the request could not be sent
+pub fn set_wayland_clipboard_text(text: &str) -> bool {
+    let clipboard = match WAYLAND_CLIPBOARD.get() {
+        Some(c) => c,
+        None => return false,
+    };
+
+    let source = clipboard.manager.create_data_source(text.to_string(), &clipboard.qh);
+    for mime_type in TEXT_MIME_TYPES.iter().chain([PANEBOARD_MIME].iter()) {
+        source.offer(mime_type);
+    }
+    clipboard.device.set_selection(&source);
+    if let Err(e) = clipboard.conn.flush() {
+        eprintln!("CLIP: FAILED to set selection ({})", e);
+        return false;
+    }
+
+    // Our own selection is skipped by the monitor; record it here
+    record_clipboard_text(text.to_string());
+    true
+}
@@ -0,0 +1,385 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+#![cfg(target_os = "linux")]
+
+//! X11 Window Backend (EWMH)
+//! WindowBackend over an X11 connection, driving windows through the window manager
+//!
+//! - Active window and client list: _NET_ACTIVE_WINDOW, _NET_CLIENT_LIST
+//!   (input focus and the root's mapped children when no EWMH WM is running)
+//! - App identity: _NET_WM_PID, then the process name from /proc/<pid>/comm
+//!   (what <Linux process="..."/> matchers compare against)
+//! - Geometry: outer frame rects, i.e. client geometry grown by _NET_FRAME_EXTENTS,
+//!   the same frame-inclusive convention AX uses on macOS
+//! - Moves/resizes: _NET_MOVERESIZE_WINDOW when the WM advertises it,
+//!   ConfigureWindow otherwise; both with NorthWest gravity, so x/y name the
+//!   frame's outer corner
+//! - Raise: _NET_ACTIVE_WINDOW request, or map + raise + focus without a WM
+//!
+//! The WM applies requests asynchronously. Writes wait (bounded) until the new
+//! geometry is visible, so read-backs see the result the way AX read-backs do.
+
+use std::time::Duration;
+
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{
+    Atom, AtomEnum, ClientMessageEvent, ConfigureWindowAux, ConnectionExt as _, EventMask, InputFocus,
+    MapState, StackMode, Window,
+};
+use x11rb::rust_connection::RustConnection;
+use x11rb::CURRENT_TIME;
+
+use crate::pbgft_types::PixelRect;
+use crate::pbgw_window::{WindowBackend, WindowError, WindowInfo};
+
+/// Poll interval and bound while waiting for the WM to apply a geometry request
+const CONFIGURE_POLL_MS: u64 = 10;
+const CONFIGURE_MAX_POLLS: u32 = 10;
+
+/// _NET_MOVERESIZE_WINDOW flags (data.l[0]): gravity, present fields, source indication
+const GRAVITY_NORTH_WEST: u32 = 1;
+const MOVERESIZE_X: u32 = 1 << 8;
+const MOVERESIZE_Y: u32 = 1 << 9;
+const MOVERESIZE_WIDTH: u32 = 1 << 10;
+const MOVERESIZE_HEIGHT: u32 = 1 << 11;
+const MOVERESIZE_SOURCE_SHIFT: u32 = 12;
+
+/// EWMH source indication for client messages: pagers and tools get direct control
+const SOURCE_PAGER: u32 = 2;
+
+/// _NET_WM_STATE client message action
+const NET_WM_STATE_REMOVE: u32 = 0;
+
+x11rb::atom_manager! {
+    pub Atoms: AtomsCookie {
+        _NET_SUPPORTED,
+        _NET_ACTIVE_WINDOW,
+        _NET_CLIENT_LIST,
+        _NET_MOVERESIZE_WINDOW,
+        _NET_FRAME_EXTENTS,
+        _NET_WM_PID,
+        _NET_WM_NAME,
+        _NET_WM_STATE,
+        _NET_WM_STATE_HIDDEN,
+        _NET_WM_STATE_FULLSCREEN,
+        _NET_WM_STATE_MAXIMIZED_VERT,
+        _NET_WM_STATE_MAXIMIZED_HORZ,
+        _NET_WORKAREA,
+        _NET_CURRENT_DESKTOP,
+        _NET_WM_STRUT,
+        _NET_WM_STRUT_PARTIAL,
+        UTF8_STRING,
+        EDID,
+    }
+}
+
+// Frontmost app information (active window's owner)
+#[derive(Clone, Debug)]
+pub struct FrontmostInfo {
+    pub pid: u32,     // 0 when the window does not set _NET_WM_PID
+    pub app_id: String,
+}
+
+// ============================================================================
+// SECTION 1: Connection and EWMH properties
+// ============================================================================
+
+/// One X11 connection plus the EWMH features the running WM advertises
+pub struct X11Backend {
+    pub conn: RustConnection,
+    pub root: Window,
+    pub atoms: Atoms,
+    supported: Vec<Atom>,
+}
+
+fn x11_err(e: impl std::fmt::Display) -> String {
+    format!("x11_error({})", e)
+}
+
+impl X11Backend {
+    /// Connect to $DISPLAY and read the WM's _NET_SUPPORTED list
+    pub fn connect() -> Result<Self, String> {
+        let (conn, screen_num) = x11rb::connect(None).map_err(|e| format!("x11_connect_failed({})", e))?;
+        let root = conn.setup().roots[screen_num].root;
+        let atoms = Atoms::new(&conn).map_err(x11_err)?.reply().map_err(x11_err)?;
+
+        let mut backend = X11Backend { conn, root, atoms, supported: Vec::new() };
+        backend.supported = backend.prop32(root, atoms._NET_SUPPORTED, AtomEnum::ATOM.into());
+        if backend.supported.is_empty() {
+            eprintln!("X11: no EWMH window manager detected, using core requests");
+        } else {
+            eprintln!("X11: EWMH window manager detected ({} hints supported)", backend.supported.len());
+        }
+        Ok(backend)
+    }
+
+    fn supports(&self, atom: Atom) -> bool {
+        self.supported.contains(&atom)
+    }
+
+    /// 32-bit property values (empty if unset or on error)
+    pub fn prop32(&self, win: Window, prop: Atom, ty: Atom) -> Vec<u32> {
+        self.conn.get_property(false, win, prop, ty, 0, u32::MAX)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .and_then(|reply| reply.value32().map(|values| values.collect()))
+            .unwrap_or_default()
+    }
+
+    fn send_root_message(&self, win: Window, ty: Atom, data: [u32; 5]) -> Result<(), WindowError> {
+        let event = ClientMessageEvent::new(32, win, ty, data);
+        let mask = EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY;
+        self.conn.send_event(false, self.root, mask, event).map_err(|_| WindowError::Platform(-1))?;
+        self.conn.flush().map_err(|_| WindowError::Platform(-1))
+    }
+
+    /// Top-level window currently active
+    pub fn active_window(&self) -> Option<Window> {
+        if self.supports(self.atoms._NET_ACTIVE_WINDOW) {
+            return self.prop32(self.root, self.atoms._NET_ACTIVE_WINDOW, AtomEnum::WINDOW.into())
+                .first().copied()
+                .filter(|&w| w != x11rb::NONE);
+        }
+
+        // No EWMH WM: walk up from the input focus to the root's child
+        let focus = self.conn.get_input_focus().ok()?.reply().ok()?.focus;
+        if focus == x11rb::NONE || focus == self.root || focus == u32::from(InputFocus::POINTER_ROOT) {
+            return None;
+        }
+        self.top_level(focus)
+    }
+
+    fn top_level(&self, mut win: Window) -> Option<Window> {
+        loop {
+            let tree = self.conn.query_tree(win).ok()?.reply().ok()?;
+            if tree.parent == self.root || tree.parent == x11rb::NONE {
+                return Some(win);
+            }
+            win = tree.parent;
+        }
+    }
+
+    /// Managed top-level windows in stacking-independent (mapping) order
+    pub fn client_list(&self) -> Vec<Window> {
+        if self.supports(self.atoms._NET_CLIENT_LIST) {
+            return self.prop32(self.root, self.atoms._NET_CLIENT_LIST, AtomEnum::WINDOW.into());
+        }
+
+        // No EWMH WM: viewable, non-override-redirect children of the root
+        let children = match self.conn.query_tree(self.root).ok().and_then(|c| c.reply().ok()) {
+            Some(tree) => tree.children,
+            None => return Vec::new(),
+        };
+        children.into_iter()
+            .filter(|&w| {
+                self.conn.get_window_attributes(w).ok()
+                    .and_then(|c| c.reply().ok())
+                    .is_some_and(|a| a.map_state == MapState::VIEWABLE && !a.override_redirect)
+            })
+            .collect()
+    }
+
+    pub fn window_pid(&self, win: Window) -> u32 {
+        self.prop32(win, self.atoms._NET_WM_PID, AtomEnum::CARDINAL.into()).first().copied().unwrap_or(0)
+    }
+
+    /// App identifier of the active window (process name, matched by <Linux process="..."/>)
+    pub fn frontmost(&self) -> Option<FrontmostInfo> {
+        let win = self.active_window()?;
+        let pid = self.window_pid(win);
+        Some(FrontmostInfo { pid, app_id: process_name(pid) })
+    }
+
+    fn title(&self, win: Window) -> Option<String> {
+        let reply = self.conn.get_property(false, win, self.atoms._NET_WM_NAME, self.atoms.UTF8_STRING, 0, 1024)
+            .ok()?.reply().ok()?;
+        let title = String::from_utf8_lossy(&reply.value).into_owned();
+        if title.is_empty() { None } else { Some(title) }
+    }
+
+    fn wm_state(&self, win: Window) -> Vec<Atom> {
+        self.prop32(win, self.atoms._NET_WM_STATE, AtomEnum::ATOM.into())
+    }
+
+    /// Decoration sizes (left, right, top, bottom) the WM adds around the client
+    fn frame_extents(&self, win: Window) -> (f64, f64, f64, f64) {
+        match self.prop32(win, self.atoms._NET_FRAME_EXTENTS, AtomEnum::CARDINAL.into())[..] {
+            [l, r, t, b, ..] => (l as f64, r as f64, t as f64, b as f64),
+            _ => (0.0, 0.0, 0.0, 0.0),
+        }
+    }
+
+    /// Work area of the current desktop (root geometry without an EWMH WM)
+    pub fn work_area(&self) -> PixelRect {
+        let desktop = self.prop32(self.root, self.atoms._NET_CURRENT_DESKTOP, AtomEnum::CARDINAL.into())
+            .first().copied().unwrap_or(0) as usize;
+        let areas = self.prop32(self.root, self.atoms._NET_WORKAREA, AtomEnum::CARDINAL.into());
+        if let Some(area) = areas.chunks_exact(4).nth(desktop) {
+            return PixelRect { x: area[0] as f64, y: area[1] as f64, width: area[2] as f64, height: area[3] as f64 };
+        }
+        self.root_rect()
+    }
+
+    pub fn root_rect(&self) -> PixelRect {
+        let screen = self.conn.setup().roots.iter().find(|s| s.root == self.root);
+        let (w, h) = screen.map_or((0, 0), |s| (s.width_in_pixels, s.height_in_pixels));
+        PixelRect { x: 0.0, y: 0.0, width: w as f64, height: h as f64 }
+    }
+
+    /// Leave maximized state so the WM honors explicit geometry
+    fn unmaximize(&self, win: Window) {
+        let state = self.wm_state(win);
+        let vert = self.atoms._NET_WM_STATE_MAXIMIZED_VERT;
+        let horz = self.atoms._NET_WM_STATE_MAXIMIZED_HORZ;
+        if state.contains(&vert) || state.contains(&horz) {
+            eprintln!("DEBUG: X11 window 0x{:x} is maximized, clearing state before move", win);
+            let _ = self.send_root_message(win, self.atoms._NET_WM_STATE, [NET_WM_STATE_REMOVE, vert, horz, SOURCE_PAGER, 0]);
+        }
+    }
+
+    /// Send a geometry request (outer-frame x/y, client width/height)
+    fn request_geometry(&self, win: Window, x: Option<f64>, y: Option<f64>, size: Option<(f64, f64)>) -> Result<(), WindowError> {
+        if self.wm_state(win).contains(&self.atoms._NET_WM_STATE_FULLSCREEN) {
+            return Err(WindowError::Constrained);
+        }
+        self.unmaximize(win);
+
+        if self.supports(self.atoms._NET_MOVERESIZE_WINDOW) {
+            let mut flags = GRAVITY_NORTH_WEST | (SOURCE_PAGER << MOVERESIZE_SOURCE_SHIFT);
+            let mut data = [0u32; 5];
+            if let Some(x) = x { flags |= MOVERESIZE_X; data[1] = x.round() as i32 as u32; }
+            if let Some(y) = y { flags |= MOVERESIZE_Y; data[2] = y.round() as i32 as u32; }
+            if let Some((w, h)) = size {
+                flags |= MOVERESIZE_WIDTH | MOVERESIZE_HEIGHT;
+                data[3] = w.round().max(1.0) as u32;
+                data[4] = h.round().max(1.0) as u32;
+            }
+            data[0] = flags;
+            return self.send_root_message(win, self.atoms._NET_MOVERESIZE_WINDOW, data);
+        }
+
+        let mut aux = ConfigureWindowAux::new();
+        if let Some(x) = x { aux = aux.x(x.round() as i32); }
+        if let Some(y) = y { aux = aux.y(y.round() as i32); }
+        if let Some((w, h)) = size {
+            aux = aux.width(w.round().max(1.0) as u32).height(h.round().max(1.0) as u32);
+        }
+        self.conn.configure_window(win, &aux).map_err(|_| WindowError::Platform(-1))?;
+        self.conn.flush().map_err(|_| WindowError::Platform(-1))
+    }
+
+    /// Wait until the window's outer rect satisfies `done` (bounded; apps may refuse)
+    fn wait_for_geometry(&self, win: Window, done: impl Fn(&PixelRect) -> bool) {
+        for _ in 0..CONFIGURE_MAX_POLLS {
+            if self.outer_rect(win).is_some_and(|r| done(&r)) {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(CONFIGURE_POLL_MS));
+        }
+        eprintln!("DEBUG: X11 window 0x{:x} geometry unchanged after {}ms", win, CONFIGURE_POLL_MS * CONFIGURE_MAX_POLLS as u64);
+    }
+
+    /// Outer frame rect in root coordinates
+    fn outer_rect(&self, win: Window) -> Option<PixelRect> {
+        let geometry = self.conn.get_geometry(win).ok()?.reply().ok()?;
+        let origin = self.conn.translate_coordinates(win, self.root, 0, 0).ok()?.reply().ok()?;
+        let (l, r, t, b) = self.frame_extents(win);
+        Some(PixelRect {
+            x: origin.dst_x as f64 - l,
+            y: origin.dst_y as f64 - t,
+            width: geometry.width as f64 + l + r,
+            height: geometry.height as f64 + t + b,
+        })
+    }
+}
+
+/// Process name for a PID (/proc/<pid>/comm), "unknown" if unavailable
+pub fn process_name(pid: u32) -> String {
+    if pid == 0 {
+        return "unknown".to_string();
+    }
+    std::fs::read_to_string(format!("/proc/{}/comm", pid))
+        .map(|name| name.trim().to_string())
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+// ============================================================================
+// SECTION 2: WindowBackend
+// ============================================================================
+
+impl WindowBackend for X11Backend {
+    type Window = Window;
+
+    /// PID 0 accepts any active window (owners without _NET_WM_PID)
+    fn focused_window(&self, pid: u32) -> Result<Window, String> {
+        let win = self.active_window().ok_or_else(|| "no_focused_window".to_string())?;
+        if pid != 0 && self.window_pid(win) != pid {
+            return Err("no_focused_window".to_string());
+        }
+        eprintln!("DEBUG: Focused window: \"{}\"", self.title(win).unwrap_or_else(|| "<no window title>".to_string()));
+        Ok(win)
+    }
+
+    fn window_by_id(&self, pid: u32, window_id: u32) -> Option<Window> {
+        self.client_list().into_iter()
+            .find(|&w| w == window_id && (pid == 0 || self.window_pid(w) == pid))
+    }
+
+    fn enumerate_windows(&self, pid: u32) -> Vec<WindowInfo> {
+        let hidden = self.atoms._NET_WM_STATE_HIDDEN;
+        self.client_list().into_iter()
+            .filter(|&w| pid == 0 || self.window_pid(w) == pid)
+            .map(|w| WindowInfo {
+                window_id: w,
+                title: self.title(w).unwrap_or_else(|| format!("<win:{}>", w)),
+                rect: self.outer_rect(w),
+                minimized: self.wm_state(w).contains(&hidden),
+            })
+            .collect()
+    }
+
+    fn window_id(&self, win: &Window) -> Option<u32> {
+        Some(*win)
+    }
+
+    fn get_rect(&self, win: &Window) -> Option<PixelRect> {
+        self.outer_rect(*win)
+    }
+
+    fn set_position(&self, win: &Window, x: f64, y: f64) -> Result<(), WindowError> {
+        self.request_geometry(*win, Some(x), Some(y), None)?;
+        self.wait_for_geometry(*win, |r| (r.x - x).abs() < 1.0 && (r.y - y).abs() < 1.0);
+        Ok(())
+    }
+
+    fn set_size(&self, win: &Window, width: f64, height: f64) -> Result<(), WindowError> {
+        // Requests carry client size; frame extents stay the WM's
+        let before = self.outer_rect(*win);
+        let (l, r, t, b) = self.frame_extents(*win);
+        self.request_geometry(*win, None, None, Some((width - l - r, height - t - b)))?;
+        self.wait_for_geometry(*win, |now| {
+            let reached = (now.width - width).abs() < 1.0 && (now.height - height).abs() < 1.0;
+            let changed = before.as_ref().is_some_and(|b| (now.width - b.width).abs() >= 1.0 || (now.height - b.height).abs() >= 1.0);
+            reached || changed
+        });
+        Ok(())
+    }
+
+    fn raise(&self, pid: u32, window_id: u32) -> bool {
+        let win = match self.window_by_id(pid, window_id) {
+            Some(w) => w,
+            None => return false,
+        };
+
+        if self.supports(self.atoms._NET_ACTIVE_WINDOW) {
+            // The WM de-iconifies, raises and focuses on request
+            return self.send_root_message(win, self.atoms._NET_ACTIVE_WINDOW, [SOURCE_PAGER, CURRENT_TIME, 0, 0, 0]).is_ok();
+        }
+
+        let raised = self.conn.map_window(win).is_ok()
+            && self.conn.configure_window(win, &ConfigureWindowAux::new().stack_mode(StackMode::ABOVE)).is_ok()
+            && self.conn.set_input_focus(InputFocus::PARENT, win, CURRENT_TIME).is_ok();
+        raised && self.conn.flush().is_ok()
+    }
+}
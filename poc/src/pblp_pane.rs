@@ -0,0 +1,483 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+#![cfg(target_os = "linux")]
+
+//! Linux Pane Actions
+//! Runs Form bindings against the active window (X11, or i3/sway over IPC)
+//!
+//! LayoutAction, DisplayMove, FocusMove, NudgeAction/ResizeAction and Macros
+//! go through the generic window layer (pbgw_window) with X11Backend, or with
+//! I3Backend when $SWAYSOCK/$I3SOCK is set, so pane cycling, fallback policies
+//! and size preservation behave exactly as on macOS. FocusMove ranks ties by
+//! window-system order (no MRU stack on Linux). SwapAction bindings and
+//! FocusMru/SwapAction Macro steps are reported as unsupported on Linux for now.
+//!
+//! `paneboard-poc action <key>...` runs the keys as one chord (the pane cycle
+//! advances from key to key), then exits. Bind it in the window manager's
+//! keyboard configuration.
+//!
+//! `paneboard-poc capture` stays resident instead. Chords come from evdev
+//! (pblbe_evdev) or, without input-device access, from XGrabKey hotkeys
+//! (pblbg_grabkey), and run against the session. With evdev and neither an
+//! i3/sway socket nor $DISPLAY (console, other Wayland compositors) chords
+//! are logged only.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::pbgam_appmin::flush_app_min_sizes;
+use crate::pbgfr_resolve::Form;
+use crate::pbgft_types::{DisplayPlacement, MacroFailurePolicy, MacroSpec, MacroStep, PixelRect};
+use crate::pbgw_window::{
+    display_index_for_rect, focus_in_direction, move_window_to_display, nudge_focused_window, resize_focused_window,
+    tile_fixed_pane, tile_next_pane, TileDisplay, WindowBackend,
+};
+use crate::pblbe_evdev::{run_capture, ChordHandler};
+use crate::pblbg_grabkey::run_grab_capture;
+use crate::pblbc_clipboard::start_clipboard_monitoring;
+use crate::pblbd_display::{gather_displays, LinuxDisplay};
+use crate::pblbi_i3ipc::{ipc_socket_path, I3Backend};
+use crate::pblbx_x11::{FrontmostInfo, X11Backend};
+
+/// Poll interval and bound while a Macro waits for a focus step to land
+const FOCUS_POLL_MS: u64 = 10;
+const FOCUS_MAX_POLLS: u32 = 20;
+
+/// What a session needs beyond WindowBackend: the active app and the displays
+pub trait SessionBackend: WindowBackend {
+    /// Log prefix for messages about this backend
+    const LABEL: &'static str;
+
+    fn frontmost(&self) -> Option<FrontmostInfo>;
+
+    /// Displays in index order (primary first)
+    fn displays(&self) -> Vec<LinuxDisplay>;
+}
+
+impl SessionBackend for X11Backend {
+    const LABEL: &'static str = "X11";
+
+    fn frontmost(&self) -> Option<FrontmostInfo> {
+        X11Backend::frontmost(self)
+    }
+
+    fn displays(&self) -> Vec<LinuxDisplay> {
+        gather_displays(self)
+    }
+}
+
+impl SessionBackend for I3Backend {
+    const LABEL: &'static str = "I3";
+
+    fn frontmost(&self) -> Option<FrontmostInfo> {
+        I3Backend::frontmost(self)
+    }
+
+    fn displays(&self) -> Vec<LinuxDisplay> {
+        self.gather_displays()
+    }
+}
+
+/// Window backend, displays and Form for the lifetime of one chord
+pub struct PaneSession<B> {
+    backend: B,
+    displays: Vec<LinuxDisplay>,
+    placements: Vec<DisplayPlacement>,   // DisplayMove view of `displays`, built once
+    form: Mutex<Form>,
+}
+
+impl<B: SessionBackend> PaneSession<B> {
+    pub fn new(backend: B) -> Self {
+        let displays = backend.displays();
+        let infos: Vec<_> = displays.iter().map(|d| d.info.clone()).collect();
+        let form = Form::load_from_file(&infos);
+        let placements = displays.iter().map(|d| d.as_placement()).collect();
+
+        // Auto-tiling needs window lifecycle events, which no Linux backend feeds yet
+        for display in &displays {
+            if let Some(config) = form.auto_tile_for_display(&display.info.as_props()) {
+                eprintln!("AUTOTILE: display={} mode={:?} ratio={}/{} | not supported on Linux yet, ignored",
+                    display.info.index, config.mode, config.ratio.num, config.ratio.den);
+            }
+        }
+
+        PaneSession { backend, displays, placements, form: Mutex::new(form) }
+    }
+
+    /// Handle any key configured in Form XML
+    /// Returns true if the key was handled, false if no binding exists or it failed
+    pub fn handle_configured_key(&self, key: &str) -> bool {
+        let frontmost = match self.backend.frontmost() {
+            Some(f) => f,
+            None => {
+                println!("TILE: {} | FAILED reason=no_active_window", key);
+                return false;
+            }
+        };
+
+        // Same binding order as macOS: Macro, LayoutAction, DisplayMove, FocusMove, SwapAction, Nudge, Resize
+        let form = self.form.lock().unwrap();
+        let macro_spec = form.macro_spec(key);
+        let has_layout = form.has_layout_action(key);
+        let has_display_move = form.has_display_move(key);
+        let focus_direction = form.focus_move_direction(key);
+        let swap_target = form.swap_action_target(key);
+        let nudge = form.nudge_action(key);
+        let resize = form.resize_action(key);
+        drop(form);
+
+        if let Some(spec) = macro_spec {
+            self.run_macro(key, &spec)
+        } else if has_layout {
+            self.tile(key, &frontmost)
+        } else if has_display_move {
+            move_window_to_display(&self.backend, &self.form, key, frontmost.pid, &frontmost.app_id, &self.placements)
+        } else if let Some(direction) = focus_direction {
+            focus_in_direction(&self.backend, frontmost.pid, direction)
+        } else if let Some(target) = swap_target {
+            eprintln!("{}: key={} is bound to a SwapAction (target={}), not supported on Linux yet", B::LABEL, key, target.as_str());
+            false
+        } else if let Some(spec) = nudge {
+            nudge_focused_window(&self.backend, frontmost.pid, spec, |r| self.viewport_for(r))
+        } else if let Some(spec) = resize {
+            resize_focused_window(&self.backend, frontmost.pid, spec, |r| self.viewport_for(r))
+        } else {
+            eprintln!("{}: key={} has no binding", B::LABEL, key);
+            false
+        }
+    }
+
+    /// Tile the active window into the next pane of a LayoutAction cycle
+    fn tile(&self, key: &str, frontmost: &FrontmostInfo) -> bool {
+        let win = match self.backend.focused_window(frontmost.pid) {
+            Ok(w) => w,
+            Err(reason) => {
+                println!("TILE: {} | FAILED reason={}", key, reason);
+                return false;
+            }
+        };
+
+        let (display_index, display) = self.display_of(&win);
+        let display = match display {
+            Some(d) => d,
+            None => {
+                println!("TILE: {} | FAILED reason=no_display_info", key);
+                return false;
+            }
+        };
+
+        match tile_next_pane(&self.backend, &self.form, &win, key, &display, &frontmost.app_id) {
+            Ok((pane_idx, None)) => {
+                println!("TILE: {} | SUCCESS | key={} pane={} app=\"{}\"", key, key, pane_idx, frontmost.app_id);
+                true
+            }
+            Ok((pane_idx, Some(fallback))) => {
+                println!("TILE: {} | SUCCESS fallback={} | key={} pane={} app=\"{}\"", key, fallback, key, pane_idx, frontmost.app_id);
+                true
+            }
+            Err(reason) => {
+                if reason == "no_pane_for_key" {
+                    eprintln!("LAYOUT: no panes available for key={} on display={}", key, display_index);
+                }
+                println!("TILE: {} | FAILED reason={}", key, reason);
+                false
+            }
+        }
+    }
+
+    /// Tile the active window into a fixed pane of a binding's cycle (Macro LayoutAction step)
+    /// No session advance; pane is a 0-based cycle index
+    fn tile_to_pane(&self, binding: &str, pane: usize, frontmost: &FrontmostInfo) -> Result<Option<String>, String> {
+        let win = self.backend.focused_window(frontmost.pid)?;
+        let display = self.display_of(&win).1.ok_or("no_display_info")?;
+        tile_fixed_pane(&self.backend, &self.form, &win, binding, pane, &display, &frontmost.app_id)
+    }
+
+    /// Index and tiling view of the display showing a window (display 0 if its rect is unknown)
+    fn display_of(&self, win: &B::Window) -> (usize, Option<TileDisplay>) {
+        let index = self.backend.get_rect(win)
+            .map_or(0, |rect| display_index_for_rect(&self.placements, &rect));
+        (index, self.displays.get(index).map(|d| d.as_tile_display()))
+    }
+
+    /// Symmetric viewport of the display containing a rect (Nudge/Resize clamping)
+    fn viewport_for(&self, rect: &PixelRect) -> Option<PixelRect> {
+        self.displays.get(display_index_for_rect(&self.placements, rect)).map(|d| d.viewport.clone())
+    }
+
+    /// Run a Macro: steps in order, each against the window active when it starts
+    /// Steps run synchronously; the backends' geometry writes already wait (bounded)
+    /// for the window manager, and a focus step waits for the active window to change.
+    /// Returns true if every step succeeded
+    fn run_macro(&self, key: &str, spec: &MacroSpec) -> bool {
+        let total = spec.steps.len();
+        let mut failed: Vec<usize> = Vec::new();
+
+        for (i, step) in spec.steps.iter().enumerate() {
+            let n = i + 1;
+            let focused_before = self.active_window_id();
+            let result = match self.backend.frontmost() {
+                Some(frontmost) => self.run_step(step, &frontmost),
+                None => Err("no_active_window".to_string()),
+            };
+
+            match result {
+                Ok(None) => eprintln!("MACRO: key={} step={}/{} {} | SUCCESS", key, n, total, step.name()),
+                Ok(Some(fallback)) => eprintln!("MACRO: key={} step={}/{} {} | SUCCESS fallback={}",
+                    key, n, total, step.name(), fallback),
+                Err(reason) => {
+                    println!("MACRO: key={} step={}/{} {} | FAILED reason={}", key, n, total, step.name(), reason);
+                    failed.push(n);
+                    if spec.on_failure == MacroFailurePolicy::Stop {
+                        println!("MACRO: key={} | FAILED stopped_at={}/{} on_failure={}",
+                            key, n, total, spec.on_failure.as_str());
+                        return false;
+                    }
+                    continue;
+                }
+            }
+
+            if matches!(step, MacroStep::FocusMove(_)) {
+                self.wait_for_focus_change(focused_before);
+            }
+        }
+
+        if failed.is_empty() {
+            println!("MACRO: key={} | SUCCESS steps={}", key, total);
+            true
+        } else {
+            let list: Vec<String> = failed.iter().map(|n| n.to_string()).collect();
+            println!("MACRO: key={} | PARTIAL failed_steps={} of {} on_failure={}",
+                key, list.join(","), total, spec.on_failure.as_str());
+            false
+        }
+    }
+
+    /// Execute one Macro step against the active window
+    /// Ok carries the TileFallback applied by a LayoutAction step, if any
+    fn run_step(&self, step: &MacroStep, frontmost: &FrontmostInfo) -> Result<Option<String>, String> {
+        let ok = match step {
+            MacroStep::LayoutAction { binding, pane } => return self.tile_to_pane(binding, *pane, frontmost),
+            MacroStep::DisplayMove { binding } => {
+                move_window_to_display(&self.backend, &self.form, binding, frontmost.pid, &frontmost.app_id, &self.placements)
+            }
+            MacroStep::FocusMove(direction) => focus_in_direction(&self.backend, frontmost.pid, *direction),
+            MacroStep::FocusMru => return Err("not_supported_on_linux".to_string()),
+            MacroStep::SwapAction(target) => return Err(format!("not_supported_on_linux(target={})", target.as_str())),
+            MacroStep::Nudge(spec) => nudge_focused_window(&self.backend, frontmost.pid, *spec, |r| self.viewport_for(r)),
+            MacroStep::Resize(spec) => resize_focused_window(&self.backend, frontmost.pid, *spec, |r| self.viewport_for(r)),
+        };
+
+        // Details were already logged by the action itself
+        if ok { Ok(None) } else { Err("action_failed".to_string()) }
+    }
+
+    fn active_window_id(&self) -> Option<u32> {
+        self.backend.focused_window(0).ok().and_then(|w| self.backend.window_id(&w))
+    }
+
+    /// Wait (bounded) for the active window to differ from `before`
+    fn wait_for_focus_change(&self, before: Option<u32>) {
+        for _ in 0..FOCUS_MAX_POLLS {
+            if self.active_window_id() != before {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(FOCUS_POLL_MS));
+        }
+        eprintln!("MACRO: focus change not observed after {}ms, continuing", FOCUS_POLL_MS * FOCUS_MAX_POLLS as u64);
+    }
+
+    /// Reset layout and display move sessions (chord release)
+    pub fn reset_sessions(&self) {
+        let mut form = self.form.lock().unwrap();
+        form.reset_layout_session();
+        form.reset_display_move_session();
+    }
+}
+
+/// Run `action <key>...`: each key once, in order, as one chord
+/// Exit code: 0 if every key was handled, 1 otherwise
+pub fn run_action_cli(keys: &[String]) -> i32 {
+    if keys.is_empty() {
+        eprintln!("Usage: paneboard-poc action <key>...");
+        return 2;
+    }
+
+    match ipc_socket_path() {
+        Some(socket) => match I3Backend::connect(&socket) {
+            Ok(i3) => run_keys(&PaneSession::new(i3), keys),
+            Err(reason) => {
+                eprintln!("I3: FAILED reason={}", reason);
+                1
+            }
+        },
+        None => match X11Backend::connect() {
+            Ok(x11) => run_keys(&PaneSession::new(x11), keys),
+            Err(reason) => {
+                eprintln!("X11: FAILED reason={}", reason);
+                1
+            }
+        },
+    }
+}
+
+fn run_keys<B: SessionBackend>(session: &PaneSession<B>, keys: &[String]) -> i32 {
+    let mut all_handled = true;
+    for key in keys {
+        all_handled &= session.handle_configured_key(key);
+    }
+    session.reset_sessions();
+    flush_app_min_sizes();
+
+    if all_handled { 0 } else { 1 }
+}
+
+impl<B: SessionBackend> ChordHandler for PaneSession<B> {
+    fn chord_key(&mut self, key: &'static str, _is_repeat: bool) {
+        self.handle_configured_key(key);
+    }
+
+    fn accepts_autorepeat(&self, key: &str) -> bool {
+        self.form.lock().unwrap().accepts_autorepeat(key)
+    }
+
+    fn chord_released(&mut self) {
+        self.reset_sessions();
+    }
+}
+
+/// Chord handler without a window system: log the keys only
+struct ChordLogger;
+
+impl ChordHandler for ChordLogger {
+    fn chord_key(&mut self, key: &'static str, is_repeat: bool) {
+        eprintln!("CHORD: key={}{} (no window backend)", key, if is_repeat { " repeat" } else { "" });
+    }
+
+    fn accepts_autorepeat(&self, _key: &str) -> bool {
+        false
+    }
+
+    fn chord_released(&mut self) {
+        eprintln!("CHORD: released");
+    }
+}
+
+/// Input backend for `capture`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CaptureBackend {
+    Evdev,   // Grab keyboards below the display server (needs /dev/input + /dev/uinput)
+    X11,     // XGrabKey the Form's bound keys (no device access needed)
+}
+
+impl CaptureBackend {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "evdev" => Some(CaptureBackend::Evdev),
+            "x11" => Some(CaptureBackend::X11),
+            _ => None,
+        }
+    }
+
+    /// evdev when uinput is writable, else X11 hotkeys when a display is available
+    fn detect() -> Self {
+        let uinput_writable = std::fs::OpenOptions::new().write(true).open("/dev/uinput").is_ok();
+        if !uinput_writable && std::env::var_os("DISPLAY").is_some() {
+            CaptureBackend::X11
+        } else {
+            CaptureBackend::Evdev
+        }
+    }
+}
+
+/// Run `capture [--backend evdev|x11] [--timeout SECONDS]`: resident chord capture
+/// Exit code: 0 on timeout, 1 if capture could not start or failed, 2 on usage errors
+pub fn run_capture_cli(args: &[String]) -> i32 {
+    const USAGE: &str = "Usage: paneboard-poc capture [--backend evdev|x11] [--timeout SECONDS]";
+    let mut backend = None;
+    let mut timeout = None;
+    let mut rest = args.iter();
+    while let Some(flag) = rest.next() {
+        let value = match rest.next() {
+            Some(v) => v,
+            None => {
+                eprintln!("{}", USAGE);
+                return 2;
+            }
+        };
+        match flag.as_str() {
+            "--backend" => match CaptureBackend::parse(value) {
+                Some(b) => backend = Some(b),
+                None => {
+                    eprintln!("ERROR: --backend must be evdev or x11, got: '{}'", value);
+                    return 2;
+                }
+            },
+            "--timeout" => match value.parse::<f64>() {
+                Ok(secs) if secs > 0.0 => timeout = Some(Duration::from_secs_f64(secs)),
+                _ => {
+                    eprintln!("ERROR: --timeout value must be a positive number, got: '{}'", value);
+                    return 2;
+                }
+            },
+            _ => {
+                eprintln!("{}", USAGE);
+                return 2;
+            }
+        }
+    }
+
+    let backend = backend.unwrap_or_else(CaptureBackend::detect);
+    eprintln!("INPUT: capture backend={:?}", backend);
+
+    // Clipboard history runs alongside capture, as on macOS
+    if std::env::var_os("DISPLAY").is_some() || std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        if let Err(reason) = start_clipboard_monitoring(false) {
+            eprintln!("CLIP: FAILED reason={}", reason);
+        }
+    }
+
+    let result = match ipc_socket_path() {
+        Some(socket) => match I3Backend::connect(&socket) {
+            Ok(i3) => capture_with(PaneSession::new(i3), backend, timeout),
+            Err(reason) if backend == CaptureBackend::Evdev => {
+                eprintln!("I3: FAILED reason={}, chords are logged only", reason);
+                run_capture(&mut ChordLogger, timeout)
+            }
+            Err(reason) => Err(reason),
+        },
+        None if backend == CaptureBackend::X11 || std::env::var_os("DISPLAY").is_some() => match X11Backend::connect() {
+            Ok(x11) => capture_with(PaneSession::new(x11), backend, timeout),
+            Err(reason) if backend == CaptureBackend::Evdev => {
+                eprintln!("X11: FAILED reason={}, chords are logged only", reason);
+                run_capture(&mut ChordLogger, timeout)
+            }
+            Err(reason) => Err(reason),
+        },
+        None => {
+            eprintln!("INPUT: no $DISPLAY or i3/sway socket, chords are logged only");
+            run_capture(&mut ChordLogger, timeout)
+        }
+    };
+
+    flush_app_min_sizes();
+    match result {
+        Ok(()) => 0,
+        Err(reason) => {
+            eprintln!("INPUT: FAILED reason={}", reason);
+            1
+        }
+    }
+}
+
+/// Capture chords with the chosen input backend and run them against a session
+fn capture_with<B: SessionBackend>(mut session: PaneSession<B>, backend: CaptureBackend, timeout: Option<Duration>) -> Result<(), String> {
+    match backend {
+        CaptureBackend::X11 => {
+            let keys = session.form.lock().unwrap().bound_keys();
+            run_grab_capture(&mut session, &keys, timeout)
+        }
+        CaptureBackend::Evdev => run_capture(&mut session, timeout),
+    }
+}
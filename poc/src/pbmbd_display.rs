@@ -11,6 +11,7 @@ use objc2_app_kit::{NSScreen, NSApplication};
 
 // Import CGGetActiveDisplayList from pbmba_ax
 use crate::pbmba_ax::CGGetActiveDisplayList;
+use crate::pbgft_types::DisplayIdentity;
 
 // Rect structure for window dimensions
 #[repr(C)]
@@ -236,16 +237,18 @@ pub struct DisplayInfo {
     pub design_width: f64,   // Design dimensions with quirks already applied
     pub design_height: f64,
     pub name: String,
+    pub identity: DisplayIdentity,
 }
 
 impl DisplayInfo {
     /// Create DisplayInfo
-    pub fn new(index: usize, width: f64, height: f64, name: String) -> Self {
+    pub fn new(index: usize, width: f64, height: f64, name: String, identity: DisplayIdentity) -> Self {
         DisplayInfo {
             index,
             design_width: width,
             design_height: height,
             name,
+            identity,
         }
     }
 
@@ -309,16 +312,6 @@ impl DisplayInfo {
             .collect()
     }
 
-    /// Filter out panes smaller than minimum usable size
-    #[cfg(target_os = "macos")]
-    pub fn filter_small(rects: &[crate::pbgft_types::PixelRect]) -> Vec<crate::pbgft_types::PixelRect> {
-        const MIN_PANE_SIZE: f64 = 100.0;
-        rects.iter()
-            .filter(|r| r.width >= MIN_PANE_SIZE && r.height >= MIN_PANE_SIZE)
-            .cloned()
-            .collect()
-    }
-
     /// Get DisplayProps for Form queries
     #[cfg(target_os = "macos")]
     pub fn as_props(&self) -> crate::pbgft_types::DisplayProps {
@@ -330,6 +323,41 @@ impl DisplayInfo {
     }
 }
 
+// Get localized display name (macOS 10.15+)
+#[allow(unexpected_cfgs)]
+pub unsafe fn display_name_for_screen(screen: &NSScreen) -> Option<String> {
+    use objc2_foundation::NSString;
+    let name_ptr: *const NSString = msg_send![screen, localizedName];
+    if name_ptr.is_null() {
+        return None;
+    }
+    let name_str = unsafe { &*name_ptr };
+    Some(name_str.to_string())
+}
+
+// Get CGDirectDisplayID from the screen's device description (0 if unavailable)
+#[allow(unexpected_cfgs)]
+pub unsafe fn display_id_for_screen(screen: &NSScreen) -> u32 {
+    use objc2_foundation::NSNumber;
+    let device_desc = screen.deviceDescription();
+    let key = objc2_foundation::ns_string!("NSScreenNumber");
+    let screen_num: *const NSNumber = msg_send![&device_desc, objectForKey: key];
+    if screen_num.is_null() {
+        return 0;
+    }
+    msg_send![screen_num, unsignedIntValue]
+}
+
+// Get stable vendor/model/serial identity for a screen
+pub unsafe fn display_identity_for_screen(screen: &NSScreen) -> DisplayIdentity {
+    let display_id = display_id_for_screen(screen);
+    DisplayIdentity {
+        vendor: CGDisplayVendorNumber(display_id),
+        model: CGDisplayModelNumber(display_id),
+        serial: CGDisplaySerialNumber(display_id),
+    }
+}
+
 // Gather all display information for layout system initialization
 // Uses symmetric viewport dimensions (not raw visibleFrame) for design calculations
 #[allow(unexpected_cfgs)]
@@ -342,22 +370,14 @@ pub unsafe fn gather_all_display_info() -> Vec<DisplayInfo> {
 
     for (idx, screen) in screens.iter().enumerate() {
         if let Some(vf) = visible_frame_for_screen(screen) {
-            // Get localized name
-            use objc2::msg_send;
-            use objc2_foundation::NSString;
-            let name_ptr: *const NSString = msg_send![screen, localizedName];
-            let name = if !name_ptr.is_null() {
-                let name_str = unsafe { &*name_ptr };
-                name_str.to_string()
-            } else {
-                format!("Display {}", idx)
-            };
+            let name = display_name_for_screen(screen).unwrap_or_else(|| format!("Display {}", idx));
+            let identity = display_identity_for_screen(screen);
 
             // Apply symmetric viewport: visibleFrame.height already has top excluded,
             // subtract one more top_inset for symmetric bottom exclusion
             let symmetric_height = vf.height - top_inset;
 
-            displays.push(DisplayInfo::new(idx, vf.width, symmetric_height, name));
+            displays.push(DisplayInfo::new(idx, vf.width, symmetric_height, name, identity));
         }
     }
 
@@ -475,6 +495,31 @@ extern "C" {
     fn CGDisplayModeGetPixelHeight(mode: *mut std::ffi::c_void) -> usize;
     fn CGDisplayModeGetRefreshRate(mode: *mut std::ffi::c_void) -> f64;
     fn CGDisplayModeRelease(mode: *mut std::ffi::c_void);
+    fn CGDisplayRegisterReconfigurationCallback(
+        callback: extern "C" fn(display: u32, flags: u32, user_info: *mut std::ffi::c_void),
+        user_info: *mut std::ffi::c_void,
+    ) -> i32;
+}
+
+/// kCGDisplayBeginConfigurationFlag: first (pre-change) callback of a reconfiguration
+const K_CG_DISPLAY_BEGIN_CONFIGURATION_FLAG: u32 = 1 << 0;
+
+extern "C" fn display_reconfiguration_callback(_display: u32, flags: u32, user_info: *mut std::ffi::c_void) {
+    // Each change is reported twice per display; act once the change is done
+    if flags & K_CG_DISPLAY_BEGIN_CONFIGURATION_FLAG != 0 {
+        return;
+    }
+    let handler: fn() = unsafe { std::mem::transmute(user_info) };
+    handler();
+}
+
+/// Call `handler` (on the main run loop) after displays are added, removed,
+/// moved or change mode. May fire several times for one physical change.
+pub unsafe fn register_display_change_handler(handler: fn()) {
+    let rc = CGDisplayRegisterReconfigurationCallback(display_reconfiguration_callback, handler as *mut std::ffi::c_void);
+    if rc != 0 {
+        eprintln!("WARNING: display reconfiguration callback registration failed: {}", rc);
+    }
 }
 
 // Print comprehensive information about all connected displays
@@ -496,34 +541,18 @@ pub unsafe fn print_all_display_info() {
         eprintln!("\n--- Display {} ---", idx);
 
         // Get localized name (macOS 10.15+)
-        {
-            use objc2::msg_send;
-            use objc2_foundation::NSString;
-            let name: *const NSString = msg_send![screen, localizedName];
-            if !name.is_null() {
-                let name_str = unsafe { &*name };
-                eprintln!("  Name: {}", name_str);
-            } else {
-                eprintln!("  Name: <unavailable>");
-            }
+        match display_name_for_screen(screen) {
+            Some(name) => eprintln!("  Name: {}", name),
+            None => eprintln!("  Name: <unavailable>"),
         }
 
         // Get CGDirectDisplayID from device description
         let device_desc = screen.deviceDescription();
-        let display_id: u32 = {
-            use objc2::msg_send;
-            use objc2_foundation::NSNumber;
-            let key = objc2_foundation::ns_string!("NSScreenNumber");
-            let screen_num: *const NSNumber = msg_send![&device_desc, objectForKey: key];
-            if !screen_num.is_null() {
-                let num_value: u32 = msg_send![screen_num, unsignedIntValue];
-                num_value
-            } else {
-                0
-            }
-        };
+        let display_id = display_id_for_screen(screen);
+        let identity = display_identity_for_screen(screen);
 
         eprintln!("  Display ID: {} (0x{:x})", display_id, display_id);
+        eprintln!("  Identity: {} (DisplayMove target=\"id:{}\")", identity, identity);
 
         // Display state flags
         let is_main = CGDisplayIsMain(display_id);
@@ -7,26 +7,28 @@ use core_foundation::base::{kCFAllocatorDefault, CFRelease};
 use core_foundation::runloop::kCFRunLoopDefaultMode;
 use core_foundation_sys::base::CFTypeRef;
 use std::ffi::c_void;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
 use single_instance::SingleInstance;
 
 use crate::pbmbk_keymap::*;
 use crate::pbgk_keylog::{KEY_LOGGING_ENABLED, update_key_state};
-use crate::pbmsm_mru::{get_mru_snapshot, update_mru_with_focus};
+use crate::pbgam_appmin::flush_app_min_sizes;
+use crate::pbgi_input::{InputEvent, InputHost, InputPipeline, Key, Modifiers, SwitcherEntry, Verdict};
+use crate::pbmsm_mru::{get_mru_snapshot, update_mru_with_focus, MruWindowEntry};
 use crate::pbmsa_alttab::{
-    ALT_TAB_SESSION,
     show_alt_tab_overlay, update_alt_tab_highlight,
-    hide_alt_tab_overlay_and_cleanup, defer_alt_tab_commit,
-    cancel_alt_tab_session,
+    hide_alt_tab_overlay, defer_alt_tab_commit,
 };
 use crate::pbmcl_clipboard::{
-    CLIPBOARD_SESSION, start_clipboard_monitoring,
-    show_clipboard_overlay, handle_clipboard_navigation,
+    start_clipboard_monitoring, set_clipboard_text,
+    show_clipboard_overlay, update_clipboard_highlight, hide_clipboard_overlay,
 };
 use crate::pbmbo_observer::{setup_mru_observer, setup_workspace_observer};
 use crate::pbmsb_browser::is_chromium_based;
 use crate::pbmp_pane::{
     handle_configured_key,
+    key_accepts_autorepeat,
     reset_layout_session,
 };
 use crate::pbmbd_display::{print_all_display_info};
@@ -38,379 +40,197 @@ use crate::pbmba_ax::{
     CFMachPortCreateRunLoopSource,
 };
 
-// Global state for unknown keycode warning
-static WARNED_UNKNOWN_ONCE: AtomicBool = AtomicBool::new(false);
-
 // Global tap pointer for health check timer
 static EVENT_TAP_PTR: std::sync::atomic::AtomicPtr<c_void> = std::sync::atomic::AtomicPtr::new(std::ptr::null_mut());
 
-extern "C" fn tap_cb(_proxy: *mut c_void, event_type: u32, event: *mut c_void, _user: *mut c_void) -> *mut c_void {
-    unsafe {
-        // Get modifier flags and keycode early (needed for both logging and chord detection)
-        let flags = CGEventGetFlags(event);
-        let keycode = CGEventGetIntegerValueField(event, K_CG_KEYCODE_FIELD_KEYCODE) as u16;
+// Event handling chain (tap callback and timers all run on the main runloop)
+lazy_static::lazy_static! {
+    static ref INPUT_PIPELINE: Mutex<InputPipeline> = Mutex::new(InputPipeline::standard());
+}
 
-        // Debug: log ALL keyup and flags changed events (disabled - too noisy)
-        // if event_type == K_CG_EVENT_KEY_UP {
-        //     eprintln!("DEBUG: [tap_cb] keyup event_type={} keycode={} flags=0x{:08x}",
-        //              event_type, keycode, flags);
-        // }
-        // if event_type == K_CG_EVENT_FLAGS_CHANGED {
-        //     eprintln!("DEBUG: [tap_cb] flags_changed event_type={} keycode={} flags=0x{:08x}",
-        //              event_type, keycode, flags);
-        // }
+// ============================================================================================
+// Input host: pipeline side effects on macOS
+// ============================================================================================
 
-        // Optional key logging (for diagnostic visibility)
-        if KEY_LOGGING_ENABLED.load(Ordering::Acquire) {
-            // Track both keydown and keyup for complete state visibility
-            if event_type == K_CG_EVENT_KEY_DOWN || event_type == K_CG_EVENT_KEY_UP {
-                // Convert virtual keycode to HID usage code
-                if let Some(usage) = vk_to_hid_usage(keycode) {
-                    let is_pressed = event_type == K_CG_EVENT_KEY_DOWN;
-                    update_key_state(usage, is_pressed);
-                }
-            }
-        }
-
-        // Extract modifier flags early (needed by multiple sections)
-        let has_ctrl = (flags & K_CG_EVENT_FLAG_MASK_CONTROL) != 0;
-        let has_shift = (flags & K_CG_EVENT_FLAG_MASK_SHIFT) != 0;
-        let has_cmd = (flags & K_CG_EVENT_FLAG_MASK_COMMAND) != 0;
-        let has_opt = (flags & K_CG_EVENT_FLAG_MASK_ALTERNATE) != 0;
+/// Overlays, focus and Form actions for the input pipeline
+struct MacInputHost {
+    snapshot: Vec<MruWindowEntry>,   // MRU list as last shown to the pipeline
+}
 
-        // ===== MOUSE CLICK: Cancel Alt-Tab session if active =====
-        // Check for any mouse button down event during active Alt-Tab session
-        let is_mouse_down = matches!(event_type,
-            K_CG_EVENT_LEFT_MOUSE_DOWN | K_CG_EVENT_RIGHT_MOUSE_DOWN | K_CG_EVENT_OTHER_MOUSE_DOWN
-        );
+impl InputHost for MacInputHost {
+    fn chord_label(&self) -> &'static str {
+        "ctrl+shift+option"
+    }
 
-        if is_mouse_down {
-            let session_active = {
-                let session = ALT_TAB_SESSION.lock().unwrap();
-                session.active
-            };
-
-            if session_active {
-                // Mouse click during Alt-Tab session - cancel immediately
-                cancel_alt_tab_session();
-                // Consume the click to prevent conflicting activation
-                return std::ptr::null_mut();
-            }
-        }
+    fn switcher_entries(&mut self) -> Vec<SwitcherEntry> {
+        self.snapshot = get_mru_snapshot();
+        self.snapshot.iter()
+            .map(|e| SwitcherEntry { app_id: e.bundle_id.clone(), title: e.title.clone() })
+            .collect()
+    }
 
-        // ===== CLIPBOARD: Ctrl chord forwarding & Ctrl+Shift+V =====
-        // Handle clipboard overlay navigation if active
-        {
-            let session = CLIPBOARD_SESSION.lock().unwrap();
-            if session.active {
-                drop(session);
-                // If clipboard overlay is active, handle navigation keys
-                if event_type == K_CG_EVENT_KEY_DOWN {
-                    handle_clipboard_navigation(keycode);
-                    return std::ptr::null_mut(); // Block all keys during clipboard session
-                }
-                return event; // Pass through other events during session
-            }
+    fn prune_switcher_entries(&mut self) {
+        // Prune stale MRU entries at session start
+        let pruned = unsafe { crate::pbmsm_mru::prune_stale_mru_entries() };
+        if pruned > 0 {
+            println!("MRU: pruned {} stale entries (pre-session validation)", pruned);
         }
+    }
 
-        // Ctrl+Shift+V: Show clipboard history overlay
-        if event_type == K_CG_EVENT_KEY_DOWN && has_ctrl && has_shift && !has_cmd && !has_opt && keycode == VK_V {
-            show_clipboard_overlay();
-            return std::ptr::null_mut(); // Block the event
-        }
+    fn show_switcher(&mut self, highlight: usize) {
+        unsafe { show_alt_tab_overlay(highlight) };
+    }
 
-        // Ctrl+C/X/V (without Shift): Mirror to Cmd+C/X/V (duplicate, don't block)
-        if event_type == K_CG_EVENT_KEY_DOWN && has_ctrl && !has_shift && !has_cmd && !has_opt {
-            if keycode == VK_C || keycode == VK_X || keycode == VK_V {
-                // Create synthetic Cmd+C/X/V event
-                let synth_event = CGEventCreateKeyboardEvent(std::ptr::null_mut(), keycode, true);
-                // Set Command flag on synthetic event
-                CGEventSetFlags(synth_event, K_CG_EVENT_FLAG_MASK_COMMAND);
-                CGEventPost(K_CG_SESSION_EVENT_TAP, synth_event);
-                CFRelease(synth_event as CFTypeRef);
-
-                // Debug logging
-                let key_name = match keycode {
-                    VK_C => "c",
-                    VK_X => "x",
-                    VK_V => "v",
-                    _ => "?",
-                };
-                eprintln!("CLIP: mirror issued cmd+{}", key_name);
-
-                // Let original Ctrl event pass through
-                return event;
-            }
-        }
+    fn update_switcher(&mut self, highlight: usize) {
+        unsafe { update_alt_tab_highlight(highlight) };
+    }
 
-        // Also mirror Ctrl+C/X/V keyup events
-        if event_type == K_CG_EVENT_KEY_UP && has_ctrl && !has_cmd && !has_opt {
-            if keycode == VK_C || keycode == VK_X || keycode == VK_V {
-                let synth_event = CGEventCreateKeyboardEvent(std::ptr::null_mut(), keycode, false);
-                CGEventSetFlags(synth_event, K_CG_EVENT_FLAG_MASK_COMMAND);
-                CGEventPost(K_CG_SESSION_EVENT_TAP, synth_event);
-                CFRelease(synth_event as CFTypeRef);
+    fn hide_switcher(&mut self) {
+        unsafe { hide_alt_tab_overlay() };
+    }
 
-                // Let original Ctrl keyup pass through
-                return event;
-            }
+    fn commit_switcher(&mut self, index: usize) {
+        // Defer to main runloop for thread safety (AX APIs must be on main thread)
+        if let Some(entry) = self.snapshot.get(index) {
+            unsafe { defer_alt_tab_commit(entry.clone()) };
         }
+    }
 
-        // ===== COMMAND+TAB: Alt-Tab Session Management =====
-        // Must come BEFORE early return for non-keydown events
-
-        // Check for Command key state changes and Tab navigation
-        let snapshot = get_mru_snapshot();
-        let mru_count = snapshot.len();
-
-        // Check for Command release via flags changed or keyup events
-        if event_type == K_CG_EVENT_FLAGS_CHANGED || event_type == K_CG_EVENT_KEY_UP {
-            let session_active = {
-                let session = ALT_TAB_SESSION.lock().unwrap();
-                // eprintln!("SWITCHER: session check: active={}", session.active);
-                session.active
-            };
-
-            if session_active {
-                eprintln!("SWITCHER: checking flags: event_type={} keycode={} has_cmd={} has_shift={}",
-                         event_type, keycode, has_cmd, has_shift);
-
-                // If Command is no longer held, commit switch and cleanup
-                if !has_cmd {
-                    eprintln!("SWITCHER: Command released (detected via flags)");
-
-                    // Defensive bounds check: verify MRU snapshot is non-empty
-                    if snapshot.is_empty() {
-                        eprintln!("SWITCHER: switch | SKIPPED reason=empty_mru");
-                        hide_alt_tab_overlay_and_cleanup();
-                        return event;
-                    }
+    fn show_clipboard(&mut self, entries: &[String], highlight: usize) {
+        unsafe { show_clipboard_overlay(entries, highlight) };
+    }
 
-                    // Get the currently highlighted entry to switch to
-                    let target_entry = {
-                        let session = ALT_TAB_SESSION.lock().unwrap();
-                        if let Some(idx) = session.highlight_index {
-                            if idx < snapshot.len() {
-                                Some(snapshot[idx].clone())
-                            } else {
-                                // Bounds check failed - highlight_index exceeds array
-                                eprintln!("SWITCHER: switch | SKIPPED reason=index_out_of_bounds idx={} len={}", idx, snapshot.len());
-                                None
-                            }
-                        } else {
-                            // No highlight set - should not happen in normal flow
-                            eprintln!("SWITCHER: switch | SKIPPED reason=no_highlight_index");
-                            None
-                        }
-                    };
+    fn update_clipboard(&mut self, entries: &[String], highlight: usize) {
+        unsafe { update_clipboard_highlight(entries, highlight) };
+    }
 
-                    // Attempt to switch focus if we have a valid target
-                    // Defer to main runloop for thread safety (AX APIs must be on main thread)
-                    if let Some(entry) = target_entry {
-                        defer_alt_tab_commit(entry);
-                    }
+    fn hide_clipboard(&mut self) {
+        unsafe { hide_clipboard_overlay() };
+    }
 
-                    // Always cleanup overlays and session state
-                    hide_alt_tab_overlay_and_cleanup();
-                    return event; // Don't block flags changed events
-                }
-            }
-        }
+    fn set_clipboard_text(&mut self, text: &str) {
+        unsafe { set_clipboard_text(text) };
+    }
 
-        // Block Tab keyup during active session
-        if event_type == K_CG_EVENT_KEY_UP && keycode == 48 {
-            let session_active = {
-                let session = ALT_TAB_SESSION.lock().unwrap();
-                session.active
-            };
+    fn key_accepts_autorepeat(&self, key: &str) -> bool {
+        key_accepts_autorepeat(key)
+    }
 
-            if session_active {
-                // Block Tab keyup during session
-                return std::ptr::null_mut();
+    fn run_chord(&mut self, key: &'static str, is_repeat: bool) -> bool {
+        // Capture frontmost app info at chord time
+        let frontmost = match unsafe { get_frontmost_app_info() } {
+            Some(f) => f,
+            None => {
+                eprintln!("DEBUG: Failed to get frontmost app info, ignoring chord");
+                return false;
             }
-            return event;
+        };
+        let chromium_tag = if is_chromium_based(&frontmost.bundle_id) { " [chromium]" } else { "" };
+        eprintln!("DEBUG: Frontmost at tap: {} (pid={}){}",
+                 frontmost.bundle_id, frontmost.pid, chromium_tag);
+
+        // Update MRU with this window (for layout actions); repeats are the same window
+        if !is_repeat {
+            unsafe { update_mru_with_focus(frontmost.pid, frontmost.bundle_id.clone()) };
         }
 
-        // Check for Tab key (48) while Command is held (only on keydown)
-        if event_type == K_CG_EVENT_KEY_DOWN && keycode == 48 && has_cmd && !has_opt && !has_ctrl {
-            // Ignore auto-repeat to prevent uncontrolled cycling
-            let is_repeat = CGEventGetIntegerValueField(event, K_CG_KEYBOARD_EVENT_AUTOREPEAT) != 0;
-            if is_repeat {
-                eprintln!("SWITCHER: Ignoring Tab auto-repeat");
-                return std::ptr::null_mut(); // Discard auto-repeat event
-            }
-
-            let mut session = ALT_TAB_SESSION.lock().unwrap();
-
-            // Start session on first Tab press
-            let is_new_session = !session.active;
-            if is_new_session {
-                session.active = true;
-                drop(session); // Release lock before calling prune (which locks MRU_STACK)
-
-                println!("SWITCHER: session start");
-
-                // Prune stale MRU entries at session start
-                let pruned = crate::pbmsm_mru::prune_stale_mru_entries();
-                if pruned > 0 {
-                    println!("MRU: pruned {} stale entries (pre-session validation)", pruned);
-                }
-
-                // Re-acquire session lock
-                session = ALT_TAB_SESSION.lock().unwrap();
-            }
+        // Consume the chord
+        println!("BLOCKED: ctrl+shift+option+{}", key);
 
-            // Get fresh snapshot after pruning (if new session)
-            let (snapshot, mru_count) = if is_new_session {
-                let fresh_snapshot = get_mru_snapshot();
-                let fresh_count = fresh_snapshot.len();
-                (fresh_snapshot, fresh_count)
-            } else {
-                (snapshot, mru_count)
-            };
-
-            if mru_count == 0 {
-                // No windows to show - reset session state
-                session.active = false;
-                session.popup_shown = false;
-                session.highlight_index = None;
-                drop(session);
-                eprintln!("SWITCHER: no windows available");
-                println!("BLOCKED: cmd+tab (no windows)");
-                return std::ptr::null_mut();
-            }
-
-            if !session.popup_shown {
-                // First Tab press - immediately advance to next MRU entry
-                // Forward (Tab): start at index 1 (skip current window at index 0)
-                // Backward (Shift+Tab): start at last index (wrap around)
-                session.popup_shown = true;
-                let initial_idx = if has_shift {
-                    // Shift+Tab: go to last entry
-                    mru_count - 1
-                } else {
-                    // Tab: skip current window (index 0), go to index 1
-                    // Handle edge case: if only 1 window, stay at 0
-                    if mru_count > 1 { 1 } else { 0 }
-                };
-                session.highlight_index = Some(initial_idx);
-                drop(session); // Release lock before calling FFI
-
-                show_alt_tab_overlay(initial_idx);
-
-                let entry = &snapshot[initial_idx];
-                if has_shift {
-                    println!("SWITCHER: backward step -> index={} app={} win=\"{}\"",
-                             initial_idx, entry.bundle_id, entry.title);
-                    println!("BLOCKED: cmd+shift+tab");
-                } else {
-                    println!("SWITCHER: forward step -> index={} app={} win=\"{}\"",
-                             initial_idx, entry.bundle_id, entry.title);
-                    println!("BLOCKED: cmd+tab");
-                }
-                return std::ptr::null_mut();
-            }
+        // Dispatch to Form-configured action (LayoutAction or DisplayMove)
+        handle_configured_key(key, frontmost);
+        true
+    }
 
-            // Navigate forward or backward based on Shift
-            let current_idx = session.highlight_index.unwrap_or(0);
-            let new_idx = if has_shift {
-                // Shift+Tab = backward
-                if current_idx == 0 {
-                    mru_count - 1 // Wrap to end
-                } else {
-                    current_idx - 1
-                }
-            } else {
-                // Tab = forward
-                (current_idx + 1) % mru_count
-            };
+    fn reset_layout_session(&mut self) {
+        reset_layout_session();
+    }
+}
 
-            session.highlight_index = Some(new_idx);
-            drop(session); // Release lock before calling FFI
+// ============================================================================================
+// Event tap: CGEvent <-> InputEvent
+// ============================================================================================
 
-            update_alt_tab_highlight(new_idx);
+fn modifiers_from_flags(flags: u64) -> Modifiers {
+    Modifiers {
+        ctrl: (flags & K_CG_EVENT_FLAG_MASK_CONTROL) != 0,
+        shift: (flags & K_CG_EVENT_FLAG_MASK_SHIFT) != 0,
+        alt: (flags & K_CG_EVENT_FLAG_MASK_ALTERNATE) != 0,
+        cmd: (flags & K_CG_EVENT_FLAG_MASK_COMMAND) != 0,
+    }
+}
 
-            let entry = &snapshot[new_idx];
-            if has_shift {
-                println!("SWITCHER: backward step -> index={} app={} win=\"{}\"",
-                         new_idx, entry.bundle_id, entry.title);
-                println!("BLOCKED: cmd+shift+tab");
-            } else {
-                println!("SWITCHER: forward step -> index={} app={} win=\"{}\"",
-                         new_idx, entry.bundle_id, entry.title);
-                println!("BLOCKED: cmd+tab");
-            }
+fn flags_from_modifiers(mods: Modifiers) -> u64 {
+    let mut flags = 0;
+    if mods.ctrl { flags |= K_CG_EVENT_FLAG_MASK_CONTROL; }
+    if mods.shift { flags |= K_CG_EVENT_FLAG_MASK_SHIFT; }
+    if mods.alt { flags |= K_CG_EVENT_FLAG_MASK_ALTERNATE; }
+    if mods.cmd { flags |= K_CG_EVENT_FLAG_MASK_COMMAND; }
+    flags
+}
 
-            return std::ptr::null_mut(); // Block the event
+/// Post a synthetic key event from the pipeline (it comes back through the tap)
+unsafe fn post_synthetic(event: &InputEvent) {
+    let (key, mods, down) = match *event {
+        InputEvent::KeyDown { key, mods, .. } => (key, mods, true),
+        InputEvent::KeyUp { key, mods } => (key, mods, false),
+        _ => {
+            eprintln!("DEBUG: cannot synthesize {} event, dropped", event.kind());
+            return;
         }
+    };
+    let synth_event = CGEventCreateKeyboardEvent(std::ptr::null_mut(), key.code, down);
+    CGEventSetFlags(synth_event, flags_from_modifiers(mods));
+    CGEventPost(K_CG_SESSION_EVENT_TAP, synth_event);
+    CFRelease(synth_event as CFTypeRef);
+}
 
-        // ===== CTRL+SHIFT+OPTION Release: Reset Layout Sequence Indices =====
-        // Track when Ctrl+Shift+Option chord is released to reset all sequence indices per spec
-        static CTRL_SHIFT_OPT_WAS_HELD: AtomicBool = AtomicBool::new(false);
-
-        if event_type == K_CG_EVENT_FLAGS_CHANGED {
-            let ctrl_shift_opt_held = has_ctrl && has_shift && has_opt;
-            let was_held = CTRL_SHIFT_OPT_WAS_HELD.load(Ordering::Acquire);
+extern "C" fn tap_cb(_proxy: *mut c_void, event_type: u32, event: *mut c_void, _user: *mut c_void) -> *mut c_void {
+    unsafe {
+        let flags = CGEventGetFlags(event);
+        let keycode = CGEventGetIntegerValueField(event, K_CG_KEYCODE_FIELD_KEYCODE) as u16;
 
-            if was_held && !ctrl_shift_opt_held {
-                // Ctrl+Shift+Option released - reset all sequence indices
-                reset_layout_session();
+        // Optional key logging (for diagnostic visibility)
+        if KEY_LOGGING_ENABLED.load(Ordering::Acquire)
+            && (event_type == K_CG_EVENT_KEY_DOWN || event_type == K_CG_EVENT_KEY_UP)
+        {
+            // Convert virtual keycode to HID usage code
+            if let Some(usage) = vk_to_hid_usage(keycode) {
+                update_key_state(usage, event_type == K_CG_EVENT_KEY_DOWN);
             }
-
-            CTRL_SHIFT_OPT_WAS_HELD.store(ctrl_shift_opt_held, Ordering::Release);
-        }
-
-        // Early exit for non-keydown events (chord interception only acts on keydown)
-        // This check comes AFTER Alt-Tab handling since we need to track Alt keyup/flags_changed for session cleanup
-        if event_type != K_CG_EVENT_KEY_DOWN { return event; }
-
-        // Ignore autorepeat
-        let is_repeat = CGEventGetIntegerValueField(event, K_CG_KEYBOARD_EVENT_AUTOREPEAT) != 0;
-        if is_repeat { return event; }
-
-        // ===== CTRL+SHIFT+OPTION: Quadrant Tiling =====
-        // Require ctrl+shift+option held, reject cmd to prevent ambiguity with app shortcuts
-        let need = K_CG_EVENT_FLAG_MASK_CONTROL | K_CG_EVENT_FLAG_MASK_SHIFT | K_CG_EVENT_FLAG_MASK_ALTERNATE;
-        let reject = K_CG_EVENT_FLAG_MASK_COMMAND;
-
-        if (flags & need) != need { return event; }
-        if (flags & reject) != 0 { return event; }
-
-        // Always log ctrl+shift+option keypresses to help diagnose keycode issues
-        eprintln!("DEBUG: ctrl+shift+option+keycode=0x{:02x}", keycode);
-
-        // Unified XML-driven key handler (LayoutAction and DisplayMove)
-        // Map keycode to XML key name using comprehensive mapping
-        let key_name = keycode_to_xml_key(keycode);
-
-        // Warn once per session for unmapped keycodes (excludes modifiers)
-        if key_name.is_none() && !WARNED_UNKNOWN_ONCE.swap(true, Ordering::AcqRel) {
-            eprintln!("NOTE: ctrl+shift+option on unmapped keycode=0x{:02x}. This key is not configured in form.xml.", keycode);
         }
 
-        if let Some(key) = key_name {
-            // Capture frontmost app info at chord time
-            if let Some(frontmost) = get_frontmost_app_info() {
-                let chromium_tag = if is_chromium_based(&frontmost.bundle_id) { " [chromium]" } else { "" };
-                eprintln!("DEBUG: Frontmost at tap: {} (pid={}){}",
-                         frontmost.bundle_id, frontmost.pid, chromium_tag);
-
-                // Update MRU with this window (for layout actions)
-                update_mru_with_focus(frontmost.pid, frontmost.bundle_id.clone());
-
-                // Consume the chord
-                println!("BLOCKED: ctrl+shift+option+{}", key);
-
-                // Dispatch to Form-configured action (LayoutAction or DisplayMove)
-                handle_configured_key(key, frontmost);
-
-                std::ptr::null_mut() // <- swallow the event
-            } else {
-                eprintln!("DEBUG: Failed to get frontmost app info, ignoring chord");
-                event
+        let mods = modifiers_from_flags(flags);
+        let key = Key { code: keycode, name: keycode_to_xml_key(keycode) };
+        let input = match event_type {
+            K_CG_EVENT_KEY_DOWN => {
+                let repeat = CGEventGetIntegerValueField(event, K_CG_KEYBOARD_EVENT_AUTOREPEAT) != 0;
+                InputEvent::KeyDown { key, mods, repeat }
             }
-        } else {
-            event
+            K_CG_EVENT_KEY_UP => InputEvent::KeyUp { key, mods },
+            K_CG_EVENT_FLAGS_CHANGED => InputEvent::FlagsChanged { code: keycode, mods },
+            K_CG_EVENT_LEFT_MOUSE_DOWN | K_CG_EVENT_RIGHT_MOUSE_DOWN | K_CG_EVENT_OTHER_MOUSE_DOWN => {
+                InputEvent::MouseDown { mods }
+            }
+            _ => return event, // Mouse up/move, tap disabled notifications
+        };
+
+        let mut host = MacInputHost { snapshot: Vec::new() };
+        let verdict = INPUT_PIPELINE.lock().unwrap().handle(&input, &mut host);
+        match verdict {
+            Verdict::Swallow => std::ptr::null_mut(),
+            Verdict::Replace(events) => {
+                let mut keep = false;
+                for replacement in &events {
+                    if *replacement == input {
+                        keep = true;
+                    } else {
+                        post_synthetic(replacement);
+                    }
+                }
+                if keep { event } else { std::ptr::null_mut() }
+            }
+            Verdict::Pass | Verdict::Continue => event,
         }
     }
 }
@@ -418,6 +238,7 @@ extern "C" fn tap_cb(_proxy: *mut c_void, event_type: u32, event: *mut c_void, _
 // Timer callback for auto-exit timeout (testing/automation)
 extern "C" fn timeout_exit_callback(_timer: *mut c_void, _info: *mut c_void) {
     eprintln!("\nAuto-exit: timeout reached, terminating gracefully");
+    flush_app_min_sizes();
     std::process::exit(0);
 }
 
@@ -448,22 +269,28 @@ extern "C" fn tap_health_check_timer(_timer: *mut c_void, _info: *mut c_void) {
             }
         }
 
-        // Check for stuck switcher overlay (Option not held but session active)
-        let session = ALT_TAB_SESSION.lock().unwrap();
-        if session.active {
+        // Check for stuck switcher overlay (Command not held but session active)
+        let session_active = INPUT_PIPELINE.lock().unwrap().sessions().alt_tab.active;
+        if session_active {
             // Check if Option is actually held by querying current event flags
             // We can't directly query key state here, but we can check if the session
             // has been active for an unreasonable amount of time without events
             // For now, just log if session is active (this will help diagnose stuck states)
-            drop(session);
 
-            // Note: A more robust check would query CGEventSourceKeyState for the Option key,
+            // Note: A more robust check would query CGEventSourceKeyState for the Command key,
             // but that requires creating an event source. For this PoC, the user can press
-            // Option again to recover, and the console warning helps diagnose the issue.
+            // Command again to recover, and the console warning helps diagnose the issue.
         }
     }
 }
 
+// Display reconfiguration: rebuild everything indexed by display
+fn display_change_callback() {
+    crate::pbmp_pane::refresh_displays();
+    // Tiled windows are keyed by display index, which may now point at another screen
+    unsafe { crate::pbmpt_autotile::init_auto_tiling(); }
+}
+
 pub unsafe fn run_quadrant_poc() -> ! {
     // Check AX permissions first
     ax_trusted_or_die();
@@ -631,6 +458,12 @@ pub unsafe fn run_quadrant_poc() -> ! {
     // Setup NSWorkspace observer for app activation
     setup_workspace_observer();
 
+    // Adopt existing windows on displays whose Space enables <AutoTile>
+    crate::pbmpt_autotile::init_auto_tiling();
+
+    // Display list, pane lists and tiled displays follow the connected displays
+    crate::pbmbd_display::register_display_change_handler(display_change_callback);
+
     // Setup clipboard monitoring
     start_clipboard_monitoring();
 
@@ -727,5 +560,6 @@ pub unsafe fn run_quadrant_poc() -> ! {
     eprintln!("DEBUG: Using CFRunLoopPerformBlock for deferred AX operations (Chrome compatibility)");
 
     CFRunLoopRun();
+    flush_app_min_sizes();
     std::process::exit(0);
 }
\ No newline at end of file
@@ -58,13 +58,20 @@ static MRU_OBSERVER: AtomicBool = AtomicBool::new(false);
 extern "C" fn window_focus_callback(
     _observer: AXObserverRef,
     _element: AXUIElementRef,
-    _notification: CFTypeRef,
+    notification: CFTypeRef,
     refcon: *mut c_void,
 ) {
     unsafe {
         // refcon contains the PID
         let pid = refcon as u32;
 
+        // Window closed or minimized: focus did not change here, only tiling reflows
+        let name = CFString::wrap_under_get_rule(notification as *const _).to_string();
+        if name == "AXUIElementDestroyed" || name == "AXWindowMiniaturized" {
+            crate::pbmpt_autotile::window_closed(pid);
+            return;
+        }
+
         // Get bundle ID from our mapping
         let bundle_id = {
             let bundle_map = PID_TO_BUNDLE.lock().unwrap();
@@ -76,6 +83,9 @@ extern "C" fn window_focus_callback(
         // Update MRU with the focused window
         // The element passed is the window element that received focus
         update_mru_with_focus(pid, bundle_id);
+
+        // Window created or refocused: sync automatic tiling for this app
+        crate::pbmpt_autotile::reconcile_app(pid);
     }
 }
 
@@ -114,13 +124,40 @@ pub unsafe fn create_observer_for_app(pid: u32, bundle_id: String) -> Result<(),
         pid as *mut c_void, // Pass PID as refcon
     );
 
-    CFRelease(app_element as CFTypeRef);
-
     if add_rc != KAX_ERROR_SUCCESS {
+        CFRelease(app_element as CFTypeRef);
         CFRelease(observer as CFTypeRef);
         return Err(format!("Failed to add notification for pid={}: error={}", pid, add_rc));
     }
 
+    // Register for AXWindowCreated (automatic tiling); focus tracking works without it
+    let created_name = CFString::from_static_string("AXWindowCreated");
+    let created_rc = AXObserverAddNotification(
+        observer,
+        app_element,
+        created_name.as_concrete_TypeRef() as CFTypeRef,
+        pid as *mut c_void,
+    );
+    if created_rc != KAX_ERROR_SUCCESS {
+        eprintln!("DEBUG: [AXObserver] AXWindowCreated not available for pid={}: error={}", pid, created_rc);
+    }
+
+    // Register for window close and minimize (automatic tiling reflows the freed slot)
+    for name in ["AXUIElementDestroyed", "AXWindowMiniaturized"] {
+        let notif = CFString::from_static_string(name);
+        let rc = AXObserverAddNotification(
+            observer,
+            app_element,
+            notif.as_concrete_TypeRef() as CFTypeRef,
+            pid as *mut c_void,
+        );
+        if rc != KAX_ERROR_SUCCESS {
+            eprintln!("DEBUG: [AXObserver] {} not available for pid={}: error={}", name, pid, rc);
+        }
+    }
+
+    CFRelease(app_element as CFTypeRef);
+
     // Add observer to runloop
     let runloop_source = AXObserverGetRunLoopSource(observer);
     if runloop_source.is_null() {
@@ -224,6 +261,9 @@ extern "C" fn focus_change_callback(pid: i32, bundle: *const std::os::raw::c_cha
         if let Err(e) = create_observer_for_app(pid as u32, bundle_id) {
             eprintln!("DEBUG: [AXObserver] Failed to create observer: {}", e);
         }
+
+        // Adopt the app's windows into automatic tiling (no-op when disabled)
+        crate::pbmpt_autotile::reconcile_app(pid as u32);
     }
 }
 
@@ -241,9 +281,13 @@ extern "C" fn app_terminated_callback(pid: i32) {
         if before_len != after_len {
             eprintln!("DEBUG: [MRU] Removed entry for pid={} from MRU stack", pid);
         }
+        drop(stack);
 
         // Remove AXObserver if any
         remove_observer_for_app(pid as u32);
+
+        // Reflow tiled displays that held this app's windows
+        crate::pbmpt_autotile::app_terminated(pid as u32);
     }
 }
 
@@ -0,0 +1,85 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+#![cfg(target_os = "macos")]
+
+//! AX Window Backend
+//! WindowBackend over the macOS Accessibility API
+//!
+//! The window handle is a retained AxElement. Each operation delegates to the
+//! existing AX helpers, so logging and error reasons match the direct calls.
+
+use crate::pbgft_types::PixelRect;
+use crate::pbgw_window::{WindowBackend, WindowError, WindowInfo};
+use crate::pbmba_ax::{validate_window_exists, AxElement, AxError};
+use crate::pbmbd_display::Rect;
+use crate::pbmp_pane::{enumerate_app_windows, focus_window_by_id, get_focused_window_by_pid, window_element_by_id};
+use crate::pbmsb_browser::is_chromium_based;
+
+/// Accessibility-backed window operations (stateless)
+pub struct AxBackend;
+
+impl From<AxError> for WindowError {
+    fn from(e: AxError) -> Self {
+        match e {
+            AxError::Permission => WindowError::Permission,
+            AxError::Constrained => WindowError::Constrained,
+            AxError::Platform(code) => WindowError::Platform(code),
+        }
+    }
+}
+
+fn to_pixel_rect(r: Rect) -> PixelRect {
+    PixelRect { x: r.x, y: r.y, width: r.w, height: r.h }
+}
+
+impl WindowBackend for AxBackend {
+    type Window = AxElement;
+
+    fn focused_window(&self, pid: u32) -> Result<AxElement, String> {
+        unsafe { get_focused_window_by_pid(pid) }
+    }
+
+    fn window_by_id(&self, pid: u32, window_id: u32) -> Option<AxElement> {
+        unsafe { window_element_by_id(pid, window_id) }
+    }
+
+    fn enumerate_windows(&self, pid: u32) -> Vec<WindowInfo> {
+        unsafe { enumerate_app_windows(pid) }.into_iter()
+            .map(|w| WindowInfo {
+                window_id: w.window_id,
+                title: w.title,
+                rect: w.rect.map(to_pixel_rect),
+                minimized: w.minimized,
+            })
+            .collect()
+    }
+
+    fn window_id(&self, win: &AxElement) -> Option<u32> {
+        unsafe { win.get_window_id() }
+    }
+
+    fn get_rect(&self, win: &AxElement) -> Option<PixelRect> {
+        unsafe { win.get_current_rect() }.map(to_pixel_rect)
+    }
+
+    fn set_position(&self, win: &AxElement, x: f64, y: f64) -> Result<(), WindowError> {
+        unsafe { win.set_position(x, y) }.map_err(WindowError::from)
+    }
+
+    fn set_size(&self, win: &AxElement, width: f64, height: f64) -> Result<(), WindowError> {
+        unsafe { win.set_size(width, height) }.map_err(WindowError::from)
+    }
+
+    fn raise(&self, pid: u32, window_id: u32) -> bool {
+        unsafe { focus_window_by_id(pid, window_id) }
+    }
+
+    fn window_exists(&self, pid: u32, window_id: u32) -> bool {
+        unsafe { validate_window_exists(pid, window_id) }
+    }
+
+    fn app_tag(&self, app_id: &str) -> &'static str {
+        if is_chromium_based(app_id) { " [chromium]" } else { "" }
+    }
+}
@@ -28,14 +28,16 @@ use crate::pbmba_ax::{
 };
 
 use crate::pbmbd_display::{
-    VisibleFrame, Rect,
-    visible_frame_main_display, visible_frame_for_screen,
+    DisplayInfo, VisibleFrame, Rect,
+    visible_frame_for_screen,
     get_all_screens, get_display_for_window_with_validation,
-    gather_all_display_info,
+    gather_all_display_info, display_name_for_screen, display_identity_for_screen,
 };
 
 use crate::pbgfr_resolve::Form;
-use crate::pbgft_types::PixelRect;
+use crate::pbgft_types::{PixelRect, DisplayPlacement, DisplayProps};
+use crate::pbgw_window::{WindowBackend, TileDisplay, set_window_rect, tile_next_pane, tile_fixed_pane, move_window_to_display};
+use crate::pbmbw_window::AxBackend;
 
 // Need to import CFRelease separately as it's used in multiple places
 use core_foundation::base::CFRelease;
@@ -54,23 +56,21 @@ lazy_static! {
         }
     };
 
-    // Quirk-adjusted displays cached at startup (caller ownership pattern)
-    static ref ADJUSTED_DISPLAYS: Vec<crate::pbmbd_display::DisplayInfo> = {
-        unsafe {
-            let raw_displays = gather_all_display_info();
-            let form = FORM.lock().unwrap();
-            form.adjust_displays(&raw_displays)
-        }
-    };
+    // Quirk-adjusted displays (caller ownership pattern)
+    // Gathered on first use, rebuilt by refresh_displays when the configuration changes
+    static ref ADJUSTED_DISPLAYS: Mutex<Option<Vec<DisplayInfo>>> = Mutex::new(None);
+
+    // Live display placements for DisplayMove (viewport, name, identity)
+    // Gathered on the first move after startup or a display change, not per key press
+    static ref DISPLAY_PLACEMENTS: Mutex<Option<Vec<DisplayPlacement>>> = Mutex::new(None);
 }
 
 /// Print expected pane sequence for all layout actions at startup
 pub unsafe fn print_expected_pane_sequences() {
     eprintln!("\n========== EXPECTED PANE SEQUENCES ==========\n");
 
-    // Iterate ADJUSTED_DISPLAYS first (triggers lazy_static initialization)
-    // Then lock FORM only when needed (avoids deadlock)
-    for display_info in ADJUSTED_DISPLAYS.iter() {
+    // Snapshot the displays first, then lock FORM only when needed (avoids deadlock)
+    for display_info in adjusted_displays().iter() {
         eprintln!("Display: {} ({}x{})",
             display_info.name,
             display_info.design_width as u32,
@@ -81,8 +81,9 @@ pub unsafe fn print_expected_pane_sequences() {
 
         for key in keys {
             // Lock FORM for each lookup (don't hold lock during I/O)
+            // Same filtered cycle a press walks (global size floor, no app-specific minimum)
             let form = FORM.lock().unwrap();
-            let panes_opt = form.panes_for_action(key, &display_props);
+            let panes_opt = form.pane_cycle(key, &display_props, (0.0, 0.0));
             drop(form);
 
             if let Some(panes) = panes_opt {
@@ -109,7 +110,7 @@ pub unsafe fn print_expected_pane_sequences() {
 /// Uses DisplayInfo's live_viewport method which implements symmetric viewport logic.
 /// See: Coordinate System Abstraction in paneboard-poc.md
 unsafe fn symmetric_viewport_for_index(screen: &objc2_app_kit::NSScreen, display_index: usize) -> Option<VisibleFrame> {
-    if let Some(display_info) = ADJUSTED_DISPLAYS.get(display_index) {
+    if let Some(display_info) = adjusted_displays().get(display_index) {
         return display_info.live_viewport(screen);
     }
     // Fallback: return raw visible frame if lookup fails
@@ -139,6 +140,59 @@ pub struct ObserverContext {
 pub struct EnumeratedWindow {
     pub window_id: u32,
     pub title: String,
+    pub rect: Option<Rect>,  // Current frame (None if AX position/size unavailable)
+    pub minimized: bool,
+}
+
+/// Auto-tiling configuration for each display (index-aligned with the display cache)
+pub(crate) fn auto_tile_configs() -> Vec<Option<crate::pbgft_types::TilingConfig>> {
+    let displays = adjusted_displays();
+    let form = FORM.lock().unwrap();
+    displays.iter()
+        .map(|display_info| form.auto_tile_for_display(&display_info.as_props()))
+        .collect()
+}
+
+/// Realize a fractional rect on a display (design dimensions, live viewport origin)
+pub unsafe fn realize_frac_on_display(display_index: usize, frac: &crate::pbgft_types::PaneFrac) -> Option<Rect> {
+    let displays = adjusted_displays();
+    let display_info = displays.get(display_index)?;
+    let pixel_rects = display_info.realize_panes(std::slice::from_ref(frac));
+    let vf = symmetric_viewport_for_index(get_all_screens().get(display_index)?, display_index)?;
+    pixel_rects.first().map(|pr| pixel_rect_to_rect(pr, &vf))
+}
+
+/// Check if a chorded key should fire on autorepeat (Nudge/Resize bindings)
+pub fn key_accepts_autorepeat(key: &str) -> bool {
+    FORM.lock().unwrap().accepts_autorepeat(key)
+}
+
+/// Quirk-adjusted displays, gathered on first use
+/// Returns a snapshot: index N pairs with the Nth live screen until the next refresh
+fn adjusted_displays() -> Vec<DisplayInfo> {
+    if let Some(displays) = ADJUSTED_DISPLAYS.lock().unwrap().as_ref() {
+        return displays.clone();
+    }
+    // Gathered without holding the cache lock (FORM is locked to adjust them)
+    let raw_displays = unsafe { gather_all_display_info() };
+    let displays = FORM.lock().unwrap().adjust_displays(&raw_displays);
+    *ADJUSTED_DISPLAYS.lock().unwrap() = Some(displays.clone());
+    displays
+}
+
+/// Rebuild the display list and pane cache (display configuration changed)
+/// Design sizes, pane lists and placements all come from the displays present now
+pub fn refresh_displays() {
+    let raw_displays = unsafe { gather_all_display_info() };
+    let mut form = FORM.lock().unwrap();
+    let displays = form.adjust_displays(&raw_displays);
+    let props: Vec<DisplayProps> = displays.iter().map(|d| d.as_props()).collect();
+    form.rebuild_pane_cache(&props);
+    drop(form);
+
+    eprintln!("LAYOUT: display configuration changed, {} displays, pane cache rebuilt", displays.len());
+    *ADJUSTED_DISPLAYS.lock().unwrap() = Some(displays);
+    *DISPLAY_PLACEMENTS.lock().unwrap() = None;
 }
 
 /// Reset layout session state (called on modifier release)
@@ -148,16 +202,23 @@ pub fn reset_layout_session() {
     form.reset_display_move_session();
 }
 
-/// Handle any key configured in Form XML (LayoutAction or DisplayMove)
+/// Handle any key configured in Form XML (Macro, LayoutAction, DisplayMove, ...)
 /// Returns true if the key was handled, false if no binding exists
 pub fn handle_configured_key(key: &str, frontmost: FrontmostInfo) -> bool {
-    // Check Form for bindings (order: LayoutAction first, then DisplayMove)
+    // Check Form for bindings (order: Macro, LayoutAction, DisplayMove, FocusMove, SwapAction, Nudge, Resize)
     let form = FORM.lock().unwrap();
+    let macro_spec = form.macro_spec(key);
     let has_layout = form.has_layout_action(key);
     let has_display_move = form.has_display_move(key);
+    let focus_direction = form.focus_move_direction(key);
+    let swap_target = form.swap_action_target(key);
+    let nudge = form.nudge_action(key);
+    let resize = form.resize_action(key);
     drop(form);
 
-    if has_layout {
+    if let Some(spec) = macro_spec {
+        unsafe { crate::pbmpm_macro::run_macro(key, &spec, frontmost) }
+    } else if has_layout {
         // Create TilingJob for Form-driven layout
         let job = TilingJob {
             frontmost,
@@ -168,6 +229,14 @@ pub fn handle_configured_key(key: &str, frontmost: FrontmostInfo) -> bool {
         true
     } else if has_display_move {
         execute_display_move_for_key(key, frontmost.pid, &frontmost.bundle_id)
+    } else if let Some(direction) = focus_direction {
+        unsafe { crate::pbmsf_focus::focus_in_direction(direction, &frontmost) }
+    } else if let Some(target) = swap_target {
+        unsafe { crate::pbmps_swap::swap_with_neighbor(target, &frontmost) }
+    } else if let Some(spec) = nudge {
+        unsafe { crate::pbmpn_nudge::nudge_focused_window(spec, frontmost.pid) }
+    } else if let Some(spec) = resize {
+        unsafe { crate::pbmpn_nudge::resize_focused_window(spec, frontmost.pid) }
     } else {
         false
     }
@@ -175,187 +244,66 @@ pub fn handle_configured_key(key: &str, frontmost: FrontmostInfo) -> bool {
 
 /// Execute a DisplayMove for the given key (checks Form configuration)
 /// Returns true if move was executed, false if no binding or out of range
-///
-/// Size preservation behavior per spec:
-/// 1. First move in chord: store original size
-/// 2. If window fits in target: preserve size ("preserved size")
-/// 3. If window too large:
-///    a. If original size would fit: restore original ("restored original size")
-///    b. Otherwise: resize to full target viewport ("resized to full screen (too large)")
+/// Size preservation rules live in pbgw_window::move_window_to_display
 pub fn execute_display_move_for_key(key: &str, pid: u32, bundle_id: &str) -> bool {
-    unsafe {
-        let screens = get_all_screens();
-        if screens.is_empty() {
-            eprintln!("DISPLAYMOVE: key={} | FAILED reason=no_screens", key);
-            return false;
-        }
-
-        // Get focused window
-        let win = match get_focused_window_by_pid(pid) {
-            Ok(w) => w,
-            Err(reason) => {
-                eprintln!("DISPLAYMOVE: key={} | FAILED reason={}", key, reason);
-                return false;
-            }
-        };
-
-        // Get current window rect
-        let current_rect = match win.get_current_rect() {
-            Some(r) => r,
-            None => {
-                eprintln!("DISPLAYMOVE: key={} | FAILED reason=cannot_get_current_rect", key);
-                return false;
-            }
-        };
-
-        // Determine current display index
-        let current_display_index = get_display_index_for_window(current_rect);
-
-        // Look up target display from Form (returns both index and target spec)
-        let mut form = FORM.lock().unwrap();
-        let (target_display_index, target_spec) = match form.execute_display_move(key, current_display_index, screens.len()) {
-            Some(result) => result,
-            None => {
-                // No binding or out of range (already logged)
-                return false;
-            }
-        };
-
-        // Format target for logging from the enum (spec format: "next", "prev", or index number)
-        use crate::pbgft_types::DisplayMoveTarget;
-        let target_string: String = match target_spec {
-            DisplayMoveTarget::Next { .. } => "next".to_string(),
-            DisplayMoveTarget::Prev { .. } => "prev".to_string(),
-            DisplayMoveTarget::Index(idx) => idx.to_string(),
-        };
-
-        // If already on target display, no-op
-        if target_display_index == current_display_index {
-            eprintln!("DISPLAYMOVE: Already on target display {}", target_display_index);
-            return false;
-        }
-
-        // Get current visible frame for offset calculation (need this before storing session)
-        let current_vf = symmetric_viewport_for_index(&screens[current_display_index], current_display_index).unwrap();
-
-        // Calculate current offset within screen (relative position)
-        let current_offset_x = current_rect.x - current_vf.min_x;
-        let current_offset_y = current_rect.y - current_vf.min_y;
-
-        // Store original size AND offset on first move in chord
-        form.start_display_move_session(current_rect.w, current_rect.h, current_offset_x, current_offset_y);
-        let original_size = form.get_original_size();
-        let original_offset = form.get_original_offset();
-        drop(form); // Release lock before AX operations
-
-        // Get target visible frame
-        let target_vf = match symmetric_viewport_for_index(&screens[target_display_index], target_display_index) {
-            Some(vf) => vf,
-            None => {
-                eprintln!("DISPLAYMOVE: key={} | FAILED reason=no_visible_frame", key);
-                return false;
-            }
-        };
-
-        // Determine final size and position, tracking which case applies
-        // Per spec: restore original when "returning to a display large enough to accommodate it"
-        let (final_w, final_h, offset_x, offset_y, size_action): (f64, f64, f64, f64, &str);
-
-        // Check if original size would fit (prioritize restoring original per spec)
-        let original_fits = original_size.map_or(false, |(ow, oh)| {
-            ow <= target_vf.width && oh <= target_vf.height
-        });
-
-        // Check if current size fits in target viewport
-        let current_fits = current_rect.w <= target_vf.width && current_rect.h <= target_vf.height;
-
-        if original_fits {
-            // Case 1: Original size fits - restore it with original offset
-            let (ow, oh) = original_size.unwrap();
-            let (ox, oy) = original_offset.unwrap_or((current_offset_x, current_offset_y));
-            final_w = ow;
-            final_h = oh;
-            offset_x = ox;
-            offset_y = oy;
-            size_action = "restored original size";
-        } else if current_fits {
-            // Case 2: Current size fits (but original too large) - preserve current with current offset
-            final_w = current_rect.w;
-            final_h = current_rect.h;
-            offset_x = current_offset_x;
-            offset_y = current_offset_y;
-            size_action = "preserved size";
-        } else {
-            // Case 3: Neither fits - resize to full target viewport, position at origin
-            final_w = target_vf.width;
-            final_h = target_vf.height;
-            offset_x = 0.0;
-            offset_y = 0.0;
-            size_action = "resized to full screen (too large)";
-        }
+    let mut placements = DISPLAY_PLACEMENTS.lock().unwrap();
+    let displays = placements.get_or_insert_with(|| unsafe { gather_display_placements() });
+    move_window_to_display(&AxBackend, &FORM, key, pid, bundle_id, displays)
+}
 
-        // Calculate position - apply offset, then clamp to target bounds
-        let mut new_x = target_vf.min_x + offset_x;
-        let mut new_y = target_vf.min_y + offset_y;
+/// Live placement of every display, queried from the screens now rather than the
+/// startup cache, so replugged monitors resolve correctly
+unsafe fn gather_display_placements() -> Vec<DisplayPlacement> {
+    get_all_screens().iter().enumerate()
+        .map(|(idx, screen)| DisplayPlacement {
+            frame: symmetric_viewport_for_index(screen, idx)
+                .map(|vf| visible_frame_to_pixel_rect(&vf))
+                .unwrap_or(PixelRect { x: 0.0, y: 0.0, width: 0.0, height: 0.0 }),
+            name: display_name_for_screen(screen).unwrap_or_else(|| format!("Display {}", idx)),
+            identity: display_identity_for_screen(screen),
+        })
+        .collect()
+}
 
-        // Clamp position to ensure window stays within target viewport bounds
-        let max_x = target_vf.min_x + target_vf.width - final_w;
-        let max_y = target_vf.min_y + target_vf.height - final_h;
+/// Look up a window element by window ID (retained; released when the AxElement drops)
+/// Returns None if the app or window is not found
+pub unsafe fn window_element_by_id(pid: u32, window_id: u32) -> Option<AxElement> {
+    let app = AxElement::from_pid(pid).ok()?;
 
-        if new_x < target_vf.min_x {
-            new_x = target_vf.min_x;
-        } else if new_x > max_x {
-            new_x = max_x.max(target_vf.min_x);
-        }
+    // Query kAXWindowsAttribute
+    let windows_attr = ax_attr_windows();
+    let mut windows_array: CFTypeRef = std::ptr::null();
+    let rc = AXUIElementCopyAttributeValue(
+        app.0,
+        windows_attr.as_concrete_TypeRef() as CFTypeRef,
+        &mut windows_array,
+    );
 
-        if new_y < target_vf.min_y {
-            new_y = target_vf.min_y;
-        } else if new_y > max_y {
-            new_y = max_y.max(target_vf.min_y);
-        }
+    if rc != KAX_ERROR_SUCCESS || windows_array.is_null() {
+        eprintln!("DEBUG: AXWindows query failed for pid={}, error={}", pid, rc);
+        return None;
+    }
 
-        // Apply position first (Position → Size policy)
-        if let Err(e) = win.set_position(new_x, new_y) {
-            let reason = match e {
-                AxError::Permission => "ax_permission_missing_or_revoked",
-                AxError::Platform(code) => {
-                    eprintln!("DEBUG: set_position failed with code {}", code);
-                    "ax_error(op=AXSetPosition)"
-                }
-                _ => "ax_error(op=AXSetPosition)",
-            };
-            eprintln!("DISPLAYMOVE: FAILED target={} | reason={}", target_string, reason);
-            return false;
+    let mut result = None;
+    let count = CFArrayGetCount(windows_array);
+    for i in 0..count {
+        let window_element = CFArrayGetValueAtIndex(windows_array, i) as AXUIElementRef;
+        if window_element.is_null() {
+            continue;
         }
 
-        // Apply size if it changed
-        let size_changed = (final_w - current_rect.w).abs() > 1.0 || (final_h - current_rect.h).abs() > 1.0;
-        if size_changed {
-            if let Err(e) = win.set_size(final_w, final_h) {
-                let reason = match e {
-                    AxError::Permission => "ax_permission_missing_or_revoked",
-                    AxError::Constrained => "size_constrained_or_fullscreen",
-                    AxError::Platform(code) => {
-                        eprintln!("DEBUG: set_size failed with code {}", code);
-                        "ax_error(op=AXSetSize)"
-                    }
-                };
-                eprintln!("DISPLAYMOVE: FAILED target={} | reason={}", target_string, reason);
-                return false;
-            }
+        let mut wid: u32 = 0;
+        let wid_rc = _AXUIElementGetWindow(window_element, &mut wid);
+        if wid_rc == 0 && wid == window_id {
+            // Retain before the array is released so the element outlives it
+            core_foundation::base::CFRetain(window_element as CFTypeRef);
+            result = Some(AxElement(window_element));
+            break;
         }
-
-        // Success - log per spec format
-        println!(
-            "DISPLAYMOVE: SUCCESS target={} | {}",
-            target_string, size_action
-        );
-        eprintln!(
-            "DEBUG: DISPLAYMOVE app=\"{}\" from_display={} to_display={} frame=({:.0},{:.0},{:.0},{:.0})",
-            bundle_id, current_display_index, target_display_index, new_x, new_y, final_w, final_h
-        );
-        true
     }
+
+    CFRelease(windows_array);
+    result
 }
 
 /// Focus a specific window by window ID using AX APIs
@@ -574,11 +522,33 @@ pub unsafe fn enumerate_app_windows(pid: u32) -> Vec<EnumeratedWindow> {
             format!("<win:{}>", window_id)
         };
 
+        // Get frame (best effort) - retain so the RAII wrapper's release stays balanced
+        core_foundation::base::CFRetain(window_element as CFTypeRef);
+        let rect = AxElement(window_element).get_current_rect();
+
+        // Get minimized state (best effort)
+        let minimized_attr = ax_attr_minimized();
+        let mut minimized_ref: CFTypeRef = std::ptr::null();
+        let minimized_rc = AXUIElementCopyAttributeValue(
+            window_element,
+            minimized_attr.as_concrete_TypeRef() as CFTypeRef,
+            &mut minimized_ref,
+        );
+        let minimized = if minimized_rc == KAX_ERROR_SUCCESS && !minimized_ref.is_null() {
+            let is_true = minimized_ref == kCFBooleanTrue;
+            CFRelease(minimized_ref);
+            is_true
+        } else {
+            false
+        };
+
         eprintln!("DEBUG: [enumerate_app_windows] Found window: window_id={} title=\"{}\"", window_id, title);
 
         result.push(EnumeratedWindow {
             window_id,
             title,
+            rect,
+            minimized,
         });
     }
 
@@ -637,26 +607,19 @@ pub fn should_use_observer_on_error(error_msg: &str) -> bool {
     error_msg.contains("ax_cannot_complete_retry_needed")
 }
 
-/// Convert PixelRect to Rect (passthrough - coordinates already absolute)
-///
-/// PixelRect coordinates are produced by DisplayInfo::realize_panes() which:
-/// 1. Calls live_viewport() to get design-based viewport (quirks applied at design time)
-/// 2. Converts fractions to absolute screen coordinates: vf.min_x + f.x * design_width
-///
-/// Therefore PixelRect already contains absolute screen coordinates ready for AX API.
-/// No additional offset or quirk correction should be applied here.
-fn pixel_rect_to_rect(pr: &PixelRect, _vf: &VisibleFrame) -> Rect {
-    Rect {
-        x: pr.x,
-        y: pr.y,
-        w: pr.width,
-        h: pr.height,
+/// Convert VisibleFrame to PixelRect (same global coordinate space)
+pub fn visible_frame_to_pixel_rect(vf: &VisibleFrame) -> PixelRect {
+    PixelRect {
+        x: vf.min_x,
+        y: vf.min_y,
+        width: vf.width,
+        height: vf.height,
     }
 }
 
 /// Determine which display index contains the given window rect (by center point)
 /// Uses symmetric viewport bounds for consistency with display move operations
-unsafe fn get_display_index_for_window(window_rect: Rect) -> usize {
+pub unsafe fn get_display_index_for_window(window_rect: Rect) -> usize {
     let screens = get_all_screens();
     let win_center_x = window_rect.x + window_rect.w / 2.0;
     let win_center_y = window_rect.y + window_rect.h / 2.0;
@@ -673,87 +636,17 @@ unsafe fn get_display_index_for_window(window_rect: Rect) -> usize {
     0 // Fallback to main display
 }
 
-// Safe wrapper to set window rect using RAII and Position → Size policy with debug logging
-pub fn set_window_rect_safe(win: &AxElement, r: Rect) -> Result<(), String> {
-    unsafe {
-        eprintln!("DEBUG: Using Position→Size policy (global experiment)");
-        eprintln!("DEBUG: set_window_rect_safe() target: ({:.0},{:.0},{:.0},{:.0})", r.x, r.y, r.w, r.h);
-
-        // Get current rect before any changes
-        if let Some(before_rect) = win.get_current_rect() {
-            eprintln!("DEBUG: Before resize - current rect: ({:.0},{:.0},{:.0},{:.0})",
-                     before_rect.x, before_rect.y, before_rect.w, before_rect.h);
-        } else {
-            eprintln!("DEBUG: Before resize - could not get current rect");
-        }
-
-        // Policy: Position first, then size (Chrome-safe practice)
-        eprintln!("DEBUG: Setting position to ({:.0},{:.0})", r.x, r.y);
-        win.set_position(r.x, r.y).map_err(|e| {
-            let msg = match e {
-                AxError::Permission => {
-                    eprintln!("DEBUG: AXPosition failed: Permission denied (code -25204)");
-                    "ax_permission_missing_or_revoked".to_string()
-                }
-                AxError::Platform(code) => {
-                    eprintln!("DEBUG: AXPosition failed: AX error code {}", code);
-                    format!("ax_error(code={}, op=AXPosition)", code)
-                }
-                _ => {
-                    eprintln!("DEBUG: AXPosition failed: {:?}", e);
-                    "ax_error(code=-1, op=AXPosition)".to_string()
-                }
-            };
-            msg
-        })?;
-
-        // Check rect after position change
-        if let Some(after_pos_rect) = win.get_current_rect() {
-            eprintln!("DEBUG: After position change - current rect: ({:.0},{:.0},{:.0},{:.0})",
-                     after_pos_rect.x, after_pos_rect.y, after_pos_rect.w, after_pos_rect.h);
-        } else {
-            eprintln!("DEBUG: After position change - could not get current rect");
-        }
-
-        eprintln!("DEBUG: Setting size to ({:.0},{:.0})", r.w, r.h);
-        win.set_size(r.w, r.h).map_err(|e| {
-            let msg = match e {
-                AxError::Permission => {
-                    eprintln!("DEBUG: AXSize failed: Permission denied (code -25204)");
-                    "ax_permission_missing_or_revoked".to_string()
-                }
-                AxError::Constrained => {
-                    eprintln!("DEBUG: AXSize failed: Size constrained or window is fullscreen");
-                    "size_constrained_or_fullscreen".to_string()
-                }
-                AxError::Platform(code) => {
-                    eprintln!("DEBUG: AXSize failed: AX error code {}", code);
-                    format!("ax_error(code={}, op=AXSize)", code)
-                }
-            };
-            msg
-        })?;
-
-        // Get final rect after both changes
-        if let Some(final_rect) = win.get_current_rect() {
-            eprintln!("DEBUG: Final rect after size change: ({:.0},{:.0},{:.0},{:.0})",
-                     final_rect.x, final_rect.y, final_rect.w, final_rect.h);
-
-            // Check if the final rect matches what we requested
-            let pos_matches = (final_rect.x - r.x).abs() < 1.0 && (final_rect.y - r.y).abs() < 1.0;
-            let size_matches = (final_rect.w - r.w).abs() < 1.0 && (final_rect.h - r.h).abs() < 1.0;
-
-            if !pos_matches || !size_matches {
-                eprintln!("DEBUG: Final rect does not exactly match target (app constraints likely)");
-            } else {
-                eprintln!("DEBUG: SUCCESS - Final rect matches target");
-            }
-        } else {
-            eprintln!("DEBUG: Final rect - could not get current rect");
-        }
+/// Get the symmetric viewport of the display containing the window (by center point)
+pub unsafe fn viewport_for_window(window_rect: Rect) -> Option<VisibleFrame> {
+    let screens = get_all_screens();
+    let display_index = get_display_index_for_window(window_rect);
+    screens.get(display_index)
+        .and_then(|screen| symmetric_viewport_for_index(screen, display_index))
+}
 
-        Ok(())
-    }
+// Set window rect with the Position → Size policy (see pbgw_window::set_window_rect)
+pub fn set_window_rect_safe(win: &AxElement, r: Rect) -> Result<(), String> {
+    set_window_rect(&AxBackend, win, &PixelRect { x: r.x, y: r.y, width: r.w, height: r.h })
 }
 
 // Cleanup helper for observer resources
@@ -819,68 +712,61 @@ pub extern "C" fn ax_observer_callback(
     unsafe {
         let tag = guard.job.key_name.as_deref().unwrap_or("UNKNOWN");
 
-        // Get the display frame for the window
-        let win_elem = AxElement(element);
-        let current_rect = win_elem.get_current_rect();
-        std::mem::forget(win_elem); // prevent CFRelease
-
-        let (vf, display_index) = if let Some(rect) = current_rect {
-            let disp_idx = get_display_index_for_window(rect);
-            let screens = get_all_screens();
-            let visible = if disp_idx < screens.len() {
-                symmetric_viewport_for_index(&screens[disp_idx], disp_idx).unwrap_or_else(|| {
-                    visible_frame_main_display().expect("Main display should exist")
-                })
-            } else {
-                visible_frame_main_display().expect("Main display should exist")
-            };
-            (visible, disp_idx)
-        } else {
-            let screens = get_all_screens();
-            let visible = if !screens.is_empty() {
-                symmetric_viewport_for_index(&screens[0], 0).expect("Main display should exist")
-            } else {
-                visible_frame_main_display().expect("Main display should exist")
-            };
-            (visible, 0)
-        };
-
-        // Look up pane from Form and convert to pixels
+        // The notification element is borrowed: forget the wrapper instead of releasing it
+        let win = AxElement(element);
+        let display_index = win.get_current_rect().map_or(0, |rect| get_display_index_for_window(rect));
         let key = guard.job.key_name.as_deref().unwrap_or("UNKNOWN");
-
-        if let Some(display_info) = ADJUSTED_DISPLAYS.get(display_index) {
-            let display_props = display_info.as_props();
-            let mut form = FORM.lock().unwrap();
-            let pane_result = form.get_next_pane(key, &display_props);
-            drop(form);
-
-            if let Some((frac_pane, pane_idx)) = pane_result {
-                // Convert fractional pane to pixels and filter small panes
-                let pixel_rects = display_info.realize_panes(&[frac_pane]);
-                let filtered = crate::pbmbd_display::DisplayInfo::filter_small(&pixel_rects);
-                if let Some(pixel_rect) = filtered.first() {
-                    let r = pixel_rect_to_rect(pixel_rect, &vf);
-                    let win = AxElement(element);
-                    match set_window_rect_safe(&win, r) {
-                        Ok(()) => println!("TILE: {tag} | SUCCESS after_observer=yes | key={} pane={} app=\"{}\"", key, pane_idx, guard.job.frontmost.bundle_id),
-                        Err(e) => println!("TILE: {tag} | FAILED reason={}", e),
-                    }
-                    std::mem::forget(win); // prevent CFRelease
-                } else {
-                    println!("TILE: {tag} | FAILED reason=pane_too_small key={}", key);
-                }
-            } else {
-                println!("TILE: {tag} | FAILED reason=no_pane_for_key key={}", key);
-            }
-        } else {
-            println!("TILE: {tag} | FAILED reason=no_display_info");
+        let bundle_id = &guard.job.frontmost.bundle_id;
+
+        match tile_display(display_index) {
+            Ok(display) => match tile_next_pane(&AxBackend, &FORM, &win, key, &display, bundle_id) {
+                Ok((pane_idx, None)) => println!("TILE: {tag} | SUCCESS after_observer=yes | key={} pane={} app=\"{}\"", key, pane_idx, bundle_id),
+                Ok((pane_idx, Some(fallback))) => println!("TILE: {tag} | SUCCESS after_observer=yes fallback={} | key={} pane={} app=\"{}\"", fallback, key, pane_idx, bundle_id),
+                Err(e) if e == "no_pane_for_key" => println!("TILE: {tag} | FAILED reason=no_pane_for_key key={}", key),
+                Err(e) => println!("TILE: {tag} | FAILED reason={}", e),
+            },
+            Err(reason) => println!("TILE: {tag} | FAILED reason={}", reason),
         }
+        std::mem::forget(win); // prevent CFRelease
 
         guard.active = false;
         cleanup_locked(&mut *guard);
     }
 }
 
+/// Display index used to tile a window with the given current rect
+/// Falls back to the main display when the rect is unavailable or off-screen
+unsafe fn tiling_display_index(current_rect: Option<Rect>) -> usize {
+    let rect = match current_rect {
+        Some(r) => r,
+        None => return 0,
+    };
+
+    match get_display_for_window_with_validation(rect) {
+        Some((_visible, _full, delta_y)) => {
+            // One-time warning if delta_y != 0
+            if delta_y != 0.0 && !VISIBLE_FRAME_WARNING_SHOWN.swap(true, Ordering::Relaxed) {
+                eprintln!("NOTE: visibleFrame correction applied (menu bar height: {:.0}px)", delta_y);
+            }
+            get_display_index_for_window(rect)
+        }
+        None => 0,
+    }
+}
+
+/// Tiling view of a display: quirk-adjusted design size plus its live symmetric viewport
+unsafe fn tile_display(display_index: usize) -> Result<TileDisplay, &'static str> {
+    let displays = adjusted_displays();
+    let display_info = displays.get(display_index).ok_or("no_display_info")?;
+    let viewport = get_all_screens().get(display_index)
+        .and_then(|screen| display_info.live_viewport(screen))
+        .ok_or("no_visible_frame")?;
+    Ok(TileDisplay {
+        props: display_info.as_props(),
+        viewport: visible_frame_to_pixel_rect(&viewport),
+    })
+}
+
 // Tile window using job struct
 pub fn tile_window_quadrant(job: TilingJob) {
     let tag = job.key_name.as_deref().unwrap_or("UNKNOWN");
@@ -888,7 +774,7 @@ pub fn tile_window_quadrant(job: TilingJob) {
     eprintln!("DEBUG: tile_window_quadrant(key={}) for pid={}, app=\"{}\"", tag, job.frontmost.pid, job.frontmost.bundle_id);
 
     // Get focused window using PID-targeted approach
-    let win = match unsafe { get_focused_window_by_pid(job.frontmost.pid) } {
+    let win = match AxBackend.focused_window(job.frontmost.pid) {
         Ok(w) => w,
         Err(reason) => {
             // Check if we should use observer approach
@@ -903,98 +789,45 @@ pub fn tile_window_quadrant(job: TilingJob) {
     };
 
     unsafe {
-        // Get the display frame for the window with validation
-        let current_rect = win.get_current_rect();
-        let (vf, display_index) = if let Some(rect) = current_rect {
-            // Determine display index first
-            let disp_idx = get_display_index_for_window(rect);
-
-            // Use validation version to get both visible and full frames
-            if let Some((mut visible, _full, delta_y)) = get_display_for_window_with_validation(rect) {
-                // One-time warning if delta_y != 0
-                if delta_y != 0.0 && !VISIBLE_FRAME_WARNING_SHOWN.swap(true, Ordering::Relaxed) {
-                    eprintln!("NOTE: visibleFrame correction applied (menu bar height: {:.0}px)", delta_y);
-                }
-
-                // Apply quirks via DisplayInfo
-                let screens = get_all_screens();
-                if disp_idx < screens.len() {
-                    if let Some(display_info) = ADJUSTED_DISPLAYS.get(disp_idx) {
-                        // Replace visible frame with design-based viewport from DisplayInfo
-                        if let Some(design_vf) = display_info.live_viewport(&screens[disp_idx]) {
-                            visible = design_vf;
-                        }
-                    }
-                }
-
-                (visible, disp_idx)
-            } else {
-                let screens = get_all_screens();
-                let visible = if !screens.is_empty() {
-                    symmetric_viewport_for_index(&screens[0], 0).unwrap_or_else(|| {
-                        visible_frame_main_display().expect("Main display should exist")
-                    })
-                } else {
-                    visible_frame_main_display().expect("Main display should exist")
-                };
-                (visible, 0)
+        let display_index = tiling_display_index(win.get_current_rect());
+        let display = match tile_display(display_index) {
+            Ok(d) => d,
+            Err(reason) => {
+                println!("TILE: {} | FAILED reason={}", tag, reason);
+                return;
             }
-        } else {
-            let screens = get_all_screens();
-            let visible = if !screens.is_empty() {
-                symmetric_viewport_for_index(&screens[0], 0).unwrap_or_else(|| {
-                    visible_frame_main_display().expect("Main display should exist")
-                })
-            } else {
-                visible_frame_main_display().expect("Main display should exist")
-            };
-            (visible, 0)
         };
 
-        // Look up pane from Form and convert to pixels
         let key = job.key_name.as_deref().unwrap_or("UNKNOWN");
-
-        if let Some(display_info) = ADJUSTED_DISPLAYS.get(display_index) {
-            let display_props = display_info.as_props();
-            let mut form = FORM.lock().unwrap();
-            let pane_result = form.get_next_pane(key, &display_props);
-            drop(form);
-
-            if let Some((frac_pane, pane_idx)) = pane_result {
-                // Convert fractional pane to pixels and filter small panes
-                let pixel_rects = display_info.realize_panes(&[frac_pane]);
-                let filtered = crate::pbmbd_display::DisplayInfo::filter_small(&pixel_rects);
-                if let Some(pixel_rect) = filtered.first() {
-                    let r = pixel_rect_to_rect(pixel_rect, &vf);
-                    match set_window_rect_safe(&win, r) {
-                        Ok(()) => println!("TILE: {} | SUCCESS | key={} pane={} app=\"{}\"", tag, key, pane_idx, job.frontmost.bundle_id),
-                        Err(reason) => {
-                            // Check if we should retry with observer
-                            if reason.contains("not_ready") || reason.contains("cannot_complete") {
-                                if job.attempt == 0 {
-                                    eprintln!("DEBUG: Will use AXObserver approach due to: {}", reason);
-                                    tile_window_with_observer(job);
-                                } else {
-                                    println!("TILE: {} | FAILED reason={}", tag, reason);
-                                }
-                            } else {
-                                println!("TILE: {} | FAILED reason={}", tag, reason);
-                            }
-                        }
-                    }
-                } else {
-                    println!("TILE: {} | FAILED reason=pane_too_small", tag);
-                }
-            } else {
+        match tile_next_pane(&AxBackend, &FORM, &win, key, &display, &job.frontmost.bundle_id) {
+            Ok((pane_idx, None)) => println!("TILE: {} | SUCCESS | key={} pane={} app=\"{}\"", tag, key, pane_idx, job.frontmost.bundle_id),
+            Ok((pane_idx, Some(fallback))) => println!("TILE: {} | SUCCESS fallback={} | key={} pane={} app=\"{}\"", tag, fallback, key, pane_idx, job.frontmost.bundle_id),
+            Err(reason) if reason == "no_pane_for_key" => {
                 eprintln!("LAYOUT: no panes available for key={} on display={}", key, display_index);
                 println!("TILE: {} | FAILED reason=no_pane_for_key", tag);
             }
-        } else {
-            println!("TILE: {} | FAILED reason=no_display_info", tag);
+            Err(reason) => {
+                // Check if we should retry with observer
+                if (reason.contains("not_ready") || reason.contains("cannot_complete")) && job.attempt == 0 {
+                    eprintln!("DEBUG: Will use AXObserver approach due to: {}", reason);
+                    tile_window_with_observer(job);
+                } else {
+                    println!("TILE: {} | FAILED reason={}", tag, reason);
+                }
+            }
         }
     }
 }
 
+/// Tile the focused window into a fixed pane of a binding's cycle (Macro LayoutAction step)
+/// Synchronous: no observer retry and no session advance; pane is a 0-based cycle index.
+/// Returns the fallback applied (if any), or the failure reason.
+pub unsafe fn tile_to_pane(binding: &str, pane: usize, frontmost: &FrontmostInfo) -> Result<Option<String>, String> {
+    let win = AxBackend.focused_window(frontmost.pid)?;
+    let display = tile_display(tiling_display_index(win.get_current_rect()))?;
+    tile_fixed_pane(&AxBackend, &FORM, &win, binding, pane, &display, &frontmost.bundle_id)
+}
+
 // Tile window using AXObserver approach
 unsafe fn tile_window_with_observer(job: TilingJob) {
     eprintln!("DEBUG: tile_window_with_observer() starting for app={}", job.frontmost.bundle_id);
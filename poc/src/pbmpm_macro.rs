@@ -0,0 +1,262 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+#![cfg(target_os = "macos")]
+
+//! Action Macros
+//! Runs a <Macro> binding: its steps execute in order against whatever window
+//! is focused when each step starts (a FocusMove/FocusMru step retargets the rest).
+//!
+//! The event tap only schedules the macro and returns. Steps then run from
+//! one-shot timers on the main run loop, one step or one poll per tick, so the
+//! run loop (and the tap) keeps servicing events between them. Before the next
+//! step reads window state, the runner polls (bounded) for the step's result to
+//! land: the focused window's frame to stop changing after a geometry step, or
+//! focus to move after a focus step. Failure handling follows the Macro's
+//! onFailure policy: "stop" aborts the remaining steps, "continue" runs them
+//! all and reports which ones failed. One macro runs at a time.
+
+use std::ffi::c_void;
+use std::sync::Mutex;
+
+use core_foundation::base::{kCFAllocatorDefault, CFRelease};
+use core_foundation::runloop::kCFRunLoopDefaultMode;
+use core_foundation_sys::base::CFTypeRef;
+use lazy_static::lazy_static;
+
+use crate::pbgft_types::{MacroFailurePolicy, MacroSpec, MacroStep};
+use crate::pbmba_ax::{
+    get_frontmost_app_info, CFAbsoluteTimeGetCurrent, CFRunLoopAddTimer, CFRunLoopGetMain,
+    CFRunLoopTimerContext, CFRunLoopTimerCreate, FrontmostInfo,
+};
+use crate::pbmbd_display::Rect;
+use crate::pbmp_pane::{execute_display_move_for_key, get_focused_window_by_pid, tile_to_pane};
+
+/// Poll interval and bound while waiting for a step's AX result
+const SETTLE_POLL_MS: u64 = 10;
+const SETTLE_MAX_POLLS: u32 = 20;
+
+/// What the running macro waits for before its next step
+enum Wait {
+    Nothing,
+    Settle { last: Option<Rect> },              // Focused window frame to read the same twice
+    Focus { previous: FrontmostInfo, focused_before: Option<u32> },
+}
+
+/// State of the macro in progress (lives between run loop ticks)
+struct MacroRun {
+    key: String,
+    spec: MacroSpec,
+    frontmost: FrontmostInfo,
+    next_step: usize,
+    failed: Vec<usize>,
+    wait: Wait,
+    polls: u32,
+}
+
+lazy_static! {
+    static ref ACTIVE_MACRO: Mutex<Option<MacroRun>> = Mutex::new(None);
+}
+
+/// Start a Macro bound to `key`; its steps run from the main run loop
+/// Returns true if the macro was scheduled, false if another one is still running
+pub unsafe fn run_macro(key: &str, spec: &MacroSpec, frontmost: FrontmostInfo) -> bool {
+    let mut active = ACTIVE_MACRO.lock().unwrap();
+    if let Some(running) = active.as_ref() {
+        println!("MACRO: key={} | FAILED reason=macro_running key={}", key, running.key);
+        return false;
+    }
+    *active = Some(MacroRun {
+        key: key.to_string(),
+        spec: spec.clone(),
+        frontmost,
+        next_step: 0,
+        failed: Vec::new(),
+        wait: Wait::Nothing,
+        polls: 0,
+    });
+    drop(active);
+
+    schedule_tick(0);
+    true
+}
+
+/// Fire macro_tick once on the main run loop after `delay_ms`
+unsafe fn schedule_tick(delay_ms: u64) {
+    let context = CFRunLoopTimerContext {
+        version: 0,
+        info: std::ptr::null_mut(),
+        retain: None,
+        release: None,
+        copy_description: None,
+    };
+    let timer = CFRunLoopTimerCreate(
+        kCFAllocatorDefault,
+        CFAbsoluteTimeGetCurrent() + delay_ms as f64 / 1000.0,
+        0.0,   // One-shot: invalidated by the run loop after firing
+        0,
+        0,
+        macro_tick,
+        &context,
+    );
+    if timer.is_null() {
+        eprintln!("MACRO: FAILED reason=timer_create_failed");
+        *ACTIVE_MACRO.lock().unwrap() = None;
+        return;
+    }
+    CFRunLoopAddTimer(CFRunLoopGetMain(), timer, kCFRunLoopDefaultMode as CFTypeRef);
+    CFRelease(timer as CFTypeRef);   // The run loop holds it until it fires
+}
+
+/// One tick: poll the pending wait, or run the next step, then reschedule
+extern "C" fn macro_tick(_timer: *mut c_void, _info: *mut c_void) {
+    unsafe {
+        let mut active = ACTIVE_MACRO.lock().unwrap();
+        let run = match active.as_mut() {
+            Some(run) => run,
+            None => return,
+        };
+
+        if !poll_wait(run) {
+            drop(active);
+            schedule_tick(SETTLE_POLL_MS);
+            return;
+        }
+
+        if run.next_step == run.spec.steps.len() {
+            finish(run);
+            *active = None;
+            return;
+        }
+
+        if run_next_step(run) {
+            // Give the step's result a poll interval to land before checking it
+            let delay = if matches!(run.wait, Wait::Nothing) { 0 } else { SETTLE_POLL_MS };
+            drop(active);
+            schedule_tick(delay);
+        } else {
+            *active = None;   // Stopped by onFailure="stop"
+        }
+    }
+}
+
+/// Run the next step and set up the wait for its result
+/// Returns false if the macro stops here
+unsafe fn run_next_step(run: &mut MacroRun) -> bool {
+    let total = run.spec.steps.len();
+    let n = run.next_step + 1;
+    let step = run.spec.steps[run.next_step].clone();
+    run.next_step += 1;
+
+    let focused_before = focused_window_id(run.frontmost.pid);
+    match run_step(&step, &run.frontmost) {
+        Ok(None) => eprintln!("MACRO: key={} step={}/{} {} | SUCCESS", run.key, n, total, step.name()),
+        Ok(Some(fallback)) => eprintln!("MACRO: key={} step={}/{} {} | SUCCESS fallback={}",
+            run.key, n, total, step.name(), fallback),
+        Err(reason) => {
+            println!("MACRO: key={} step={}/{} {} | FAILED reason={}", run.key, n, total, step.name(), reason);
+            run.failed.push(n);
+            if run.spec.on_failure == MacroFailurePolicy::Stop {
+                println!("MACRO: key={} | FAILED stopped_at={}/{} on_failure={}",
+                    run.key, n, total, run.spec.on_failure.as_str());
+                return false;
+            }
+            return true;
+        }
+    }
+
+    // Wait for this step's result before the next step reads window state
+    run.polls = 0;
+    run.wait = match step {
+        MacroStep::FocusMove(_) | MacroStep::FocusMru => Wait::Focus { previous: run.frontmost.clone(), focused_before },
+        _ => Wait::Settle { last: focused_rect(run.frontmost.pid) },
+    };
+    true
+}
+
+/// Report the outcome once every step has run
+fn finish(run: &MacroRun) {
+    let total = run.spec.steps.len();
+    if run.failed.is_empty() {
+        println!("MACRO: key={} | SUCCESS steps={}", run.key, total);
+    } else {
+        let list: Vec<String> = run.failed.iter().map(|n| n.to_string()).collect();
+        println!("MACRO: key={} | PARTIAL failed_steps={} of {} on_failure={}",
+            run.key, list.join(","), total, run.spec.on_failure.as_str());
+    }
+}
+
+/// Execute one step against the current frontmost app's focused window
+/// Ok carries the TileFallback applied by a LayoutAction step, if any
+unsafe fn run_step(step: &MacroStep, frontmost: &FrontmostInfo) -> Result<Option<String>, String> {
+    let ok = match step {
+        MacroStep::LayoutAction { binding, pane } => return tile_to_pane(binding, *pane, frontmost),
+        MacroStep::DisplayMove { binding } => execute_display_move_for_key(binding, frontmost.pid, &frontmost.bundle_id),
+        MacroStep::FocusMove(direction) => crate::pbmsf_focus::focus_in_direction(*direction, frontmost),
+        MacroStep::FocusMru => crate::pbmsf_focus::focus_mru(frontmost),
+        MacroStep::SwapAction(target) => crate::pbmps_swap::swap_with_neighbor(*target, frontmost),
+        MacroStep::Nudge(spec) => crate::pbmpn_nudge::nudge_focused_window(*spec, frontmost.pid),
+        MacroStep::Resize(spec) => crate::pbmpn_nudge::resize_focused_window(*spec, frontmost.pid),
+    };
+
+    // Details were already logged by the action itself
+    if ok { Ok(None) } else { Err("action_failed".to_string()) }
+}
+
+unsafe fn focused_window_id(pid: u32) -> Option<u32> {
+    get_focused_window_by_pid(pid).ok().and_then(|w| w.get_window_id())
+}
+
+unsafe fn focused_rect(pid: u32) -> Option<Rect> {
+    get_focused_window_by_pid(pid).ok().and_then(|w| w.get_current_rect())
+}
+
+fn rects_match(a: &Option<Rect>, b: &Option<Rect>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => (a.x - b.x).abs() < 1.0 && (a.y - b.y).abs() < 1.0
+            && (a.w - b.w).abs() < 1.0 && (a.h - b.h).abs() < 1.0,
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Check the pending wait once; true when the next step may run
+/// Settle: the focused window's frame reads the same on consecutive polls.
+/// Focus: focus has left the window focused before the step (the remaining
+/// steps then target the new frontmost app).
+unsafe fn poll_wait(run: &mut MacroRun) -> bool {
+    let timed_out = run.polls >= SETTLE_MAX_POLLS;
+    run.polls += 1;
+
+    let done = match &mut run.wait {
+        Wait::Nothing => true,
+        Wait::Settle { last } => {
+            let now = focused_rect(run.frontmost.pid);
+            let settled = rects_match(&now, last);
+            *last = now;
+            if !settled && timed_out {
+                eprintln!("MACRO: window still moving after {}ms, continuing", SETTLE_POLL_MS * SETTLE_MAX_POLLS as u64);
+            }
+            settled
+        }
+        Wait::Focus { previous, focused_before } => match get_frontmost_app_info() {
+            Some(current) if current.pid != previous.pid || focused_window_id(current.pid) != *focused_before => {
+                run.frontmost = current;
+                true
+            }
+            _ => {
+                if timed_out {
+                    eprintln!("MACRO: focus change not observed after {}ms, continuing", SETTLE_POLL_MS * SETTLE_MAX_POLLS as u64);
+                    run.frontmost = get_frontmost_app_info().unwrap_or_else(|| previous.clone());
+                }
+                false
+            }
+        },
+    };
+
+    if done || timed_out {
+        run.wait = Wait::Nothing;
+        return true;
+    }
+    false
+}
@@ -0,0 +1,37 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+#![cfg(target_os = "macos")]
+
+//! Incremental Nudge and Resize
+//! Moves the focused window, or one of its edges, by a fraction of the display
+//! so steps feel the same on every monitor. Bindings fire on autorepeat.
+//!
+//! Steps are clamped to the symmetric viewport (DisplayInfo::live_viewport)
+//! of the display containing the window. The step itself is platform-neutral
+//! (pbgw_window::nudge_focused_window / resize_focused_window); this module
+//! supplies the AX backend and the viewport lookup.
+
+use crate::pbgft_types::{NudgeSpec, PixelRect, ResizeSpec};
+use crate::pbgw_window;
+use crate::pbmbd_display::Rect;
+use crate::pbmbw_window::AxBackend;
+use crate::pbmp_pane::{viewport_for_window, visible_frame_to_pixel_rect};
+
+/// Symmetric viewport of the display containing a window rect
+unsafe fn viewport_for(r: &PixelRect) -> Option<PixelRect> {
+    viewport_for_window(Rect { x: r.x, y: r.y, w: r.width, h: r.height })
+        .map(|vf| visible_frame_to_pixel_rect(&vf))
+}
+
+/// Move the focused window by the bound step
+/// Returns true if the window moved, false if already at the viewport edge or on failure
+pub unsafe fn nudge_focused_window(spec: NudgeSpec, pid: u32) -> bool {
+    pbgw_window::nudge_focused_window(&AxBackend, pid, spec, |r| viewport_for(r))
+}
+
+/// Move one edge of the focused window by the bound step
+/// Returns true if the window changed, false if at a limit or on failure
+pub unsafe fn resize_focused_window(spec: ResizeSpec, pid: u32) -> bool {
+    pbgw_window::resize_focused_window(&AxBackend, pid, spec, |r| viewport_for(r))
+}
@@ -0,0 +1,140 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+#![cfg(target_os = "macos")]
+
+//! Window Swap
+//! Exchanges the focused window's rect with a partner window: its spatial
+//! neighbor in a direction, or the previously focused (MRU) window.
+//!
+//! Both moves go through set_window_rect_safe. If either move fails outright,
+//! both windows are restored to their starting rects. If both moves succeed but
+//! an app enforces its own size limits, the swap is kept and reported as partial
+//! unless the clamped windows now overlap, in which case it is rolled back too.
+
+use crate::pbgft_types::{PixelRect, SwapTarget};
+use crate::pbgg_geometry::{rects_overlap, window_in_direction};
+use crate::pbmba_ax::{AxElement, FrontmostInfo};
+use crate::pbmbd_display::Rect;
+use crate::pbmp_pane::{get_focused_window_by_pid, set_window_rect_safe, window_element_by_id};
+use crate::pbmsf_focus::{gather_window_candidates, most_recent_candidate};
+
+/// True if the window's current rect matches the requested one (within 1px)
+fn rect_matches(current: Option<&Rect>, r: &Rect) -> bool {
+    match current {
+        Some(c) => (c.x - r.x).abs() < 1.0 && (c.y - r.y).abs() < 1.0
+            && (c.w - r.w).abs() < 1.0 && (c.h - r.h).abs() < 1.0,
+        None => false,
+    }
+}
+
+fn to_pixel_rect(r: &Rect) -> PixelRect {
+    PixelRect { x: r.x, y: r.y, width: r.w, height: r.h }
+}
+
+/// Restore both windows to their starting rects (best effort, logged)
+unsafe fn rollback(focused: &AxElement, focused_rect: Rect, partner: &AxElement, partner_rect: Rect) {
+    if let Err(e) = set_window_rect_safe(focused, focused_rect) {
+        eprintln!("SWAP: rollback of focused window failed: {}", e);
+    }
+    if let Err(e) = set_window_rect_safe(partner, partner_rect) {
+        eprintln!("SWAP: rollback of partner window failed: {}", e);
+    }
+}
+
+/// Swap the focused window's rect with the partner selected by `target`
+/// Returns true if both windows moved, false if no partner or the swap was rolled back
+pub unsafe fn swap_with_neighbor(target: SwapTarget, frontmost: &FrontmostInfo) -> bool {
+    let label = target.as_str();
+
+    let focused = match get_focused_window_by_pid(frontmost.pid) {
+        Ok(w) => w,
+        Err(reason) => {
+            eprintln!("SWAP: target={} | FAILED reason={}", label, reason);
+            return false;
+        }
+    };
+    let focused_rect = match focused.get_current_rect() {
+        Some(r) => r,
+        None => {
+            eprintln!("SWAP: target={} | FAILED reason=cannot_get_current_rect", label);
+            return false;
+        }
+    };
+    let focused_window_id = focused.get_window_id().unwrap_or(0);
+
+    // Select partner from the same candidate set directional focus uses
+    let (candidates, entries) = gather_window_candidates(frontmost, focused_window_id);
+    let partner_idx = match target {
+        SwapTarget::Direction(direction) => {
+            window_in_direction(&to_pixel_rect(&focused_rect), &candidates, direction)
+        }
+        SwapTarget::Mru => most_recent_candidate(&candidates),
+    };
+
+    let partner_idx = match partner_idx {
+        Some(idx) => idx,
+        None => {
+            eprintln!("SWAP: target={} | no partner window ({} candidates)", label, candidates.len());
+            return false;
+        }
+    };
+    let partner_entry = &entries[partner_idx];
+    let partner_pr = &candidates[partner_idx].0;
+    let partner_rect = Rect { x: partner_pr.x, y: partner_pr.y, w: partner_pr.width, h: partner_pr.height };
+
+    let partner = match window_element_by_id(partner_entry.identity.pid, partner_entry.identity.window_id) {
+        Some(w) => w,
+        None => {
+            eprintln!("SWAP: target={} | FAILED reason=partner_window_gone app=\"{}\"", label, partner_entry.bundle_id);
+            return false;
+        }
+    };
+
+    // Move focused window into partner's rect, then partner into focused's rect
+    if let Err(reason) = set_window_rect_safe(&focused, partner_rect) {
+        eprintln!("SWAP: target={} | FAILED reason={} (focused window), rolling back", label, reason);
+        rollback(&focused, focused_rect, &partner, partner_rect);
+        return false;
+    }
+    if let Err(reason) = set_window_rect_safe(&partner, focused_rect) {
+        eprintln!("SWAP: target={} | FAILED reason={} (partner app=\"{}\"), rolling back",
+            label, reason, partner_entry.bundle_id);
+        rollback(&focused, focused_rect, &partner, partner_rect);
+        return false;
+    }
+
+    // Both moves accepted; apps may still have clamped their own sizes
+    let focused_now = focused.get_current_rect();
+    let partner_now = partner.get_current_rect();
+    let focused_exact = rect_matches(focused_now.as_ref(), &partner_rect);
+    let partner_exact = rect_matches(partner_now.as_ref(), &focused_rect);
+
+    // A clamped window that now covers its partner is worse than no swap
+    if !(focused_exact && partner_exact) {
+        let overlapping = match (&focused_now, &partner_now) {
+            (Some(f), Some(p)) => rects_overlap(&to_pixel_rect(f), &to_pixel_rect(p)),
+            _ => true,   // Cannot verify the result
+        };
+        if overlapping {
+            eprintln!("SWAP: target={} | FAILED reason=app_constraints_overlap (partner app=\"{}\"), rolling back",
+                label, partner_entry.bundle_id);
+            rollback(&focused, focused_rect, &partner, partner_rect);
+            return false;
+        }
+    }
+
+    if focused_exact && partner_exact {
+        println!("SWAP: target={} | SUCCESS app=\"{}\" with=\"{}\" win=\"{}\"",
+            label, frontmost.bundle_id, partner_entry.bundle_id, partner_entry.title);
+    } else {
+        let constrained = match (focused_exact, partner_exact) {
+            (false, false) => "both",
+            (false, true) => "focused",
+            _ => "partner",
+        };
+        println!("SWAP: target={} | SUCCESS (partial: app constraints on {}) app=\"{}\" with=\"{}\" win=\"{}\"",
+            label, constrained, frontmost.bundle_id, partner_entry.bundle_id, partner_entry.title);
+    }
+    true
+}
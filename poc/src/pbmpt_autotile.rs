@@ -0,0 +1,185 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+#![cfg(target_os = "macos")]
+
+//! Automatic Tiling (macOS glue)
+//! Feeds AX window events into the platform-neutral tiling engine and realizes
+//! its arrangement on displays whose Space enables <AutoTile>.
+//!
+//! Each notification reconciles the app's current window list against the
+//! engine: new windows are added, vanished or minimized windows are removed.
+//! Destroyed and minimized notifications reconcile the app that owned the
+//! window, so its slot reflows even when focus moves to another app.
+
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+
+use crate::pbgt_tiling::{TiledWindow, TilingEngine, TilingEvent};
+use crate::pbmba_ax::get_focused_window_info;
+use crate::pbmp_pane::{
+    auto_tile_configs, enumerate_app_windows, get_display_index_for_window,
+    realize_frac_on_display, set_window_rect_safe, window_element_by_id,
+};
+use crate::pbmsm_mru::get_mru_snapshot;
+
+lazy_static! {
+    static ref ENGINE: Mutex<TilingEngine> = Mutex::new(TilingEngine::new());
+}
+
+/// Configure tiled displays from the Form and adopt existing windows
+/// Called at startup, after MRU prepopulation, and after a display change
+/// (the engine starts over, since its display indices may now name other screens)
+pub unsafe fn init_auto_tiling() {
+    let configs = auto_tile_configs();
+    {
+        let mut engine = ENGINE.lock().unwrap();
+        *engine = TilingEngine::new();
+        for (display, config) in configs.iter().enumerate() {
+            if let Some(config) = config {
+                eprintln!("AUTOTILE: display={} enabled mode={:?} ratio={}/{}",
+                    display, config.mode, config.ratio.num, config.ratio.den);
+            }
+            engine.configure(display, *config);
+        }
+        if !engine.is_enabled() {
+            eprintln!("AUTOTILE: disabled (no matching Space with <AutoTile>)");
+            return;
+        }
+    }
+
+    // Adopt windows of every known app
+    let mut pids: Vec<u32> = Vec::new();
+    for entry in get_mru_snapshot() {
+        if !pids.contains(&entry.identity.pid) {
+            pids.push(entry.identity.pid);
+        }
+    }
+    for pid in pids {
+        reconcile_app(pid);
+    }
+}
+
+/// Sync the engine with an app's current windows and reflow changed displays
+/// Called from AX window notifications (created, focus changed, destroyed, minimized)
+/// and app activation
+pub unsafe fn reconcile_app(pid: u32) {
+    if !ENGINE.lock().unwrap().is_enabled() {
+        return;
+    }
+
+    // Query AX without holding the engine lock (these are cross-process round trips)
+    let current: Vec<(TiledWindow, usize)> = enumerate_app_windows(pid).into_iter()
+        .filter(|w| !w.minimized)
+        .filter_map(|w| {
+            let rect = w.rect?;
+            Some((TiledWindow { pid, window_id: w.window_id }, get_display_index_for_window(rect)))
+        })
+        .collect();
+    let focused = get_focused_window_info(pid).ok()
+        .map(|info| TiledWindow { pid, window_id: info.window_id });
+
+    let mut changed: Vec<usize> = Vec::new();
+    {
+        let mut engine = ENGINE.lock().unwrap();
+
+        // Removed: managed before, gone (or minimized) now
+        let managed: Vec<TiledWindow> = engine.managed_windows().into_iter()
+            .filter(|(w, _)| w.pid == pid)
+            .map(|(w, _)| w)
+            .collect();
+        for window in managed {
+            if !current.iter().any(|(w, _)| *w == window) {
+                changed.extend(engine.handle_event(TilingEvent::Removed { window }));
+            }
+        }
+
+        // Focus first, so new windows are placed next to the focused one
+        if let Some(window) = focused {
+            engine.handle_event(TilingEvent::Focused { window });
+        }
+
+        // Added (or moved between displays)
+        for (window, display) in current {
+            changed.extend(engine.handle_event(TilingEvent::Added { window, display }));
+        }
+    }
+
+    changed.sort();
+    changed.dedup();
+    for display in changed {
+        apply_layout(display);
+    }
+}
+
+/// A window of `pid` was destroyed or minimized: drop it and reflow its display
+/// Destroyed fires for any UI element of the app, so apps without tiled windows return early
+pub unsafe fn window_closed(pid: u32) {
+    let manages_app = ENGINE.lock().unwrap().managed_windows().iter().any(|(w, _)| w.pid == pid);
+    if manages_app {
+        reconcile_app(pid);
+    }
+}
+
+/// Forget all windows of a terminated app and reflow their displays
+pub fn app_terminated(pid: u32) {
+    let mut changed: Vec<usize> = Vec::new();
+    {
+        let mut engine = ENGINE.lock().unwrap();
+        let managed: Vec<TiledWindow> = engine.managed_windows().into_iter()
+            .filter(|(w, _)| w.pid == pid)
+            .map(|(w, _)| w)
+            .collect();
+        for window in managed {
+            changed.extend(engine.handle_event(TilingEvent::Removed { window }));
+        }
+    }
+
+    changed.sort();
+    changed.dedup();
+    for display in changed {
+        unsafe { apply_layout(display); }
+    }
+}
+
+/// Place every managed window on a display according to the engine's arrangement
+unsafe fn apply_layout(display: usize) {
+    let placements = loop {
+        let arrangement = ENGINE.lock().unwrap().layout(display);
+
+        // Windows closed since the event leave the arrangement before anything moves,
+        // so the remaining windows fill their slots instead of leaving a hole
+        let mut placements = Vec::new();
+        let mut vanished = Vec::new();
+        for (window, frac) in arrangement {
+            match window_element_by_id(window.pid, window.window_id) {
+                Some(win) => placements.push((window, win, frac)),
+                None => vanished.push(window),
+            }
+        }
+        if vanished.is_empty() {
+            break placements;
+        }
+
+        let mut engine = ENGINE.lock().unwrap();
+        for window in vanished {
+            eprintln!("AUTOTILE: window_id={} closed, removed", window.window_id);
+            engine.handle_event(TilingEvent::Removed { window });
+        }
+    };
+    eprintln!("AUTOTILE: display={} reflow windows={}", display, placements.len());
+
+    for (window, win, frac) in placements {
+        let rect = match realize_frac_on_display(display, &frac) {
+            Some(r) => r,
+            None => {
+                eprintln!("AUTOTILE: display={} | FAILED reason=no_visible_frame", display);
+                return;
+            }
+        };
+        if let Err(reason) = set_window_rect_safe(&win, rect) {
+            eprintln!("AUTOTILE: window_id={} | FAILED reason={}", window.window_id, reason);
+        }
+    }
+}
@@ -3,7 +3,6 @@
 
 #![cfg(target_os = "macos")]
 
-use std::sync::{Arc, Mutex};
 use std::ffi::c_void;
 use core_foundation_sys::base::CFTypeRef;
 use crate::pbmsm_mru::{get_mru_snapshot, MruWindowEntry, ActivationState};
@@ -16,28 +15,6 @@ use crate::pbmbo_overlay::{
     pbmbo_hide_alt_tab_overlay,
 };
 
-// Alt-Tab session state
-lazy_static::lazy_static! {
-    pub static ref ALT_TAB_SESSION: Arc<Mutex<AltTabSession>> = Arc::new(Mutex::new(AltTabSession::default()));
-}
-
-#[derive(Debug, Clone)]
-pub struct AltTabSession {
-    pub active: bool,           // Is Alt currently held?
-    pub popup_shown: bool,      // Has popup been displayed this session?
-    pub highlight_index: Option<usize>,  // Current highlight position (None = not navigating)
-}
-
-impl Default for AltTabSession {
-    fn default() -> Self {
-        Self {
-            active: false,
-            popup_shown: false,
-            highlight_index: None,
-        }
-    }
-}
-
 /// Helper to show Alt-Tab overlay with MRU entries
 /// Defers to main runloop for thread safety
 pub unsafe fn show_alt_tab_overlay(highlight_index: usize) {
@@ -128,9 +105,9 @@ pub unsafe fn update_alt_tab_highlight(highlight_index: usize) {
     crate::pbmba_ax::CFRunLoopWakeUp(main_runloop);
 }
 
-/// Helper to hide Alt-Tab overlay and reset session state
+/// Helper to hide Alt-Tab overlay (session state belongs to the input pipeline)
 /// Defers to main runloop for thread safety
-pub unsafe fn hide_alt_tab_overlay_and_cleanup() {
+pub unsafe fn hide_alt_tab_overlay() {
     use block2::StackBlock;
 
     // Create block that calls Swift function
@@ -146,38 +123,6 @@ pub unsafe fn hide_alt_tab_overlay_and_cleanup() {
         &*block as *const _ as *const c_void,
     );
     crate::pbmba_ax::CFRunLoopWakeUp(main_runloop);
-
-    // Reset session state (can do this immediately)
-    let mut session = ALT_TAB_SESSION.lock().unwrap();
-    *session = AltTabSession::default();
-
-    println!("SWITCHER: cleanup | overlays hidden, state reset");
-}
-
-/// Cancel Alt-Tab session without performing focus switch
-/// Called when mouse click occurs during active session
-pub unsafe fn cancel_alt_tab_session() {
-    use block2::StackBlock;
-
-    // Create block that calls Swift function to hide overlay
-    let block = StackBlock::new(|| {
-        pbmbo_hide_alt_tab_overlay();
-    });
-
-    // Schedule on main runloop
-    let main_runloop = crate::pbmba_ax::CFRunLoopGetMain();
-    crate::pbmba_ax::CFRunLoopPerformBlock(
-        main_runloop,
-        core_foundation::runloop::kCFRunLoopDefaultMode as CFTypeRef,
-        &*block as *const _ as *const c_void,
-    );
-    crate::pbmba_ax::CFRunLoopWakeUp(main_runloop);
-
-    // Reset session state (can do this immediately)
-    let mut session = ALT_TAB_SESSION.lock().unwrap();
-    *session = AltTabSession::default();
-
-    println!("SWITCHER: cancelled | reason=mouse_click");
 }
 
 /// Defer Alt-Tab switch commit to main runloop for thread safety
@@ -0,0 +1,145 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Scale Invariant
+
+#![cfg(target_os = "macos")]
+
+//! Directional Focus Navigation
+//! Focuses the nearest window left/right/above/below the focused one, or the
+//! previously focused (MRU) window.
+//!
+//! Candidates come from the MRU stack (which apps to look at, and recency rank)
+//! plus per-app AX enumeration (current frames). Scoring is platform-neutral
+//! (see pbgg_geometry::window_in_direction); activation reuses the switcher commit.
+
+use crate::pbgft_types::{Direction, PixelRect};
+use crate::pbgg_geometry::window_in_direction;
+use crate::pbmba_ax::FrontmostInfo;
+use crate::pbmp_pane::{enumerate_app_windows, get_focused_window_by_pid};
+use crate::pbmsa_alttab::commit_alt_tab_switch;
+use crate::pbmsm_mru::{get_mru_snapshot, ActivationState, MruWindowEntry, WindowIdentity};
+
+/// Gather other windows as scoring candidates
+/// Returns (rect, mru_rank) pairs plus the matching entries used to focus them.
+/// Apps are searched in MRU order (frontmost first even if MRU has not caught up);
+/// minimized windows, windows without a frame, and the excluded window are skipped.
+/// Windows not in the MRU stack get rank = MRU length (after every ranked window).
+pub unsafe fn gather_window_candidates(
+    frontmost: &FrontmostInfo,
+    exclude_window_id: u32,
+) -> (Vec<(PixelRect, usize)>, Vec<MruWindowEntry>) {
+    let mru = get_mru_snapshot();
+    let mut apps: Vec<(u32, String)> = vec![(frontmost.pid, frontmost.bundle_id.clone())];
+    for entry in &mru {
+        if !apps.iter().any(|(pid, _)| *pid == entry.identity.pid) {
+            apps.push((entry.identity.pid, entry.bundle_id.clone()));
+        }
+    }
+
+    let mut candidates: Vec<(PixelRect, usize)> = Vec::new();
+    let mut entries: Vec<MruWindowEntry> = Vec::new();
+
+    for (pid, bundle_id) in &apps {
+        for w in enumerate_app_windows(*pid) {
+            if w.minimized || (*pid == frontmost.pid && w.window_id == exclude_window_id) {
+                continue;
+            }
+            let rect = match w.rect {
+                Some(r) if r.w > 0.0 && r.h > 0.0 => r,
+                _ => continue,
+            };
+
+            let mru_rank = mru.iter()
+                .position(|e| e.identity.pid == *pid && e.identity.window_id == w.window_id)
+                .unwrap_or(mru.len());
+
+            candidates.push((PixelRect { x: rect.x, y: rect.y, width: rect.w, height: rect.h }, mru_rank));
+            entries.push(MruWindowEntry {
+                identity: WindowIdentity { pid: *pid, window_id: w.window_id },
+                bundle_id: bundle_id.clone(),
+                title: w.title,
+                activation_state: ActivationState::Known,
+            });
+        }
+    }
+
+    (candidates, entries)
+}
+
+/// Focus the nearest window in the given direction
+/// Returns true if focus moved, false if no candidate or focus failed
+pub unsafe fn focus_in_direction(direction: Direction, frontmost: &FrontmostInfo) -> bool {
+    let dir = direction.as_str();
+
+    // Source window: the focused window of the frontmost app
+    let win = match get_focused_window_by_pid(frontmost.pid) {
+        Ok(w) => w,
+        Err(reason) => {
+            eprintln!("FOCUS: direction={} | FAILED reason={}", dir, reason);
+            return false;
+        }
+    };
+    let from = match win.get_current_rect() {
+        Some(r) => PixelRect { x: r.x, y: r.y, width: r.w, height: r.h },
+        None => {
+            eprintln!("FOCUS: direction={} | FAILED reason=cannot_get_current_rect", dir);
+            return false;
+        }
+    };
+    let from_window_id = win.get_window_id().unwrap_or(0);
+    drop(win);
+
+    let (candidates, entries) = gather_window_candidates(frontmost, from_window_id);
+
+    let target = match window_in_direction(&from, &candidates, direction) {
+        Some(idx) => &entries[idx],
+        None => {
+            eprintln!("FOCUS: direction={} | no window in that direction ({} candidates)", dir, candidates.len());
+            return false;
+        }
+    };
+
+    if commit_alt_tab_switch(target) {
+        println!("FOCUS: direction={} | SUCCESS app=\"{}\" win=\"{}\"", dir, target.bundle_id, target.title);
+        true
+    } else {
+        eprintln!("FOCUS: direction={} | FAILED reason=focus_failed app=\"{}\"", dir, target.bundle_id);
+        false
+    }
+}
+
+/// Index of the most recent candidate actually present in the MRU stack
+/// (candidates outside the stack carry rank = MRU length and never qualify)
+pub fn most_recent_candidate(candidates: &[(PixelRect, usize)]) -> Option<usize> {
+    let ranked = get_mru_snapshot().len();
+    candidates.iter().enumerate()
+        .filter(|(_, (_, rank))| *rank < ranked)
+        .min_by_key(|(_, (_, rank))| *rank)
+        .map(|(idx, _)| idx)
+}
+
+/// Focus the previously focused window (MRU order, excluding the focused one)
+/// Returns true if focus moved, false if no candidate or focus failed
+pub unsafe fn focus_mru(frontmost: &FrontmostInfo) -> bool {
+    let from_window_id = match get_focused_window_by_pid(frontmost.pid) {
+        Ok(w) => w.get_window_id().unwrap_or(0),
+        Err(_) => 0,  // App without a focused window: any MRU window qualifies
+    };
+
+    let (candidates, entries) = gather_window_candidates(frontmost, from_window_id);
+
+    let target = match most_recent_candidate(&candidates) {
+        Some(idx) => &entries[idx],
+        None => {
+            eprintln!("FOCUS: target=mru | no previous window ({} candidates)", candidates.len());
+            return false;
+        }
+    };
+
+    if commit_alt_tab_switch(target) {
+        println!("FOCUS: target=mru | SUCCESS app=\"{}\" win=\"{}\"", target.bundle_id, target.title);
+        true
+    } else {
+        eprintln!("FOCUS: target=mru | FAILED reason=focus_failed app=\"{}\"", target.bundle_id);
+        false
+    }
+}
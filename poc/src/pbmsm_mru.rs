@@ -135,14 +135,15 @@ pub unsafe fn add_app_to_mru_as_guess(pid: u32, bundle_id: String, name: String)
 /// Validates each (pid, window_id) pair and removes entries that no longer exist
 /// Returns count of pruned entries
 pub unsafe fn prune_stale_mru_entries() -> usize {
-    use crate::pbmba_ax::validate_window_exists;
+    use crate::pbgw_window::WindowBackend;
+    use crate::pbmbw_window::AxBackend;
 
     let mut stack = MRU_STACK.lock().unwrap();
     let initial_count = stack.len();
 
     // Retain only entries that still correspond to live windows
     stack.retain(|entry| {
-        validate_window_exists(entry.identity.pid, entry.identity.window_id)
+        AxBackend.window_exists(entry.identity.pid, entry.identity.window_id)
     });
 
     let pruned_count = initial_count - stack.len();